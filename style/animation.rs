@@ -10,29 +10,33 @@
 use crate::context::{CascadeInputs, SharedStyleContext};
 use crate::dom::{OpaqueNode, TDocument, TElement, TNode};
 use crate::properties::animated_properties::{AnimationValue, AnimationValueMap};
+use crate::properties::longhands::animation_composition::computed_value::single_value::T as AnimationComposition;
 use crate::properties::longhands::animation_direction::computed_value::single_value::T as AnimationDirection;
 use crate::properties::longhands::animation_fill_mode::computed_value::single_value::T as AnimationFillMode;
 use crate::properties::longhands::animation_play_state::computed_value::single_value::T as AnimationPlayState;
 use crate::properties::AnimationDeclarations;
 use crate::properties::{
-    ComputedValues, Importance, LonghandId, PropertyDeclarationBlock, PropertyDeclarationId,
-    PropertyDeclarationIdSet,
+    ComputedValues, Importance, LonghandId, OwnedPropertyDeclarationId, PropertyDeclarationBlock,
+    PropertyDeclarationId, PropertyDeclarationIdSet,
 };
 use crate::rule_tree::CascadeLevel;
 use crate::selector_parser::PseudoElement;
 use crate::shared_lock::{Locked, SharedRwLock};
+use crate::properties_and_values::value::CustomAnimatedValue;
 use crate::style_resolver::StyleResolverForElement;
+use crate::stylist::Stylist;
 use crate::stylesheets::keyframes_rule::{KeyframesAnimation, KeyframesStep, KeyframesStepValue};
 use crate::stylesheets::layer_rule::LayerOrder;
 use crate::values::animated::{Animate, Procedure};
-use crate::values::computed::{Time, TimingFunction};
-use crate::values::generics::easing::BeforeFlag;
-use crate::values::specified::TransitionBehavior;
+use crate::values::computed::{AnimationRangeValue, Length, Time, TimingFunction};
+use crate::values::generics::easing::{BeforeFlag, TimingKeyword};
+use crate::values::specified::{TimelineRangeName, TransitionBehavior};
 use crate::Atom;
 use fxhash::FxHashMap;
 use parking_lot::RwLock;
 use servo_arc::Arc;
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Represents an animation for a given property.
 #[derive(Clone, Debug, MallocSizeOf)]
@@ -91,7 +95,7 @@ impl PropertyAnimation {
         // by treating as if the flag is unset at all times.
         // https://drafts.csswg.org/css-easing/#step-timing-function-algo
         self.timing_function
-            .calculate_output(progress, BeforeFlag::Unset, epsilon)
+            .calculate_output_with_epsilon(progress, BeforeFlag::Unset, epsilon)
     }
 
     /// Update the given animation at a given point of progress.
@@ -147,6 +151,107 @@ pub enum KeyframesIterationState {
     Finite(f64, f64),
 }
 
+/// The geometry of a view progress timeline, used to place keyframes and
+/// animation ranges that refer to its named timeline ranges. Positions along
+/// the timeline are distances in CSS pixels from the start of its `cover`
+/// range.
+///
+/// https://drafts.csswg.org/scroll-animations-1/#view-timelines-ranges
+#[derive(Clone, Copy, Debug, MallocSizeOf, PartialEq)]
+pub struct TimelineRanges {
+    /// The size of the subject's border box along the timeline's axis.
+    pub subject_size: f32,
+    /// The size of the scrollport along the timeline's axis, after applying
+    /// `view-timeline-inset`.
+    pub scrollport_size: f32,
+}
+
+impl TimelineRanges {
+    /// Create a new `TimelineRanges` for the given subject and scrollport sizes.
+    pub fn new(subject_size: f32, scrollport_size: f32) -> Self {
+        Self {
+            subject_size,
+            scrollport_size,
+        }
+    }
+
+    /// Returns the start and end positions of the given named range.
+    pub fn range(&self, name: TimelineRangeName) -> (f32, f32) {
+        let subject = self.subject_size;
+        let scrollport = self.scrollport_size;
+        let cover_end = subject + scrollport;
+        match name {
+            TimelineRangeName::Cover => (0., cover_end),
+            TimelineRangeName::Contain => (subject.min(scrollport), subject.max(scrollport)),
+            TimelineRangeName::Entry => (0., subject.min(scrollport)),
+            TimelineRangeName::Exit => (subject.max(scrollport), cover_end),
+            TimelineRangeName::EntryCrossing => (0., subject),
+            TimelineRangeName::ExitCrossing => (scrollport, cover_end),
+        }
+    }
+
+    /// Resolves an `animation-range-start` value, or an `animation-range-end`
+    /// value if `is_end` is true, into a position along the timeline.
+    pub fn resolve(&self, value: &AnimationRangeValue, is_end: bool) -> f32 {
+        let (cover_start, cover_end) = self.range(TimelineRangeName::Cover);
+        match *value {
+            AnimationRangeValue::Normal if is_end => cover_end,
+            AnimationRangeValue::Normal => cover_start,
+            AnimationRangeValue::LengthPercentage(ref offset) => {
+                cover_start + offset.resolve(Length::new(cover_end - cover_start)).px()
+            },
+            AnimationRangeValue::Named(name, ref offset) => {
+                let (start, end) = self.range(name);
+                match *offset {
+                    Some(ref offset) => start + offset.resolve(Length::new(end - start)).px(),
+                    None if is_end => end,
+                    None => start,
+                }
+            },
+        }
+    }
+
+    /// Returns the active range of an animation attached to this timeline, given
+    /// its `animation-range-start` and `animation-range-end` values.
+    fn active_range(
+        &self,
+        range_start: &AnimationRangeValue,
+        range_end: &AnimationRangeValue,
+    ) -> ActiveTimelineRange {
+        ActiveTimelineRange {
+            ranges: *self,
+            start: self.resolve(range_start, false),
+            end: self.resolve(range_end, true),
+        }
+    }
+}
+
+/// The portion of a view progress timeline that an animation's iterations are
+/// mapped onto.
+struct ActiveTimelineRange {
+    ranges: TimelineRanges,
+    start: f32,
+    end: f32,
+}
+
+impl ActiveTimelineRange {
+    /// Converts a keyframe offset into a named timeline range into a
+    /// percentage of the animation. Returns `None` if the keyframe falls
+    /// outside of the active range, in which case it's ignored.
+    fn keyframe_percentage(&self, name: TimelineRangeName, percentage: f32) -> Option<f32> {
+        if self.end <= self.start {
+            return None;
+        }
+        let (range_start, range_end) = self.ranges.range(name);
+        let position = range_start + percentage * (range_end - range_start);
+        let percentage = (position - self.start) / (self.end - self.start);
+        if !(0. ..=1.).contains(&percentage) {
+            return None;
+        }
+        Some(percentage)
+    }
+}
+
 /// A temporary data structure used when calculating ComputedKeyframes for an
 /// animation. This data structure is used to collapse information for steps
 /// which may be spread across multiple keyframe declarations into a single
@@ -154,6 +259,7 @@ pub enum KeyframesIterationState {
 struct IntermediateComputedKeyframe {
     declarations: PropertyDeclarationBlock,
     timing_function: Option<TimingFunction>,
+    composition: Option<AnimationComposition>,
     start_percentage: f32,
 }
 
@@ -162,21 +268,42 @@ impl IntermediateComputedKeyframe {
         IntermediateComputedKeyframe {
             declarations: PropertyDeclarationBlock::new(),
             timing_function: None,
+            composition: None,
             start_percentage,
         }
     }
 
     /// Walk through all keyframe declarations and combine all declarations with the
     /// same `start_percentage` into individual `IntermediateComputedKeyframe`s.
+    ///
+    /// Keyframes attached to a named timeline range are only used when the
+    /// animation has an `active_range`, and are placed after the other keyframes
+    /// with the same resolved percentage.
     fn generate_for_keyframes(
         animation: &KeyframesAnimation,
         context: &SharedStyleContext,
         base_style: &ComputedValues,
+        active_range: Option<&ActiveTimelineRange>,
     ) -> Vec<Self> {
-        let mut intermediate_steps: Vec<Self> = Vec::with_capacity(animation.steps.len());
+        let mut steps: Vec<(f32, &KeyframesStep)> = animation
+            .steps
+            .iter()
+            .map(|step| (step.start_percentage.0, step))
+            .collect();
+        if let Some(active_range) = active_range {
+            for step in animation.timeline_range_steps.iter() {
+                let name = step.range_name.expect("Should have a timeline range name");
+                let percentage = active_range.keyframe_percentage(name, step.start_percentage.0);
+                if let Some(percentage) = percentage {
+                    steps.push((percentage, step));
+                }
+            }
+            steps.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+
+        let mut intermediate_steps: Vec<Self> = Vec::with_capacity(steps.len());
         let mut current_step = IntermediateComputedKeyframe::new(0.);
-        for step in animation.steps.iter() {
-            let start_percentage = step.start_percentage.0;
+        for (start_percentage, step) in steps {
             if start_percentage != current_step.start_percentage {
                 let new_step = IntermediateComputedKeyframe::new(start_percentage);
                 intermediate_steps.push(std::mem::replace(&mut current_step, new_step));
@@ -206,21 +333,22 @@ impl IntermediateComputedKeyframe {
         if let Some(timing_function) = step.get_animation_timing_function(&guard) {
             self.timing_function = Some(timing_function.to_computed_value_without_context());
         }
+        if let Some(composition) = step.get_animation_composition(guard) {
+            self.composition = Some(composition);
+        }
 
         let block = match step.value {
             KeyframesStepValue::ComputedValues => return,
             KeyframesStepValue::Declarations { ref block } => block,
         };
 
-        // Filter out !important, non-animatable properties, and the
-        // 'display' property (which is only animatable from SMIL).
-        let guard = block.read_with(&guard);
-        for declaration in guard.normal_declaration_iter() {
-            if let PropertyDeclarationId::Longhand(id) = declaration.id() {
-                if id == LonghandId::Display {
-                    continue;
-                }
+        self.push_declarations(block.read_with(guard), base_style);
+    }
 
+    fn push_declarations(&mut self, block: &PropertyDeclarationBlock, base_style: &ComputedValues) {
+        // Filter out !important and non-animatable properties.
+        for declaration in block.normal_declaration_iter() {
+            if let PropertyDeclarationId::Longhand(id) = declaration.id() {
                 if !id.is_animatable() {
                     continue;
                 }
@@ -289,20 +417,63 @@ struct ComputedKeyframe {
     /// The animation values to transition to and from when processing this
     /// keyframe animation step.
     values: Box<[AnimationValue]>,
+
+    /// How each value is composited onto the underlying value. `None` means
+    /// that the value wasn't specified in the first or last keyframe, so the
+    /// underlying value is used as-is.
+    compositions: Box<[Option<AnimationComposition>]>,
+}
+
+/// Get the underlying value of an animating property from the base style.
+/// Custom properties can be absent from it, in which case they fall back to
+/// their registered initial value, or to the guaranteed-invalid value, which
+/// animates discretely.
+fn underlying_value(
+    property: PropertyDeclarationId,
+    base_style: &ComputedValues,
+    stylist: &Stylist,
+) -> AnimationValue {
+    if let Some(value) = AnimationValue::from_computed_values(property, base_style) {
+        return value;
+    }
+    let name = match property {
+        PropertyDeclarationId::Custom(name) => name,
+        PropertyDeclarationId::Longhand(..) => unreachable!("Unexpected non-animatable property."),
+    };
+    let registration = stylist.get_custom_property_registration(name);
+    AnimationValue::Custom(
+        match stylist.get_custom_property_initial_values().get(registration, name) {
+            Some(value) => CustomAnimatedValue::from_computed(name, value),
+            None => CustomAnimatedValue::guaranteed_invalid(name),
+        },
+    )
 }
 
 impl ComputedKeyframe {
+    /// Generate the keyframes of the animation at `index` in the animation
+    /// properties of `base_style`.
     fn generate_for_keyframes<E>(
         element: E,
         animation: &KeyframesAnimation,
         context: &SharedStyleContext,
         base_style: &Arc<ComputedValues>,
-        default_timing_function: TimingFunction,
+        index: usize,
+        timeline_ranges: Option<TimelineRanges>,
         resolver: &mut StyleResolverForElement<E>,
     ) -> Box<[Self]>
     where
         E: TElement,
     {
+        let ui_style = base_style.get_ui();
+        let default_timing_function = ui_style.animation_timing_function_mod(index);
+        let default_composition = ui_style.animation_composition_mod(index);
+        let active_range = timeline_ranges.map(|ranges| {
+            ranges.active_range(
+                &ui_style.animation_range_start_mod(index),
+                &ui_style.animation_range_end_mod(index),
+            )
+        });
+
         let mut animating_properties = PropertyDeclarationIdSet::default();
         for property in animation.properties_changed.iter() {
             debug_assert!(property.is_animatable());
@@ -311,59 +482,68 @@ impl ComputedKeyframe {
 
         let animation_values_from_style: Vec<AnimationValue> = animating_properties
             .iter()
-            .map(|property| {
-                AnimationValue::from_computed_values(property, &**base_style)
-                    .expect("Unexpected non-animatable property.")
-            })
+            .map(|property| underlying_value(property, base_style, context.stylist))
             .collect();
 
         let intermediate_steps =
-            IntermediateComputedKeyframe::generate_for_keyframes(animation, context, base_style);
+            IntermediateComputedKeyframe::generate_for_keyframes(
+                animation,
+                context,
+                base_style,
+                active_range.as_ref(),
+            );
 
         let mut computed_steps: Vec<Self> = Vec::with_capacity(intermediate_steps.len());
         for (step_index, step) in intermediate_steps.into_iter().enumerate() {
             let start_percentage = step.start_percentage;
             let properties_changed_in_step = step.declarations.property_ids().clone();
             let step_timing_function = step.timing_function.clone();
+            let step_composition = step.composition.unwrap_or(default_composition);
             let step_style = step.resolve_style(element, context, base_style, resolver);
             let timing_function =
                 step_timing_function.unwrap_or_else(|| default_timing_function.clone());
 
-            let values = {
-                // If a value is not set in a property declaration we use the value from
-                // the style for the first and last keyframe. For intermediate ones, we
-                // use the value from the previous keyframe.
-                //
-                // TODO(mrobinson): According to the spec, we should use an interpolated
-                // value for properties missing from keyframe declarations.
-                let default_values = if start_percentage == 0. || start_percentage == 1.0 {
-                    animation_values_from_style.as_slice()
-                } else {
-                    debug_assert!(step_index != 0);
-                    &computed_steps[step_index - 1].values
-                };
-
-                // For each property that is animating, pull the value from the resolved
-                // style for this step if it's in one of the declarations. Otherwise, we
-                // use the default value from the set we calculated above.
-                animating_properties
-                    .iter()
-                    .zip(default_values.iter())
-                    .map(|(property_declaration, default_value)| {
-                        if properties_changed_in_step.contains(property_declaration) {
-                            AnimationValue::from_computed_values(property_declaration, &step_style)
-                                .unwrap_or_else(|| default_value.clone())
-                        } else {
-                            default_value.clone()
-                        }
-                    })
-                    .collect()
-            };
+            // If a value is not set in a property declaration we use the underlying
+            // value for the first and last keyframe. For intermediate ones, we use the
+            // value from the previous keyframe.
+            //
+            // TODO(mrobinson): According to the spec, we should use an interpolated
+            // value for properties missing from keyframe declarations.
+            let is_endpoint = start_percentage == 0. || start_percentage == 1.0;
+            let (values, compositions): (Vec<_>, Vec<_>) = animating_properties
+                .iter()
+                .enumerate()
+                .map(|(index, property_declaration)| {
+                    let default = if is_endpoint {
+                        (animation_values_from_style[index].clone(), None)
+                    } else {
+                        debug_assert!(step_index != 0);
+                        let previous = &computed_steps[step_index - 1];
+                        (
+                            previous.values[index].clone(),
+                            previous.compositions[index],
+                        )
+                    };
+
+                    // For each property that is animating, pull the value from the
+                    // resolved style for this step if it's in one of the declarations.
+                    // Otherwise, we use the default value from above.
+                    if !properties_changed_in_step.contains(property_declaration) {
+                        return default;
+                    }
+                    match AnimationValue::from_computed_values(property_declaration, &step_style)
+                    {
+                        Some(value) => (value, Some(step_composition)),
+                        None => default,
+                    }
+                })
+                .unzip();
 
             computed_steps.push(ComputedKeyframe {
                 timing_function,
                 start_percentage,
-                values,
+                values: values.into_boxed_slice(),
+                compositions: compositions.into_boxed_slice(),
             });
         }
         computed_steps.into_boxed_slice()
@@ -456,8 +636,11 @@ impl Animation {
     fn iterate(&mut self) {
         debug_assert!(!self.on_last_iteration());
 
-        if let KeyframesIterationState::Finite(ref mut current, max) = self.iteration_state {
-            *current = (*current + 1.).min(max);
+        match self.iteration_state {
+            KeyframesIterationState::Finite(ref mut current, max) => {
+                *current = (*current + 1.).min(max);
+            },
+            KeyframesIterationState::Infinite(ref mut current) => *current += 1.,
         }
 
         if let AnimationState::Paused(ref mut progress) = self.state {
@@ -490,6 +673,34 @@ impl Animation {
         }
     }
 
+    /// The index of the current iteration of this animation.
+    pub fn current_iteration(&self) -> f64 {
+        match self.iteration_state {
+            KeyframesIterationState::Finite(current, _) => current,
+            KeyframesIterationState::Infinite(current) => current,
+        }
+    }
+
+    /// The duration of all the iterations of this animation, which is infinite if
+    /// the animation repeats forever.
+    pub fn active_duration(&self) -> f64 {
+        match self.iteration_state {
+            KeyframesIterationState::Finite(_, max) => max * self.duration,
+            KeyframesIterationState::Infinite(_) => f64::INFINITY,
+        }
+    }
+
+    /// The time elapsed in the active interval of this animation at the given time.
+    fn elapsed_time(&self, now: f64) -> f64 {
+        let iteration_progress = match self.state {
+            AnimationState::Paused(progress) => progress,
+            _ => (now - self.started_at) / self.duration,
+        };
+        ((self.current_iteration() + iteration_progress) * self.duration)
+            .min(self.active_duration())
+            .max(0.)
+    }
+
     /// The duration of the current iteration of this animation which may be less
     /// than the animation duration if it has a non-integral iteration count.
     pub fn current_iteration_duration(&self) -> f64 {
@@ -528,6 +739,117 @@ impl Animation {
         progress >= self.current_iteration_end_progress()
     }
 
+    /// Whether this animation can be sampled by a compositor, that is, if all the
+    /// properties it animates can be sampled off the main thread and none of its
+    /// keyframes are composited onto the underlying value.
+    pub fn can_run_on_compositor(&self) -> bool {
+        if self.state == AnimationState::Canceled ||
+            !self.properties_changed.iter().all(is_compositor_animatable)
+        {
+            return false;
+        }
+        self.computed_steps.iter().all(|step| {
+            step.compositions
+                .iter()
+                .all(|c| matches!(c, None | Some(AnimationComposition::Replace)))
+        })
+    }
+
+    /// Returns a description of this animation for a compositor, if it can run on
+    /// one.
+    pub fn compositor_animation(&self) -> Option<CompositorAnimation> {
+        if !self.can_run_on_compositor() {
+            return None;
+        }
+
+        let keyframes = self
+            .computed_steps
+            .iter()
+            .map(|step| CompositorKeyframe {
+                offset: step.start_percentage as f64,
+                timing_function: step.timing_function.clone(),
+                values: step.values.clone(),
+            })
+            .collect();
+        let iterations = match self.iteration_state {
+            KeyframesIterationState::Finite(_, max) => max,
+            KeyframesIterationState::Infinite(_) => f64::INFINITY,
+        };
+        let elapsed_iterations = self.current_iteration() * self.duration;
+        let paused_at = match self.state {
+            AnimationState::Paused(progress) => Some(elapsed_iterations + progress * self.duration),
+            _ => None,
+        };
+        Some(CompositorAnimation {
+            keyframes,
+            timing: EffectTiming {
+                fill_mode: self.fill_mode,
+                iterations,
+                duration: self.duration,
+                direction: self.direction,
+                ..Default::default()
+            },
+            start_time: self.started_at - elapsed_iterations,
+            paused_at,
+        })
+    }
+
+    /// Returns a copy of this animation in the state it would have at the given
+    /// time, leaving this animation untouched. Unlike ticking, this only depends on
+    /// the time and not on how far this animation was ticked already, so it works
+    /// for times in the past too.
+    fn advanced_to(&self, time: f64) -> Self {
+        let mut animation = self.clone();
+        if matches!(self.state, AnimationState::Paused(..) | AnimationState::Canceled) {
+            return animation;
+        }
+
+        // Each iteration moves the start time forward by the duration, see `iterate`.
+        let first_iteration_start = self.started_at - self.current_iteration() * self.duration;
+        if time < first_iteration_start {
+            animation.state = AnimationState::Pending;
+        } else {
+            animation.state = AnimationState::Running;
+        }
+
+        let last_iteration = match self.iteration_state {
+            KeyframesIterationState::Finite(_, max) => (max - 1.).ceil().max(0.),
+            KeyframesIterationState::Infinite(_) => f64::INFINITY,
+        };
+        // Like `iteration_over`, only move to the next iteration once the current one
+        // is over.
+        let iteration = if animation.state != AnimationState::Running {
+            0.
+        } else if self.duration > 0. {
+            ((time - first_iteration_start) / self.duration).ceil() - 1.
+        } else if time > first_iteration_start && last_iteration.is_finite() {
+            last_iteration
+        } else {
+            0.
+        };
+        let iteration = iteration.min(last_iteration).max(0.);
+
+        animation.iteration_state = match self.iteration_state {
+            KeyframesIterationState::Finite(_, max) => {
+                KeyframesIterationState::Finite(iteration, max)
+            },
+            KeyframesIterationState::Infinite(_) => KeyframesIterationState::Infinite(iteration),
+        };
+        animation.started_at = first_iteration_start + iteration * self.duration;
+        animation.current_direction = match self.direction {
+            AnimationDirection::Normal => AnimationDirection::Normal,
+            AnimationDirection::Reverse => AnimationDirection::Reverse,
+            AnimationDirection::Alternate if iteration % 2. == 0. => AnimationDirection::Normal,
+            AnimationDirection::AlternateReverse if iteration % 2. != 0. => {
+                AnimationDirection::Normal
+            },
+            AnimationDirection::Alternate | AnimationDirection::AlternateReverse => {
+                AnimationDirection::Reverse
+            },
+        };
+        animation
+    }
+
     /// Updates the appropiate state from other animation.
     ///
     /// This happens when an animation is re-submitted to layout, presumably
@@ -563,6 +885,10 @@ impl Animation {
             (
                 &mut KeyframesIterationState::Finite(ref mut iters, _),
                 KeyframesIterationState::Finite(old_iters, _),
+            ) |
+            (
+                &mut KeyframesIterationState::Infinite(ref mut iters),
+                KeyframesIterationState::Infinite(old_iters),
             ) => *iters = old_iters,
             _ => {},
         }
@@ -600,6 +926,26 @@ impl Animation {
         }
     }
 
+    /// Composites the value of the given keyframe onto the value below this
+    /// animation in the effect stack, or the base value if there is none.
+    fn composited_keyframe_value(
+        &self,
+        keyframe: &ComputedKeyframe,
+        index: usize,
+        map: &AnimationValueMap,
+    ) -> AnimationValue {
+        let value = &keyframe.values[index];
+        let composition = keyframe.compositions[index];
+        if composition == Some(AnimationComposition::Replace) {
+            return value.clone();
+        }
+        let underlying = map
+            .get(&value.id().to_owned())
+            .cloned()
+            .or_else(|| AnimationValue::from_computed_values(value.id(), &self.cascade_style));
+        composite_value(value, underlying.as_ref(), composition)
+    }
+
     /// Fill in an `AnimationValueMap` with values calculated from this animation at
     /// the given time value.
     fn get_property_declaration_at_time(&self, now: f64, map: &mut AnimationValueMap) {
@@ -676,17 +1022,20 @@ impl Animation {
 
         // If we only need to take into account one keyframe, then exit early
         // in order to avoid doing more work.
-        let mut add_declarations_to_map = |keyframe: &ComputedKeyframe| {
-            for value in keyframe.values.iter() {
-                map.insert(value.id().to_owned(), value.clone());
-            }
+        let single_keyframe = if total_progress <= 0.0 {
+            Some(prev_keyframe)
+        } else if total_progress >= 1.0 {
+            Some(next_keyframe)
+        } else {
+            None
         };
-        if total_progress <= 0.0 {
-            add_declarations_to_map(&prev_keyframe);
-            return;
-        }
-        if total_progress >= 1.0 {
-            add_declarations_to_map(&next_keyframe);
+        if let Some(keyframe) = single_keyframe {
+            let values: Vec<_> = (0..keyframe.values.len())
+                .map(|index| self.composited_keyframe_value(keyframe, index, map))
+                .collect();
+            for value in values {
+                map.insert(value.id().to_owned(), value);
+            }
             return;
         }
 
@@ -702,10 +1051,10 @@ impl Animation {
             direction_aware_prev_keyframe_start_percentage) /
             percentage_between_keyframes;
 
-        for (from, to) in prev_keyframe.values.iter().zip(next_keyframe.values.iter()) {
+        for index in 0..prev_keyframe.values.len() {
             let animation = PropertyAnimation {
-                from: from.clone(),
-                to: to.clone(),
+                from: self.composited_keyframe_value(prev_keyframe, index, map),
+                to: self.composited_keyframe_value(next_keyframe, index, map),
                 timing_function: prev_keyframe.timing_function.clone(),
                 duration: duration_between_keyframes as f64,
             };
@@ -732,6 +1081,31 @@ impl fmt::Debug for Animation {
     }
 }
 
+/// Composites a keyframe value onto the underlying value with the given
+/// operation, where `None` stands for a neutral keyframe, which takes the
+/// underlying value. Values that can't be added fall back to replacing.
+///
+/// <https://drafts.csswg.org/web-animations-1/#effect-composition>
+fn composite_value(
+    value: &AnimationValue,
+    underlying: Option<&AnimationValue>,
+    composition: Option<AnimationComposition>,
+) -> AnimationValue {
+    let underlying = match underlying {
+        Some(underlying) => underlying,
+        None => return value.clone(),
+    };
+    let procedure = match composition {
+        None => return underlying.clone(),
+        Some(AnimationComposition::Replace) => return value.clone(),
+        Some(AnimationComposition::Add) => Procedure::Add,
+        Some(AnimationComposition::Accumulate) => Procedure::Accumulate { count: 1 },
+    };
+    underlying
+        .animate(value, procedure)
+        .unwrap_or_else(|()| value.clone())
+}
+
 /// A CSS Transition
 #[derive(Clone, Debug, MallocSizeOf)]
 pub struct Transition {
@@ -839,6 +1213,40 @@ impl Transition {
         time >= self.start_time + (self.property_animation.duration)
     }
 
+    /// Whether this transition can be sampled by a compositor, that is, if its
+    /// property can be sampled off the main thread.
+    pub fn can_run_on_compositor(&self) -> bool {
+        self.state != AnimationState::Canceled &&
+            is_compositor_animatable(self.property_animation.property_id())
+    }
+
+    /// Returns a description of this transition for a compositor, if it can run on
+    /// one.
+    pub fn compositor_animation(&self) -> Option<CompositorAnimation> {
+        if !self.can_run_on_compositor() {
+            return None;
+        }
+        let property_animation = &self.property_animation;
+        let keyframe = |offset, value: &AnimationValue| CompositorKeyframe {
+            offset,
+            timing_function: property_animation.timing_function.clone(),
+            values: Box::new([value.clone()]),
+        };
+        Some(CompositorAnimation {
+            keyframes: Box::new([
+                keyframe(0., &property_animation.from),
+                keyframe(1., &property_animation.to),
+            ]),
+            timing: EffectTiming {
+                fill_mode: AnimationFillMode::Both,
+                duration: property_animation.duration,
+                ..Default::default()
+            },
+            start_time: self.start_time,
+            paused_at: None,
+        })
+    }
+
     /// Update the given animation at a given point of progress.
     pub fn calculate_value(&self, time: f64) -> AnimationValue {
         let progress = (time - self.start_time) / (self.property_animation.duration);
@@ -846,87 +1254,885 @@ impl Transition {
     }
 }
 
-/// Holds the animation state for a particular element.
-#[derive(Debug, Default, MallocSizeOf)]
-pub struct ElementAnimationSet {
-    /// The animations for this element.
-    pub animations: Vec<Animation>,
+/// The timing properties of a script-created animation effect, with times in seconds.
+///
+/// <https://drafts.csswg.org/web-animations-1/#the-effecttiming-dictionaries>
+#[derive(Clone, Debug, MallocSizeOf)]
+pub struct EffectTiming {
+    /// The delay before the start of the active interval.
+    pub delay: f64,
+    /// The delay after the end of the active interval.
+    pub end_delay: f64,
+    /// Whether the effect applies outside of its active interval.
+    pub fill_mode: AnimationFillMode,
+    /// The iteration index at which the effect starts, which may be fractional.
+    pub iteration_start: f64,
+    /// The number of iterations, which may be infinite.
+    pub iterations: f64,
+    /// The duration of a single iteration.
+    pub duration: f64,
+    /// The playback direction of the iterations.
+    pub direction: AnimationDirection,
+    /// The timing function applied to each iteration.
+    pub easing: TimingFunction,
+}
 
-    /// The transitions for this element.
-    pub transitions: Vec<Transition>,
+impl Default for EffectTiming {
+    fn default() -> Self {
+        EffectTiming {
+            delay: 0.,
+            end_delay: 0.,
+            fill_mode: AnimationFillMode::None,
+            iteration_start: 0.,
+            iterations: 1.,
+            duration: 0.,
+            direction: AnimationDirection::Normal,
+            easing: TimingFunction::Keyword(TimingKeyword::Linear),
+        }
+    }
+}
 
-    /// Whether or not this ElementAnimationSet has had animations or transitions
-    /// which have been added, removed, or had their state changed.
-    pub dirty: bool,
+/// The phase of an animation effect at a given local time.
+///
+/// <https://drafts.csswg.org/web-animations-1/#animation-effect-phases-and-states>
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EffectPhase {
+    Before,
+    Active,
+    After,
 }
 
-impl ElementAnimationSet {
-    /// Cancel all animations in this `ElementAnimationSet`. This is typically called
-    /// when the element has been removed from the DOM.
-    pub fn cancel_all_animations(&mut self) {
-        self.dirty = !self.animations.is_empty();
-        for animation in self.animations.iter_mut() {
-            animation.state = AnimationState::Canceled;
+impl EffectTiming {
+    /// Whether these timing properties are valid, that is, whether the iteration start is
+    /// finite and the iteration start, the number of iterations and the duration are
+    /// non-negative numbers.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#updating-animationeffect-timing>
+    pub fn is_valid(&self) -> bool {
+        self.iteration_start.is_finite() &&
+            self.iteration_start >= 0. &&
+            self.iterations >= 0. &&
+            self.duration >= 0.
+    }
+
+    /// The length of the active interval.
+    pub fn active_duration(&self) -> f64 {
+        if self.iterations == 0. || self.duration == 0. {
+            return 0.;
         }
-        self.cancel_active_transitions();
+        self.duration * self.iterations
     }
 
-    fn cancel_active_transitions(&mut self) {
-        for transition in self.transitions.iter_mut() {
-            if transition.state != AnimationState::Finished {
-                self.dirty = true;
-                transition.state = AnimationState::Canceled;
-            }
+    /// The end time of the effect, including both delays.
+    pub fn end_time(&self) -> f64 {
+        (self.delay + self.active_duration() + self.end_delay).max(0.)
+    }
+
+    fn phase(&self, local_time: f64, playback_rate: f64) -> EffectPhase {
+        let end_time = self.end_time();
+        let before_active_boundary = self.delay.min(end_time).max(0.);
+        let active_after_boundary = (self.delay + self.active_duration()).min(end_time).max(0.);
+        let backwards = playback_rate < 0.;
+        if local_time < before_active_boundary ||
+            (backwards && local_time == before_active_boundary)
+        {
+            return EffectPhase::Before;
+        }
+        if local_time > active_after_boundary ||
+            (!backwards && local_time == active_after_boundary)
+        {
+            return EffectPhase::After;
         }
+        EffectPhase::Active
     }
 
-    /// Apply all active animations.
-    pub fn apply_active_animations(
-        &self,
-        context: &SharedStyleContext,
-        style: &mut Arc<ComputedValues>,
-    ) {
-        let now = context.current_time_for_animations;
-        let mutable_style = Arc::make_mut(style);
-        if let Some(map) = self.get_value_map_for_active_animations(now) {
-            for value in map.values() {
-                value.set_in_style_for_servo(mutable_style);
+    /// Returns the progress into the current iteration at the given local time, after applying
+    /// the direction and the timing function, or `None` if the effect doesn't apply at that time.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#core-animation-effect-calculations>
+    pub fn transformed_progress(&self, local_time: f64, playback_rate: f64) -> Option<f64> {
+        let phase = self.phase(local_time, playback_rate);
+        let active_duration = self.active_duration();
+        let fills_backwards =
+            matches!(self.fill_mode, AnimationFillMode::Backwards | AnimationFillMode::Both);
+        let fills_forwards =
+            matches!(self.fill_mode, AnimationFillMode::Forwards | AnimationFillMode::Both);
+        let active_time = match phase {
+            EffectPhase::Before if fills_backwards => (local_time - self.delay).max(0.),
+            EffectPhase::Active => local_time - self.delay,
+            EffectPhase::After if fills_forwards => {
+                (local_time - self.delay).min(active_duration).max(0.)
+            },
+            _ => return None,
+        };
+
+        let overall_progress = if self.duration == 0. {
+            match phase {
+                EffectPhase::Before => self.iteration_start,
+                _ => self.iteration_start + self.iterations,
             }
+        } else {
+            self.iteration_start + active_time / self.duration
+        };
+
+        let mut simple_iteration_progress = if overall_progress.is_infinite() {
+            self.iteration_start % 1.
+        } else {
+            overall_progress % 1.
+        };
+        if simple_iteration_progress == 0. &&
+            phase != EffectPhase::Before &&
+            active_time == active_duration &&
+            self.iterations != 0.
+        {
+            simple_iteration_progress = 1.;
         }
 
-        if let Some(map) = self.get_value_map_for_active_transitions(now) {
-            for value in map.values() {
-                value.set_in_style_for_servo(mutable_style);
+        let current_iteration = if phase == EffectPhase::After && self.iterations.is_infinite() {
+            f64::INFINITY
+        } else if simple_iteration_progress == 1. {
+            overall_progress.floor() - 1.
+        } else {
+            overall_progress.floor()
+        };
+
+        let forwards = match self.direction {
+            AnimationDirection::Normal => true,
+            AnimationDirection::Reverse => false,
+            AnimationDirection::Alternate => {
+                current_iteration.is_infinite() || current_iteration % 2. == 0.
+            },
+            AnimationDirection::AlternateReverse => {
+                !current_iteration.is_infinite() && current_iteration % 2. != 0.
+            },
+        };
+        let directed_progress = if forwards {
+            simple_iteration_progress
+        } else {
+            1. - simple_iteration_progress
+        };
+
+        let before_flag = if (phase == EffectPhase::Before && forwards) ||
+            (phase == EffectPhase::After && !forwards)
+        {
+            BeforeFlag::Set
+        } else {
+            BeforeFlag::Unset
+        };
+        let epsilon = 1. / (200. * self.duration.max(1.));
+        Some(
+            self.easing
+                .calculate_output_with_epsilon(directed_progress, before_flag, epsilon),
+        )
+    }
+}
+
+/// A keyframe of a script-created animation, as passed to `element.animate()`.
+#[derive(Clone, Debug, MallocSizeOf)]
+pub struct ScriptKeyframe {
+    /// The offset of this keyframe, if specified.
+    pub offset: Option<f64>,
+    /// The timing function used from this keyframe to the next one.
+    pub easing: TimingFunction,
+    /// The composite operation of this keyframe, or `None` to use the one of the effect.
+    pub composite: Option<AnimationComposition>,
+    /// The declarations of this keyframe, which are computed against the style of the target.
+    #[ignore_malloc_size_of = "Arc"]
+    pub declarations: Arc<Locked<PropertyDeclarationBlock>>,
+}
+
+/// A keyframe of a `KeyframeEffect`, resolved against the style of the target.
+#[derive(Clone, Debug, MallocSizeOf)]
+struct ComputedEffectKeyframe {
+    offset: f64,
+    easing: TimingFunction,
+    composite: Option<AnimationComposition>,
+    /// One value for each animating property, or `None` for properties this keyframe doesn't
+    /// specify.
+    values: Box<[Option<AnimationValue>]>,
+}
+
+/// The keyframes of a `KeyframeEffect`, resolved against the style of the target.
+#[derive(Clone, Debug, MallocSizeOf)]
+struct ComputedKeyframeEffect {
+    /// The properties that are animated by any keyframe.
+    properties: PropertyDeclarationIdSet,
+    /// The values of the animated properties in the base style, used for keyframes which are
+    /// implicitly at offset 0 or 1.
+    underlying_values: Box<[AnimationValue]>,
+    keyframes: Box<[ComputedEffectKeyframe]>,
+}
+
+/// A keyframe effect built from script keyframes.
+///
+/// <https://drafts.csswg.org/web-animations-1/#keyframe-effects>
+#[derive(Clone, Debug, MallocSizeOf)]
+pub struct KeyframeEffect {
+    /// The keyframes, in the order they were specified.
+    keyframes: Box<[ScriptKeyframe]>,
+    /// The computed offset of each keyframe.
+    offsets: Box<[f64]>,
+    /// The timing properties of this effect.
+    pub timing: EffectTiming,
+    /// The composite operation of the keyframes which don't specify one.
+    pub composite: AnimationComposition,
+    /// The keyframes resolved against the style of the target, or `None` if they haven't been
+    /// resolved yet.
+    computed: Option<ComputedKeyframeEffect>,
+}
+
+impl KeyframeEffect {
+    /// Creates a new `KeyframeEffect`, filling in the missing keyframe offsets. Fails if the
+    /// specified offsets are out of the [0, 1] range or not sorted, or if the timing properties
+    /// are invalid.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#compute-missing-keyframe-offsets>
+    pub fn new(
+        keyframes: Vec<ScriptKeyframe>,
+        timing: EffectTiming,
+    ) -> Result<Self, ScriptAnimationError> {
+        if !timing.is_valid() {
+            return Err(ScriptAnimationError::TypeError);
+        }
+
+        let mut previous_offset = 0.;
+        for offset in keyframes.iter().filter_map(|keyframe| keyframe.offset) {
+            if !(0. ..=1.).contains(&offset) || offset < previous_offset {
+                return Err(ScriptAnimationError::TypeError);
             }
+            previous_offset = offset;
         }
-    }
 
-    /// Clear all canceled animations and transitions from this `ElementAnimationSet`.
-    pub fn clear_canceled_animations(&mut self) {
-        self.animations
-            .retain(|animation| animation.state != AnimationState::Canceled);
-        self.transitions
-            .retain(|animation| animation.state != AnimationState::Canceled);
-    }
+        let mut offsets: Vec<Option<f64>> = keyframes.iter().map(|k| k.offset).collect();
+        if offsets.len() > 1 && offsets[0].is_none() {
+            offsets[0] = Some(0.);
+        }
+        if let Some(last @ None) = offsets.last_mut() {
+            *last = Some(1.);
+        }
 
-    /// Whether this `ElementAnimationSet` is empty, which means it doesn't
-    /// hold any animations in any state.
-    pub fn is_empty(&self) -> bool {
-        self.animations.is_empty() && self.transitions.is_empty()
+        // Space out the keyframes without an offset evenly between their neighbours.
+        let mut start = 0;
+        for end in 1..offsets.len() {
+            let end_offset = match offsets[end] {
+                Some(offset) => offset,
+                None => continue,
+            };
+            let start_offset = offsets[start].unwrap();
+            let count = (end - start) as f64;
+            for (i, offset) in offsets.iter_mut().enumerate().take(end).skip(start + 1) {
+                let fraction = (i - start) as f64 / count;
+                *offset = Some(start_offset + (end_offset - start_offset) * fraction);
+            }
+            start = end;
+        }
+
+        Ok(KeyframeEffect {
+            keyframes: keyframes.into_boxed_slice(),
+            offsets: offsets.into_iter().map(Option::unwrap).collect(),
+            timing,
+            composite: AnimationComposition::Replace,
+            computed: None,
+        })
     }
 
-    /// Whether or not this state needs animation ticks for its transitions
-    /// or animations.
-    pub fn needs_animation_ticks(&self) -> bool {
-        self.animations
-            .iter()
-            .any(|animation| animation.state.needs_to_be_ticked()) ||
-            self.transitions
-                .iter()
-                .any(|transition| transition.state.needs_to_be_ticked())
+    /// Whether the keyframes of this effect need to be resolved against the style of the target.
+    pub fn needs_resolution(&self) -> bool {
+        self.computed.is_none()
     }
 
-    /// The number of running animations and transitions for this `ElementAnimationSet`.
+    fn resolve<E>(
+        &mut self,
+        element: E,
+        context: &SharedStyleContext,
+        base_style: &Arc<ComputedValues>,
+        resolver: &mut StyleResolverForElement<E>,
+    ) where
+        E: TElement,
+    {
+        let guard = &context.guards.author;
+        let mut properties = PropertyDeclarationIdSet::default();
+        let mut resolved_steps = Vec::with_capacity(self.keyframes.len());
+        for (keyframe, offset) in self.keyframes.iter().zip(self.offsets.iter()) {
+            let mut step = IntermediateComputedKeyframe::new(*offset as f32);
+            step.push_declarations(keyframe.declarations.read_with(guard), base_style);
+            let properties_in_step = step.declarations.property_ids().clone();
+            for property in properties_in_step.iter() {
+                properties.insert(property);
+            }
+            let style = step.resolve_style(element, context, base_style, resolver);
+            resolved_steps.push((properties_in_step, style));
+        }
+
+        let underlying_values = properties
+            .iter()
+            .map(|property| underlying_value(property, base_style, context.stylist))
+            .collect();
+        let keyframes = self
+            .keyframes
+            .iter()
+            .zip(self.offsets.iter())
+            .zip(resolved_steps)
+            .map(|((keyframe, offset), (properties_in_step, style))| {
+                let values = properties
+                    .iter()
+                    .map(|property| {
+                        if !properties_in_step.contains(property) {
+                            return None;
+                        }
+                        AnimationValue::from_computed_values(property, &style)
+                    })
+                    .collect();
+                ComputedEffectKeyframe {
+                    offset: *offset,
+                    easing: keyframe.easing.clone(),
+                    composite: keyframe.composite,
+                    values,
+                }
+            })
+            .collect();
+
+        self.computed = Some(ComputedKeyframeEffect {
+            properties,
+            underlying_values,
+            keyframes,
+        });
+    }
+
+    /// Fill in an `AnimationValueMap` with the values of this effect at the given local time.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#the-effect-value-of-a-keyframe-effect>
+    fn sample(&self, local_time: f64, playback_rate: f64, map: &mut AnimationValueMap) {
+        let computed = match self.computed {
+            Some(ref computed) => computed,
+            None => return,
+        };
+        let progress = match self.timing.transformed_progress(local_time, playback_rate) {
+            Some(progress) => progress,
+            None => return,
+        };
+
+        let linear = TimingFunction::Keyword(TimingKeyword::Linear);
+        for (index, base_value) in computed.underlying_values.iter().enumerate() {
+            let underlying_value = map.get(&base_value.id().to_owned()).unwrap_or(base_value);
+
+            // The keyframes specifying this property, composited onto the underlying value,
+            // with neutral keyframes at 0 and 1 if needed.
+            let mut keyframes: Vec<(f64, &TimingFunction, AnimationValue)> = computed
+                .keyframes
+                .iter()
+                .filter_map(|k| {
+                    let composite = k.composite.unwrap_or(self.composite);
+                    let value = k.values[index].as_ref()?;
+                    let value = composite_value(value, Some(underlying_value), Some(composite));
+                    Some((k.offset, &k.easing, value))
+                })
+                .collect();
+            if !matches!(keyframes.first(), Some(keyframe) if keyframe.0 == 0.) {
+                keyframes.insert(0, (0., &linear, underlying_value.clone()));
+            }
+            if !matches!(keyframes.last(), Some(keyframe) if keyframe.0 == 1.) {
+                keyframes.push((1., &linear, underlying_value.clone()));
+            }
+
+            let count_at = |offset: f64| keyframes.iter().filter(|k| k.0 == offset).count();
+            let value = if progress < 0. && count_at(0.) > 1 {
+                keyframes[0].2.clone()
+            } else if progress >= 1. && count_at(1.) > 1 {
+                keyframes[keyframes.len() - 1].2.clone()
+            } else {
+                let start = keyframes
+                    .iter()
+                    .rposition(|k| k.0 <= progress && k.0 < 1.)
+                    .unwrap_or(0);
+                let (start_offset, easing, ref from) = keyframes[start];
+                let (end_offset, _, ref to) = keyframes[start + 1];
+                let animation = PropertyAnimation {
+                    from: from.clone(),
+                    to: to.clone(),
+                    timing_function: easing.clone(),
+                    duration: (end_offset - start_offset) * self.timing.duration,
+                };
+                animation.calculate_value((progress - start_offset) / (end_offset - start_offset))
+            };
+            map.insert(value.id().to_owned(), value);
+        }
+    }
+}
+
+/// The errors of operations on script-created animations, named after the exceptions script
+/// should throw.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScriptAnimationError {
+    /// The keyframes or timing properties are invalid.
+    TypeError,
+    /// The operation isn't possible in the current state of the animation.
+    InvalidStateError,
+}
+
+/// The play state of a script-created animation.
+///
+/// <https://drafts.csswg.org/web-animations-1/#play-states>
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScriptAnimationPlayState {
+    /// The animation has no current time, because it hasn't been played or was canceled.
+    Idle,
+    /// The animation is running.
+    Running,
+    /// The animation is paused.
+    Paused,
+    /// The animation reached the end of its effect in the playback direction.
+    Finished,
+}
+
+/// An animation created by script, for example with `element.animate()`.
+///
+/// Times are in seconds and relative to the document timeline, like the times of CSS
+/// animations. Play and pause requests are applied immediately, so there are no pending tasks.
+///
+/// <https://drafts.csswg.org/web-animations-1/#animations>
+#[derive(Clone, Debug, MallocSizeOf)]
+pub struct ScriptAnimation {
+    /// The position of this animation in the composite order. This also identifies the
+    /// animation within its `DocumentAnimationSet`.
+    pub composite_order: usize,
+
+    /// The effect of this animation.
+    pub effect: KeyframeEffect,
+
+    /// The timeline time at which the current time of this animation was zero.
+    start_time: Option<f64>,
+
+    /// The current time of this animation, if it doesn't advance with the timeline. This is
+    /// the case for paused and finished animations.
+    hold_time: Option<f64>,
+
+    /// The rate at which this animation advances relative to the timeline.
+    playback_rate: f64,
+
+    /// Whether this animation is paused.
+    paused: bool,
+}
+
+impl ScriptAnimation {
+    fn new(composite_order: usize, effect: KeyframeEffect) -> Self {
+        ScriptAnimation {
+            composite_order,
+            effect,
+            start_time: None,
+            hold_time: None,
+            playback_rate: 1.,
+            paused: false,
+        }
+    }
+
+    /// The current time of this animation, given the current timeline time.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#the-current-time-of-an-animation>
+    pub fn current_time(&self, now: f64) -> Option<f64> {
+        if let Some(hold_time) = self.hold_time {
+            return Some(hold_time);
+        }
+        let current_time = (now - self.start_time?) * self.playback_rate;
+        let end_time = self.effect.timing.end_time();
+        Some(if self.playback_rate > 0. && current_time >= end_time {
+            end_time
+        } else if self.playback_rate < 0. && current_time <= 0. {
+            0.
+        } else {
+            current_time
+        })
+    }
+
+    /// Whether this animation is idle, that is, it has no current time.
+    pub fn is_idle(&self) -> bool {
+        self.start_time.is_none() && self.hold_time.is_none()
+    }
+
+    /// The playback rate of this animation.
+    pub fn playback_rate(&self) -> f64 {
+        self.playback_rate
+    }
+
+    /// The play state of this animation, given the current timeline time.
+    pub fn play_state(&self, now: f64) -> ScriptAnimationPlayState {
+        let current_time = match self.current_time(now) {
+            Some(current_time) => current_time,
+            None => return ScriptAnimationPlayState::Idle,
+        };
+        if self.paused {
+            return ScriptAnimationPlayState::Paused;
+        }
+        if (self.playback_rate > 0. && current_time >= self.effect.timing.end_time()) ||
+            (self.playback_rate < 0. && current_time <= 0.)
+        {
+            return ScriptAnimationPlayState::Finished;
+        }
+        ScriptAnimationPlayState::Running
+    }
+
+    /// Whether or not this animation needs to be ticked.
+    pub fn needs_to_be_ticked(&self) -> bool {
+        !self.paused && self.hold_time.is_none() && self.start_time.is_some()
+    }
+
+    /// Sets the current time of this animation. Seeking an idle animation pauses it.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#setting-the-current-time-of-an-animation>
+    pub fn set_current_time(&mut self, seek_time: f64, now: f64) {
+        if self.is_idle() {
+            self.paused = true;
+        }
+        self.seek(seek_time, now);
+    }
+
+    fn seek(&mut self, seek_time: f64, now: f64) {
+        if self.paused || self.playback_rate == 0. {
+            self.hold_time = Some(seek_time);
+            if self.paused {
+                self.start_time = None;
+            }
+        } else {
+            self.start_time = Some(now - seek_time / self.playback_rate);
+            self.hold_time = None;
+        }
+    }
+
+    /// Sets the playback rate of this animation, preserving its current time.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#setting-the-playback-rate-of-an-animation>
+    pub fn set_playback_rate(&mut self, playback_rate: f64, now: f64) {
+        let current_time = self.current_time(now);
+        self.playback_rate = playback_rate;
+        if let Some(current_time) = current_time {
+            self.seek(current_time, now);
+        }
+    }
+
+    /// Plays this animation, rewinding it if it's finished or hasn't started. Fails if the
+    /// animation would have to be rewound to the end of an infinite effect.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#playing-an-animation-section>
+    pub fn play(&mut self, now: f64) -> Result<(), ScriptAnimationError> {
+        let current_time = self.current_time(now);
+        let end_time = self.effect.timing.end_time();
+        let seek_time = if self.playback_rate > 0. {
+            match current_time {
+                Some(time) if time >= 0. && time < end_time => time,
+                _ => 0.,
+            }
+        } else if self.playback_rate < 0. {
+            match current_time {
+                Some(time) if time > 0. && time <= end_time => time,
+                _ if end_time.is_infinite() => return Err(ScriptAnimationError::InvalidStateError),
+                _ => end_time,
+            }
+        } else {
+            current_time.unwrap_or(0.)
+        };
+        self.paused = false;
+        self.seek(seek_time, now);
+        Ok(())
+    }
+
+    /// Pauses this animation. Fails if the animation would have to be paused at the end of an
+    /// infinite effect.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#pausing-an-animation-section>
+    pub fn pause(&mut self, now: f64) -> Result<(), ScriptAnimationError> {
+        if self.paused {
+            return Ok(());
+        }
+        let seek_time = match self.current_time(now) {
+            Some(time) => time,
+            None if self.playback_rate >= 0. => 0.,
+            None => {
+                let end_time = self.effect.timing.end_time();
+                if end_time.is_infinite() {
+                    return Err(ScriptAnimationError::InvalidStateError);
+                }
+                end_time
+            },
+        };
+        self.paused = true;
+        self.seek(seek_time, now);
+        Ok(())
+    }
+
+    /// Reverses the playback direction of this animation and plays it.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#reversing-an-animation-section>
+    pub fn reverse(&mut self, now: f64) -> Result<(), ScriptAnimationError> {
+        let original = self.clone();
+        self.set_playback_rate(-self.playback_rate, now);
+        if self.play(now).is_err() {
+            *self = original;
+            return Err(ScriptAnimationError::InvalidStateError);
+        }
+        Ok(())
+    }
+
+    /// Seeks this animation to the end of its effect in the playback direction. Fails if the
+    /// playback rate is zero, or if the effect is infinite and the animation is playing forwards.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#finishing-an-animation-section>
+    pub fn finish(&mut self, now: f64) -> Result<(), ScriptAnimationError> {
+        let end_time = self.effect.timing.end_time();
+        if self.playback_rate == 0. || (self.playback_rate > 0. && end_time.is_infinite()) {
+            return Err(ScriptAnimationError::InvalidStateError);
+        }
+        let limit = if self.playback_rate > 0. { end_time } else { 0. };
+        self.paused = false;
+        self.start_time = Some(now - limit / self.playback_rate);
+        self.hold_time = Some(limit);
+        Ok(())
+    }
+
+    /// Cancels this animation, which stops it from having any effect until it's played again.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#canceling-an-animation-section>
+    pub fn cancel(&mut self) {
+        self.start_time = None;
+        self.hold_time = None;
+        self.paused = false;
+    }
+
+    /// Holds the current time of this animation if it has finished, so that it stops advancing
+    /// with the timeline. This happens when animations are ticked, see
+    /// `DocumentAnimationSet::update_animations_and_take_events`.
+    ///
+    /// <https://drafts.csswg.org/web-animations-1/#updating-the-finished-state>
+    pub fn update_finished_state(&mut self, now: f64) {
+        if self.needs_to_be_ticked() && self.play_state(now) == ScriptAnimationPlayState::Finished
+        {
+            self.hold_time = self.current_time(now);
+        }
+    }
+
+    fn get_property_declaration_at_time(&self, now: f64, map: &mut AnimationValueMap) {
+        if let Some(local_time) = self.current_time(now) {
+            self.effect.sample(local_time, self.playback_rate, map);
+        }
+    }
+}
+
+/// Whether animations of the given property can be sampled by a compositor,
+/// without restyling the element.
+pub fn is_compositor_animatable(property: PropertyDeclarationId) -> bool {
+    matches!(
+        property,
+        PropertyDeclarationId::Longhand(
+            LonghandId::Transform |
+                LonghandId::Translate |
+                LonghandId::Rotate |
+                LonghandId::Scale |
+                LonghandId::Opacity |
+                LonghandId::Filter
+        )
+    )
+}
+
+/// A keyframe of a `CompositorAnimation`.
+#[derive(Clone, Debug, MallocSizeOf)]
+pub struct CompositorKeyframe {
+    /// The offset of this keyframe, between 0 and 1.
+    pub offset: f64,
+    /// The timing function used from this keyframe to the next one.
+    pub timing_function: TimingFunction,
+    /// The value of each animated property at this keyframe.
+    pub values: Box<[AnimationValue]>,
+}
+
+/// A self-contained description of a CSS animation or transition, which a
+/// compositor can sample off the main thread without access to the style of the
+/// element.
+#[derive(Clone, Debug, MallocSizeOf)]
+pub struct CompositorAnimation {
+    /// The keyframes of this animation, sorted by offset, starting at 0 and ending
+    /// at 1.
+    pub keyframes: Box<[CompositorKeyframe]>,
+    /// The timing of this animation. The delay is already accounted for in
+    /// `start_time`.
+    pub timing: EffectTiming,
+    /// The timeline time at which the first iteration starts.
+    pub start_time: f64,
+    /// If this animation is paused, the time into the animation at which it is.
+    pub paused_at: Option<f64>,
+}
+
+impl CompositorAnimation {
+    /// Computes the values of the animated properties at the given timeline time,
+    /// or nothing if the animation doesn't apply at that time.
+    pub fn sample(&self, time: f64) -> Vec<AnimationValue> {
+        let local_time = self.paused_at.unwrap_or(time - self.start_time);
+        let progress = match self.timing.transformed_progress(local_time, 1.) {
+            Some(progress) => progress,
+            None => return vec![],
+        };
+
+        let last = self.keyframes.len() - 1;
+        let next_index = self
+            .keyframes
+            .iter()
+            .position(|keyframe| progress < keyframe.offset)
+            .unwrap_or(last)
+            .max(1);
+        let prev = &self.keyframes[next_index - 1];
+        let next = &self.keyframes[next_index];
+        let offset_between_keyframes = next.offset - prev.offset;
+        if offset_between_keyframes <= 0. {
+            return next.values.to_vec();
+        }
+
+        let progress_between_keyframes = (progress - prev.offset) / offset_between_keyframes;
+        prev.values
+            .iter()
+            .zip(next.values.iter())
+            .map(|(from, to)| {
+                let animation = PropertyAnimation {
+                    from: from.clone(),
+                    to: to.clone(),
+                    timing_function: prev.timing_function.clone(),
+                    duration: offset_between_keyframes * self.timing.duration,
+                };
+                animation.calculate_value(progress_between_keyframes)
+            })
+            .collect()
+    }
+}
+
+/// Holds the animation state for a particular element.
+#[derive(Debug, Default, MallocSizeOf)]
+pub struct ElementAnimationSet {
+    /// The animations for this element.
+    pub animations: Vec<Animation>,
+
+    /// The transitions for this element.
+    pub transitions: Vec<Transition>,
+
+    /// The animations created by script for this element, sorted by composite order.
+    pub script_animations: Vec<ScriptAnimation>,
+
+    /// The named timeline ranges of the view progress timeline this element's
+    /// CSS animations are attached to, if any. This is used to place keyframes
+    /// and `animation-range` values which refer to named timeline ranges.
+    timeline_ranges: Option<TimelineRanges>,
+
+    /// Whether `timeline_ranges` changed since the keyframes of the CSS
+    /// animations were last generated.
+    timeline_ranges_changed: bool,
+
+    /// Whether or not this ElementAnimationSet has had animations or transitions
+    /// which have been added, removed, or had their state changed.
+    pub dirty: bool,
+}
+
+impl ElementAnimationSet {
+    /// Cancel all animations in this `ElementAnimationSet`. This is typically called
+    /// when the element has been removed from the DOM.
+    pub fn cancel_all_animations(&mut self) {
+        self.dirty = !self.animations.is_empty();
+        for animation in self.animations.iter_mut() {
+            animation.state = AnimationState::Canceled;
+        }
+        for animation in self.script_animations.iter_mut() {
+            self.dirty = true;
+            animation.cancel();
+        }
+        self.cancel_active_transitions();
+    }
+
+    fn cancel_active_transitions(&mut self) {
+        for transition in self.transitions.iter_mut() {
+            if transition.state != AnimationState::Finished {
+                self.dirty = true;
+                transition.state = AnimationState::Canceled;
+            }
+        }
+    }
+
+    /// Apply all active animations.
+    pub fn apply_active_animations(
+        &self,
+        context: &SharedStyleContext,
+        style: &mut Arc<ComputedValues>,
+    ) {
+        let now = context.current_time_for_animations;
+        let mutable_style = Arc::make_mut(style);
+        if let Some(map) = self.get_value_map_for_active_animations(now) {
+            for value in map.values() {
+                value.set_in_style_for_servo(mutable_style, context.stylist);
+            }
+        }
+
+        if let Some(map) = self.get_value_map_for_active_transitions(now) {
+            for value in map.values() {
+                value.set_in_style_for_servo(mutable_style, context.stylist);
+            }
+        }
+    }
+
+    /// Clear all canceled animations and transitions from this `ElementAnimationSet`.
+    pub fn clear_canceled_animations(&mut self) {
+        self.animations
+            .retain(|animation| animation.state != AnimationState::Canceled);
+        self.transitions
+            .retain(|animation| animation.state != AnimationState::Canceled);
+    }
+
+    /// Whether this `ElementAnimationSet` is empty, which means it doesn't
+    /// hold any animations in any state, nor any named timeline ranges.
+    pub fn is_empty(&self) -> bool {
+        self.animations.is_empty() &&
+            self.transitions.is_empty() &&
+            self.script_animations.is_empty() &&
+            self.timeline_ranges.is_none()
+    }
+
+    /// Whether or not this state needs animation ticks for its transitions
+    /// or animations.
+    pub fn needs_animation_ticks(&self) -> bool {
+        self.animations
+            .iter()
+            .any(|animation| animation.state.needs_to_be_ticked()) ||
+            self.transitions
+                .iter()
+                .any(|transition| transition.state.needs_to_be_ticked()) ||
+            self.script_animations
+                .iter()
+                .any(|animation| animation.needs_to_be_ticked())
+    }
+
+    /// Whether or not this state needs animation ticks on the main thread, assuming
+    /// that the animations and transitions which can run on a compositor are sampled
+    /// there instead.
+    pub fn needs_main_thread_animation_ticks(&self) -> bool {
+        self.animations.iter().any(|animation| {
+            animation.state.needs_to_be_ticked() && !animation.can_run_on_compositor()
+        }) ||
+            self.transitions.iter().any(|transition| {
+                transition.state.needs_to_be_ticked() && !transition.can_run_on_compositor()
+            }) ||
+            self.script_animations
+                .iter()
+                .any(|animation| animation.needs_to_be_ticked())
+    }
+
+    /// The descriptions of the animations and transitions of this set which can be
+    /// sampled by a compositor, in cascade order.
+    pub fn compositor_animations(&self) -> Vec<CompositorAnimation> {
+        self.animations
+            .iter()
+            .filter_map(|animation| animation.compositor_animation())
+            .chain(
+                self.transitions
+                    .iter()
+                    .filter_map(|transition| transition.compositor_animation()),
+            )
+            .collect()
+    }
+
+    /// The number of running animations and transitions for this `ElementAnimationSet`.
     pub fn running_animation_and_transition_count(&self) -> usize {
         self.animations
             .iter()
@@ -935,6 +2141,10 @@ impl ElementAnimationSet {
             self.transitions
                 .iter()
                 .filter(|transition| transition.state.needs_to_be_ticked())
+                .count() +
+            self.script_animations
+                .iter()
+                .filter(|animation| animation.needs_to_be_ticked())
                 .count()
     }
 
@@ -945,6 +2155,36 @@ impl ElementAnimationSet {
             .any(|animation| animation.state != AnimationState::Canceled)
     }
 
+    /// If this `ElementAnimationSet` has any script-created animations which aren't idle.
+    pub fn has_active_script_animation(&self) -> bool {
+        self.script_animations
+            .iter()
+            .any(|animation| !animation.is_idle())
+    }
+
+    /// Set the named timeline ranges of the view progress timeline this element's CSS
+    /// animations are attached to.
+    pub fn set_timeline_ranges(&mut self, timeline_ranges: Option<TimelineRanges>) {
+        if self.timeline_ranges != timeline_ranges {
+            self.timeline_ranges = timeline_ranges;
+            self.timeline_ranges_changed = true;
+        }
+    }
+
+    /// Whether the keyframes of the CSS animations need to be generated again because the
+    /// named timeline ranges they may refer to changed.
+    pub fn timeline_ranges_changed(&self) -> bool {
+        self.timeline_ranges_changed
+    }
+
+    /// Whether the keyframes of any script-created animation need to be resolved against the
+    /// style of this element.
+    pub fn needs_script_keyframe_resolution(&self) -> bool {
+        self.script_animations
+            .iter()
+            .any(|animation| animation.effect.needs_resolution())
+    }
+
     /// If this `ElementAnimationSet` has any any active transitions.
     pub fn has_active_transition(&self) -> bool {
         self.transitions
@@ -970,6 +2210,13 @@ impl ElementAnimationSet {
         }
 
         maybe_start_animations(element, &context, &new_style, self, resolver);
+        self.timeline_ranges_changed = false;
+
+        for animation in self.script_animations.iter_mut() {
+            animation
+                .effect
+                .resolve(element, context, new_style, resolver);
+        }
     }
 
     /// Update our transitions given a new style, canceling or starting new animations
@@ -981,15 +2228,20 @@ impl ElementAnimationSet {
         old_style: Option<&Arc<ComputedValues>>,
         after_change_style: &Arc<ComputedValues>,
     ) {
-        // If this is the first style, we don't trigger any transitions and we assume
-        // there were no previously triggered transitions.
+        // If there is no before-change style, that is, this is the first style and there
+        // is no starting style, we don't trigger any transitions and we assume there were
+        // no previously triggered transitions.
         let mut before_change_style = match old_style {
             Some(old_style) => Arc::clone(old_style),
             None => return,
         };
 
-        // If the style of this element is display:none, then cancel all active transitions.
-        if after_change_style.get_box().clone_display().is_none() {
+        // If the style of this element is display:none, then cancel all active transitions,
+        // unless `display` itself transitions, which keeps the element rendered until that
+        // transition ends.
+        if after_change_style.get_box().clone_display().is_none() &&
+            !self.transitions_display(after_change_style)
+        {
             self.cancel_active_transitions();
             return;
         }
@@ -999,7 +2251,10 @@ impl ElementAnimationSet {
         }
 
         // We convert old values into `before-change-style` here.
-        if self.has_active_transition() || self.has_active_animation() {
+        if self.has_active_transition() ||
+            self.has_active_animation() ||
+            self.has_active_script_animation()
+        {
             self.apply_active_animations(context, &mut before_change_style);
         }
 
@@ -1023,6 +2278,30 @@ impl ElementAnimationSet {
         }
     }
 
+    /// Whether `display` has an active transition, or may start one for the given
+    /// after-change style.
+    fn transitions_display(&self, after_change_style: &ComputedValues) -> bool {
+        let display = PropertyDeclarationId::Longhand(LonghandId::Display);
+        let has_active_transition = self.transitions.iter().any(|transition| {
+            transition.state != AnimationState::Canceled &&
+                transition.state != AnimationState::Finished &&
+                transition.property_animation.property_id() == display
+        });
+        if has_active_transition {
+            return true;
+        }
+
+        let style = after_change_style.get_ui();
+        after_change_style.transition_properties().any(|transition| {
+            let index = transition.index;
+            transition.property.as_borrowed() == display &&
+                style.transition_behavior_mod(index) == TransitionBehavior::AllowDiscrete &&
+                style.transition_duration_mod(index).seconds().max(0.) +
+                    style.transition_delay_mod(index).seconds() >
+                    0.
+        })
+    }
+
     fn start_transition_if_applicable(
         &mut self,
         context: &SharedStyleContext,
@@ -1109,6 +2388,124 @@ impl ElementAnimationSet {
         self.dirty = true;
     }
 
+    /// Advance the state of the animations and transitions of this set to the given
+    /// time, and queue the events this produces.
+    fn update_and_queue_events(
+        &mut self,
+        key: &AnimationSetKey,
+        now: f64,
+        events: &mut Vec<AnimationEvent>,
+    ) {
+        let mut queue = |event_type, source, elapsed_time, scheduled_time| {
+            events.push(AnimationEvent {
+                key: key.clone(),
+                event_type,
+                source,
+                elapsed_time,
+                scheduled_time,
+            })
+        };
+
+        for animation in self.animations.iter_mut() {
+            let source = AnimationEventSource::Animation(animation.name.clone());
+            let was_new = std::mem::replace(&mut animation.is_new, false);
+            if animation.state == AnimationState::Canceled {
+                // Animations which were never seen by a previous update didn't
+                // start, so there is nothing to cancel.
+                if !was_new {
+                    let elapsed_time = animation.elapsed_time(now);
+                    queue(AnimationEventType::AnimationCancel, source.clone(), elapsed_time, now);
+                }
+                continue;
+            }
+
+            if animation.state == AnimationState::Pending && animation.started_at <= now {
+                animation.state = AnimationState::Running;
+                let elapsed_time = (-animation.delay).min(animation.active_duration()).max(0.);
+                let scheduled_time = animation.started_at;
+                let event_type = AnimationEventType::AnimationStart;
+                queue(event_type, source.clone(), elapsed_time, scheduled_time);
+            }
+
+            if animation.state != AnimationState::Running {
+                continue;
+            }
+
+            if animation.iterate_if_necessary(now) {
+                let elapsed_time = animation.current_iteration() * animation.duration;
+                let scheduled_time = animation.started_at;
+                let event_type = AnimationEventType::AnimationIteration;
+                queue(event_type, source.clone(), elapsed_time, scheduled_time);
+            }
+
+            if animation.has_ended(now) {
+                animation.state = AnimationState::Finished;
+                let elapsed_time = animation.active_duration();
+                let scheduled_time = animation.started_at + animation.current_iteration_duration();
+                let event_type = AnimationEventType::AnimationEnd;
+                queue(event_type, source.clone(), elapsed_time, scheduled_time);
+            }
+        }
+
+        for transition in self.transitions.iter_mut() {
+            let property = transition.property_animation.property_id().to_owned();
+            let source = AnimationEventSource::Transition(property);
+            let duration = transition.property_animation.duration;
+            let was_new = std::mem::replace(&mut transition.is_new, false);
+            if transition.state == AnimationState::Canceled {
+                if !was_new {
+                    let elapsed_time = (now - transition.start_time).min(duration).max(0.);
+                    queue(AnimationEventType::TransitionCancel, source.clone(), elapsed_time, now);
+                }
+                continue;
+            }
+
+            let start_elapsed_time = (-transition.delay).min(duration).max(0.);
+            if was_new {
+                let scheduled_time = transition.start_time - transition.delay;
+                let event_type = AnimationEventType::TransitionRun;
+                queue(event_type, source.clone(), start_elapsed_time, scheduled_time);
+            }
+
+            if transition.state == AnimationState::Pending && transition.start_time <= now {
+                transition.state = AnimationState::Running;
+                let scheduled_time = transition.start_time;
+                let event_type = AnimationEventType::TransitionStart;
+                queue(event_type, source.clone(), start_elapsed_time, scheduled_time);
+            }
+
+            if transition.state == AnimationState::Running && transition.has_ended(now) {
+                transition.state = AnimationState::Finished;
+                let scheduled_time = transition.start_time + duration;
+                queue(AnimationEventType::TransitionEnd, source.clone(), duration, scheduled_time);
+            }
+        }
+
+        for animation in self.script_animations.iter_mut() {
+            animation.update_finished_state(now);
+        }
+    }
+
+    /// Compute the animated values of this `ElementAnimationSet` at the given time,
+    /// with transitions applied over animations as in the cascade. This doesn't
+    /// change the state of any animation, so it can be used to sample any time
+    /// deterministically.
+    pub fn sample(&self, time: f64) -> AnimationValueMap {
+        let mut map = AnimationValueMap::default();
+        for animation in &self.animations {
+            animation
+                .advanced_to(time)
+                .get_property_declaration_at_time(time, &mut map);
+        }
+        for animation in &self.script_animations {
+            animation.get_property_declaration_at_time(time, &mut map);
+        }
+        if let Some(transitions) = self.get_value_map_for_active_transitions(time) {
+            map.extend(transitions);
+        }
+        map
+    }
+
     /// Generate a `AnimationValueMap` for this `ElementAnimationSet`'s
     /// active transitions at the given time value.
     pub fn get_value_map_for_active_transitions(&self, now: f64) -> Option<AnimationValueMap> {
@@ -1130,9 +2527,10 @@ impl ElementAnimationSet {
     }
 
     /// Generate a `AnimationValueMap` for this `ElementAnimationSet`'s
-    /// active animations at the given time value.
+    /// active animations at the given time value. CSS animations come first in
+    /// the composite order, followed by script-created animations.
     pub fn get_value_map_for_active_animations(&self, now: f64) -> Option<AnimationValueMap> {
-        if !self.has_active_animation() {
+        if !self.has_active_animation() && !self.has_active_script_animation() {
             return None;
         }
 
@@ -1140,6 +2538,9 @@ impl ElementAnimationSet {
         for animation in &self.animations {
             animation.get_property_declaration_at_time(now, &mut map);
         }
+        for animation in &self.script_animations {
+            animation.get_property_declaration_at_time(now, &mut map);
+        }
 
         Some(map)
     }
@@ -1181,21 +2582,136 @@ impl AnimationSetKey {
     }
 }
 
+/// A source of the current time for animations, in seconds.
+pub trait AnimationTimeline {
+    /// The current time of this timeline.
+    fn current_time(&self) -> f64;
+}
+
+/// A timeline which only advances when told to, so that animations can be
+/// sampled deterministically, for instance to snapshot frames in reftests.
+#[derive(Clone, Copy, Debug, Default, MallocSizeOf, PartialEq)]
+pub struct VirtualTimeline {
+    current_time: f64,
+}
+
+impl VirtualTimeline {
+    /// Create a new `VirtualTimeline` at the given time.
+    pub fn new(current_time: f64) -> Self {
+        VirtualTimeline { current_time }
+    }
+
+    /// Advance this timeline by the given amount of time.
+    pub fn advance_by(&mut self, delta: f64) {
+        self.current_time += delta;
+    }
+
+    /// Move this timeline to the given time, which may be in the past.
+    pub fn set_current_time(&mut self, current_time: f64) {
+        self.current_time = current_time;
+    }
+}
+
+impl AnimationTimeline for VirtualTimeline {
+    fn current_time(&self) -> f64 {
+        self.current_time
+    }
+}
+
+/// The type of an animation or transition event.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AnimationEventType {
+    /// <https://drafts.csswg.org/css-animations/#eventdef-globaleventhandlers-animationstart>
+    AnimationStart,
+    /// <https://drafts.csswg.org/css-animations/#eventdef-globaleventhandlers-animationiteration>
+    AnimationIteration,
+    /// <https://drafts.csswg.org/css-animations/#eventdef-globaleventhandlers-animationend>
+    AnimationEnd,
+    /// <https://drafts.csswg.org/css-animations/#eventdef-globaleventhandlers-animationcancel>
+    AnimationCancel,
+    /// <https://drafts.csswg.org/css-transitions/#transitionrun>
+    TransitionRun,
+    /// <https://drafts.csswg.org/css-transitions/#transitionstart>
+    TransitionStart,
+    /// <https://drafts.csswg.org/css-transitions/#transitionend>
+    TransitionEnd,
+    /// <https://drafts.csswg.org/css-transitions/#transitioncancel>
+    TransitionCancel,
+}
+
+impl AnimationEventType {
+    /// The name of the DOM event of this type.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            AnimationEventType::AnimationStart => "animationstart",
+            AnimationEventType::AnimationIteration => "animationiteration",
+            AnimationEventType::AnimationEnd => "animationend",
+            AnimationEventType::AnimationCancel => "animationcancel",
+            AnimationEventType::TransitionRun => "transitionrun",
+            AnimationEventType::TransitionStart => "transitionstart",
+            AnimationEventType::TransitionEnd => "transitionend",
+            AnimationEventType::TransitionCancel => "transitioncancel",
+        }
+    }
+
+    /// Whether this is the type of a `TransitionEvent`, as opposed to an
+    /// `AnimationEvent`.
+    pub fn is_transition_event(&self) -> bool {
+        matches!(
+            *self,
+            AnimationEventType::TransitionRun |
+                AnimationEventType::TransitionStart |
+                AnimationEventType::TransitionEnd |
+                AnimationEventType::TransitionCancel
+        )
+    }
+}
+
+/// The animation or transition that an event is about.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AnimationEventSource {
+    /// The CSS animation with the given name, the `animationName` of the event.
+    Animation(Atom),
+    /// The CSS transition of the given property, the `propertyName` of the event.
+    Transition(OwnedPropertyDeclarationId),
+}
+
+/// An animation or transition event, ready to be dispatched by the embedder.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationEvent {
+    /// The node and pseudo-element this event is about.
+    pub key: AnimationSetKey,
+    /// The type of this event.
+    pub event_type: AnimationEventType,
+    /// The animation or transition this event is about.
+    pub source: AnimationEventSource,
+    /// The `elapsedTime` of this event, in seconds.
+    pub elapsed_time: f64,
+    /// The timeline time at which this event happened, which determines the order
+    /// of events.
+    pub scheduled_time: f64,
+}
+
 #[derive(Clone, Debug, Default, MallocSizeOf)]
 /// A set of animations for a document.
 pub struct DocumentAnimationSet {
     /// The `ElementAnimationSet`s that this set contains.
     #[ignore_malloc_size_of = "Arc is hard"]
     pub sets: Arc<RwLock<FxHashMap<AnimationSetKey, ElementAnimationSet>>>,
+
+    /// The composite order of the next script-created animation.
+    #[ignore_malloc_size_of = "Arc is hard"]
+    next_composite_order: Arc<AtomicUsize>,
 }
 
 impl DocumentAnimationSet {
-    /// Return whether or not the provided node has active CSS animations.
+    /// Return whether or not the provided node has active CSS or script-created
+    /// animations.
     pub fn has_active_animations(&self, key: &AnimationSetKey) -> bool {
-        self.sets
-            .read()
-            .get(key)
-            .map_or(false, |set| set.has_active_animation())
+        matches!(
+            self.sets.read().get(key),
+            Some(set) if set.has_active_animation() || set.has_active_script_animation()
+        )
     }
 
     /// Return whether or not the provided node has active CSS transitions.
@@ -1270,6 +2786,108 @@ impl DocumentAnimationSet {
         }
     }
 
+    /// Create an animation with the given effect for the given key and play it, as
+    /// `element.animate()` does. Returns the composite order of the new animation,
+    /// which identifies it in this set. The element needs to be restyled for the
+    /// animation to take effect.
+    pub fn animate(
+        &self,
+        key: AnimationSetKey,
+        effect: KeyframeEffect,
+        now: f64,
+    ) -> Result<usize, ScriptAnimationError> {
+        let composite_order = self.next_composite_order.fetch_add(1, Ordering::Relaxed);
+        let mut animation = ScriptAnimation::new(composite_order, effect);
+        animation.play(now)?;
+
+        let mut sets = self.sets.write();
+        let set = sets.entry(key).or_default();
+        set.script_animations.push(animation);
+        set.dirty = true;
+        Ok(composite_order)
+    }
+
+    /// Run the given function on the script-created animation with the given
+    /// composite order, if it exists. The element needs to be restyled for any
+    /// change to take effect.
+    pub fn with_script_animation<F, R>(
+        &self,
+        key: &AnimationSetKey,
+        composite_order: usize,
+        f: F,
+    ) -> Option<R>
+    where
+        F: FnOnce(&mut ScriptAnimation) -> R,
+    {
+        let mut sets = self.sets.write();
+        let set = sets.get_mut(key)?;
+        let animation = set
+            .script_animations
+            .iter_mut()
+            .find(|animation| animation.composite_order == composite_order)?;
+        let result = f(animation);
+        set.dirty = true;
+        Some(result)
+    }
+
+    /// Remove the script-created animation with the given composite order, for
+    /// instance because script no longer holds a reference to it.
+    pub fn remove_script_animation(&self, key: &AnimationSetKey, composite_order: usize) {
+        if let Some(set) = self.sets.write().get_mut(key) {
+            set.script_animations
+                .retain(|animation| animation.composite_order != composite_order);
+            set.dirty = true;
+        }
+    }
+
+    /// Set the named timeline ranges of the view progress timeline the CSS animations
+    /// for the given key are attached to. The element needs to be restyled for any
+    /// change to take effect.
+    pub fn set_timeline_ranges(
+        &self,
+        key: AnimationSetKey,
+        timeline_ranges: Option<TimelineRanges>,
+    ) {
+        let mut sets = self.sets.write();
+        match timeline_ranges {
+            Some(_) => sets.entry(key).or_default().set_timeline_ranges(timeline_ranges),
+            None => {
+                if let Some(set) = sets.get_mut(&key) {
+                    set.set_timeline_ranges(None);
+                }
+            },
+        }
+    }
+
+    /// Compute the animated values for the given key at the current time of the
+    /// given timeline, without changing the state of any animation. See
+    /// `ElementAnimationSet::sample`.
+    pub fn sample(
+        &self,
+        key: &AnimationSetKey,
+        timeline: &dyn AnimationTimeline,
+    ) -> Option<AnimationValueMap> {
+        let time = timeline.current_time();
+        self.sets.read().get(key).map(|set| set.sample(time))
+    }
+
+    /// Advance the state of all animations and transitions to the given time, and
+    /// return the events this produces, sorted by the time at which they happened.
+    /// Canceled animations and transitions are removed once their events have been
+    /// queued.
+    pub fn update_animations_and_take_events(&self, now: f64) -> Vec<AnimationEvent> {
+        let mut events = Vec::new();
+        let mut sets = self.sets.write();
+        for (key, set) in sets.iter_mut() {
+            set.update_and_queue_events(key, now, &mut events);
+            set.clear_canceled_animations();
+        }
+        sets.retain(|_, set| !set.is_empty());
+
+        events.sort_by(|a, b| a.scheduled_time.total_cmp(&b.scheduled_time));
+        events
+    }
+
     /// Cancel all animations for set at the given key.
     pub fn cancel_all_animations_for_key(&self, key: &AnimationSetKey) {
         if let Some(set) = self.sets.write().get_mut(key) {
@@ -1384,7 +3002,8 @@ pub fn maybe_start_animations<E>(
             &keyframe_animation,
             context,
             new_style,
-            style.animation_timing_function_mod(i),
+            i,
+            animation_state.timeline_ranges,
             resolver,
         );
 
@@ -1429,3 +3048,81 @@ pub fn maybe_start_animations<E>(
         animation_state.animations.push(new_animation);
     }
 }
+
+#[cfg(all(test, feature = "servo"))]
+mod tests {
+    use super::*;
+    use crate::custom_properties::VariableValue;
+    use crate::font_metrics::FontMetrics;
+    use crate::media_queries::{Device, MediaType};
+    use crate::properties::style_structs::Font;
+    use crate::properties_and_values::value::ComputedValue;
+    use crate::queries::values::PrefersColorScheme;
+    use crate::servo::media_queries::FontMetricsProvider;
+    use crate::stylesheets::UrlExtraData;
+    use crate::values::computed::font::GenericFontFamily;
+    use crate::values::computed::CSSPixelLength;
+    use euclid::{Scale, Size2D};
+    use selectors::matching::QuirksMode;
+
+    #[derive(Debug)]
+    struct DummyFontMetricsProvider;
+
+    impl FontMetricsProvider for DummyFontMetricsProvider {
+        fn query_font_metrics(
+            &self,
+            _vertical: bool,
+            _font: &Font,
+            _base_size: CSSPixelLength,
+            _in_media_query: bool,
+            _retrieve_math_scales: bool,
+        ) -> FontMetrics {
+            Default::default()
+        }
+
+        fn base_size_for_generic(&self, _generic: GenericFontFamily) -> Length {
+            Length::new(16.)
+        }
+    }
+
+    fn stylist() -> Stylist {
+        let device = Device::new(
+            MediaType::screen(),
+            QuirksMode::NoQuirks,
+            Size2D::new(800., 600.),
+            Scale::new(1.),
+            Box::new(DummyFontMetricsProvider),
+            ComputedValues::initial_values_with_font_override(Font::initial_values()),
+            PrefersColorScheme::Light,
+        );
+        Stylist::new(device, QuirksMode::NoQuirks)
+    }
+
+    #[test]
+    fn custom_property_absent_from_base_style() {
+        // `@keyframes k { to { --x: 1 } }` on an element that doesn't have `--x`.
+        let stylist = stylist();
+        let base_style = stylist.device().default_computed_values();
+        let name = Atom::from("x");
+        let underlying =
+            underlying_value(PropertyDeclarationId::Custom(&name), base_style, &stylist);
+        let guaranteed_invalid = CustomAnimatedValue::guaranteed_invalid(&name);
+        assert!(underlying == AnimationValue::Custom(guaranteed_invalid));
+
+        let url_data = UrlExtraData::from(url::Url::parse("about:blank").unwrap());
+        let value =
+            VariableValue::new("1".to_owned(), &url_data, Default::default(), Default::default());
+        let keyframe = AnimationValue::Custom(CustomAnimatedValue::from_computed(
+            &name,
+            &ComputedValue::universal(Arc::new(value)),
+        ));
+        let procedure = Procedure::Interpolate { progress: 0.25 };
+        assert!(underlying.animate(&keyframe, procedure).is_err());
+
+        let mut style = base_style.clone();
+        keyframe.set_in_style_for_servo(&mut style, &stylist);
+        assert!(style.custom_properties().inherited.get(&name).is_some());
+        underlying.set_in_style_for_servo(&mut style, &stylist);
+        assert!(style.custom_properties().inherited.get(&name).is_none());
+    }
+}
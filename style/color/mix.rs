@@ -187,6 +187,89 @@ pub fn mix(
     }
 }
 
+/// Mix any number of colors into one, as per the n-ary form of color-mix().
+///
+/// The colors are mixed progressively: each item is mixed into the running
+/// result with a weight relative to the sum of the weights seen so far.
+///
+/// https://drafts.csswg.org/css-color-5/#color-mix-result
+pub fn mix_many(
+    interpolation: ColorInterpolationMethod,
+    items: &[(AbsoluteColor, f32)],
+    flags: ColorMixFlags,
+) -> AbsoluteColor {
+    if let [(ref left, left_weight), (ref right, right_weight)] = *items {
+        return mix(interpolation, left, left_weight, right, right_weight, flags);
+    }
+
+    debug_assert!(!items.is_empty(), "color-mix() needs at least one color");
+
+    // https://drafts.csswg.org/css-color-5/#color-mix-percent-norm
+    let mut scale = 1.0;
+    let mut alpha_multiplier = 1.0;
+    if flags.contains(ColorMixFlags::NORMALIZE_WEIGHTS) {
+        let sum: f32 = items.iter().map(|&(_, weight)| weight).sum();
+        if sum != 1.0 {
+            scale = 1.0 / sum;
+            if sum < 1.0 {
+                alpha_multiplier = sum;
+            }
+        }
+    }
+
+    let (ref first, first_weight) = items[0];
+    let mut result = *first;
+    let mut accumulated_weight = first_weight * scale;
+    if items.len() == 1 {
+        // A single color is still converted into the interpolation color
+        // space, and has its alpha adjusted.
+        result = mix_in(
+            interpolation.space,
+            first,
+            1.0,
+            first,
+            0.0,
+            interpolation.hue,
+            alpha_multiplier,
+        );
+    }
+
+    let last_index = items.len() - 1;
+    for (index, &(ref color, weight)) in items.iter().enumerate().skip(1) {
+        let weight = weight * scale;
+        let total = accumulated_weight + weight;
+        if total <= 0.0 {
+            continue;
+        }
+        result = mix_in(
+            interpolation.space,
+            &result,
+            accumulated_weight / total,
+            color,
+            weight / total,
+            interpolation.hue,
+            if index == last_index {
+                alpha_multiplier
+            } else {
+                1.0
+            },
+        );
+        accumulated_weight = total;
+    }
+
+    if flags.contains(ColorMixFlags::RESULT_IN_MODERN_SYNTAX) {
+        if result.is_legacy_syntax() {
+            result.to_color_space(ColorSpace::Srgb)
+        } else {
+            result
+        }
+    } else if items.iter().all(|(color, _)| color.is_legacy_syntax()) {
+        result.into_srgb_legacy()
+    } else {
+        result
+    }
+}
+
 /// What the outcome of each component should be in a mix result.
 #[derive(Clone, Copy)]
 #[repr(u8)]
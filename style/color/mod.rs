@@ -11,6 +11,7 @@ mod color_function;
 pub mod component;
 pub mod mix;
 pub mod parsing;
+pub mod profile;
 mod to_css;
 
 use self::parsing::ChannelKeyword;
@@ -152,6 +153,21 @@ impl ColorSpace {
         }
     }
 
+    /// Returns the RGB color space whose components are limited to the [0, 1] range in the
+    /// gamut of this color space, or `None` if the color space has no gamut limits.
+    #[inline]
+    pub fn gamut_color_space(&self) -> Option<Self> {
+        match self {
+            Self::Srgb | Self::Hsl | Self::Hwb => Some(Self::Srgb),
+            Self::SrgbLinear |
+            Self::DisplayP3 |
+            Self::A98Rgb |
+            Self::ProphotoRgb |
+            Self::Rec2020 => Some(*self),
+            _ => None,
+        }
+    }
+
     /// Returns an index of the hue component in the color space, otherwise
     /// `None`.
     #[inline]
@@ -624,6 +640,142 @@ impl AbsoluteColor {
     }
 }
 
+/// Gamut mapping, which converts colors that can't be displayed in a color space to the closest
+/// color that can, preserving their lightness and hue.
+///
+/// <https://drafts.csswg.org/css-color-4/#gamut-mapping>
+impl AbsoluteColor {
+    /// Returns whether this color is within the gamut of the given color space, that is, whether
+    /// it can be represented by that color space without clipping its components.
+    pub fn is_in_gamut(&self, color_space: ColorSpace) -> bool {
+        let Some(gamut_space) = color_space.gamut_color_space() else {
+            return true;
+        };
+        let converted = self.to_color_space(gamut_space);
+        [converted.c0(), converted.c1(), converted.c2()]
+            .iter()
+            .all(|c| (0.0..=1.0).contains(&c.unwrap_or(0.0)))
+    }
+
+    /// Clamps the components of a color in an RGB color space into its gamut.
+    fn clip(&self) -> Self {
+        debug_assert!(self.color_space.gamut_color_space().is_some());
+        let clamp = |c: Option<f32>| c.map(|c| c.clamp(0.0, 1.0));
+        Self::new(
+            self.color_space,
+            clamp(self.c0()),
+            clamp(self.c1()),
+            clamp(self.c2()),
+            self.alpha(),
+        )
+    }
+
+    /// Returns the distance between two colors in the Oklab color space, deltaEOK.
+    ///
+    /// <https://drafts.csswg.org/css-color-4/#color-difference-OK>
+    pub fn delta_eok(&self, other: &Self) -> f32 {
+        let one = self.to_color_space(ColorSpace::Oklab);
+        let other = other.to_color_space(ColorSpace::Oklab);
+        let difference = |a: Option<f32>, b: Option<f32>| a.unwrap_or(0.0) - b.unwrap_or(0.0);
+        let delta_l = difference(one.c0(), other.c0());
+        let delta_a = difference(one.c1(), other.c1());
+        let delta_b = difference(one.c2(), other.c2());
+        (delta_l * delta_l + delta_a * delta_a + delta_b * delta_b).sqrt()
+    }
+
+    /// Converts this color to the given color space, mapping it into the gamut of that color
+    /// space if needed, by reducing its chroma in OKLCH until clipping it is not noticeable.
+    ///
+    /// <https://drafts.csswg.org/css-color-4/#binsearch>
+    pub fn map_to_gamut(&self, target: ColorSpace) -> Self {
+        // The just noticeable difference between two colors in deltaEOK.
+        const JND: f32 = 0.02;
+        // The precision of the binary search in chroma.
+        const EPSILON: f32 = 0.0001;
+
+        let Some(gamut_space) = target.gamut_color_space() else {
+            return self.to_color_space(target);
+        };
+
+        let origin = self.to_color_space(ColorSpace::Oklch);
+        let lightness = origin.c0().unwrap_or(0.0);
+        let mapped = if lightness >= 1.0 {
+            Self::new(gamut_space, 1.0, 1.0, 1.0, self.alpha())
+        } else if lightness <= 0.0 {
+            Self::new(gamut_space, 0.0, 0.0, 0.0, self.alpha())
+        } else if self.is_in_gamut(gamut_space) {
+            return self.to_color_space(target);
+        } else {
+            let mut current = origin;
+            let mut clipped = current.to_color_space(gamut_space).clip();
+            if clipped.delta_eok(&current) >= JND {
+                let mut min = 0.0;
+                let mut max = origin.c1().unwrap_or(0.0);
+                let mut min_in_gamut = true;
+                while max - min > EPSILON {
+                    let chroma = (min + max) / 2.0;
+                    current.components.1 = chroma;
+                    current.flags.remove(ColorFlags::C1_IS_NONE);
+                    if min_in_gamut && current.is_in_gamut(gamut_space) {
+                        min = chroma;
+                        continue;
+                    }
+                    clipped = current.to_color_space(gamut_space).clip();
+                    let delta = clipped.delta_eok(&current);
+                    if delta >= JND {
+                        max = chroma;
+                        continue;
+                    }
+                    if JND - delta < EPSILON {
+                        break;
+                    }
+                    min_in_gamut = false;
+                    min = chroma;
+                }
+            }
+            clipped
+        };
+        mapped.to_color_space(target)
+    }
+}
+
+/// Contrast, as defined by WCAG 2.1.
+///
+/// <https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio>
+impl AbsoluteColor {
+    /// Returns the relative luminance of this color, ignoring its alpha.
+    ///
+    /// <https://www.w3.org/TR/WCAG21/#dfn-relative-luminance>
+    pub fn relative_luminance(&self) -> f32 {
+        let linear = self
+            .map_to_gamut(ColorSpace::Srgb)
+            .to_color_space(ColorSpace::SrgbLinear);
+        let component = |c: Option<f32>| c.unwrap_or(0.0).clamp(0.0, 1.0);
+        0.2126 * component(linear.c0()) +
+            0.7152 * component(linear.c1()) +
+            0.0722 * component(linear.c2())
+    }
+
+    /// Returns the contrast ratio between this color and another one, from 1 to 21.
+    pub fn contrast_ratio(&self, other: &Self) -> f32 {
+        let one = self.relative_luminance();
+        let other = other.relative_luminance();
+        (one.max(other) + 0.05) / (one.min(other) + 0.05)
+    }
+
+    /// Returns whichever of white or black contrasts the most with this color, preferring white
+    /// on a tie, as used by contrast-color().
+    ///
+    /// <https://drafts.csswg.org/css-color-5/#contrast-color>
+    pub fn contrast_color(&self) -> Self {
+        if Self::WHITE.contrast_ratio(self) >= Self::BLACK.contrast_ratio(self) {
+            Self::WHITE
+        } else {
+            Self::BLACK
+        }
+    }
+}
+
 impl From<PredefinedColorSpace> for ColorSpace {
     fn from(value: PredefinedColorSpace) -> Self {
         match value {
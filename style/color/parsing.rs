@@ -9,6 +9,7 @@
 use super::{
     color_function::ColorFunction,
     component::{ColorComponent, ColorComponentType},
+    profile::ProfiledColor,
     AbsoluteColor,
 };
 use crate::{
@@ -16,6 +17,7 @@ use crate::{
     values::{
         generics::{calc::CalcUnits, Optional},
         specified::{angle::Angle as SpecifiedAngle, calc::Leaf, color::Color as SpecifiedColor},
+        DashedIdent,
     },
 };
 use cssparser::{
@@ -30,6 +32,12 @@ pub fn rcs_enabled() -> bool {
     static_prefs::pref!("layout.css.relative-color-syntax.enabled")
 }
 
+/// Returns true if the `@color-profile` rule and custom color spaces are enabled.
+#[inline]
+pub fn color_profile_enabled() -> bool {
+    static_prefs::pref!("layout.css.color-profile.enabled")
+}
+
 /// Represents a channel keyword inside a color.
 #[derive(Clone, Copy, Debug, MallocSizeOf, Parse, PartialEq, PartialOrd, ToCss, ToShmem)]
 #[repr(u8)]
@@ -98,6 +106,13 @@ pub fn parse_color_with<'i, 't>(
         Token::Function(ref name) => {
             let name = name.clone();
             return input.parse_nested_block(|arguments| {
+                if color_profile_enabled() && name.eq_ignore_ascii_case("color") {
+                    if let Ok(profile) = arguments.try_parse(|i| DashedIdent::parse(context, i)) {
+                        let color = parse_color_with_profile(context, arguments, profile)?;
+                        return Ok(SpecifiedColor::Profiled(Box::new(color)));
+                    }
+                }
+
                 let color_function = parse_color_function(context, name, arguments)?;
 
                 if color_function.has_origin_color() {
@@ -360,6 +375,39 @@ fn parse_color_with_color_space<'i, 't>(
     ))
 }
 
+/// Parse the arguments of the color() function with a custom color space, after its name.
+fn parse_color_with_profile<'i, 't>(
+    context: &ParserContext,
+    arguments: &mut Parser<'i, 't>,
+    profile: DashedIdent,
+) -> Result<ProfiledColor, ParseError<'i>> {
+    // The number of components depends on the profile, which isn't known until it is loaded.
+    let mut components = vec![parse_number_or_percentage(context, arguments, true)?];
+    while let Ok(component) =
+        arguments.try_parse(|i| parse_number_or_percentage(context, i, true))
+    {
+        components.push(component);
+    }
+
+    let alpha = parse_modern_alpha(context, arguments)?;
+    arguments.expect_exhausted()?;
+
+    // Validate the calc expressions, which can't refer to an origin color.
+    if components
+        .iter()
+        .chain(std::iter::once(&alpha))
+        .any(|component| component.resolve(None).is_err())
+    {
+        return Err(arguments.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+    }
+
+    Ok(ProfiledColor {
+        profile,
+        components: components.into(),
+        alpha,
+    })
+}
+
 /// Either a percentage or a number.
 #[derive(Clone, Copy, Debug, MallocSizeOf, PartialEq, ToAnimatedValue, ToShmem)]
 #[repr(u8)]
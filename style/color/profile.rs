@@ -0,0 +1,475 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Custom color spaces, described by ICC profiles and used through `@color-profile` rules and
+//! `color(--name ...)`.
+//!
+//! The profile data is fetched by the embedder, which hands it to [`ColorProfile::from_icc`].
+//! Only matrix/TRC profiles are supported, that is, RGB profiles made of three tone curves and a
+//! matrix to the profile connection space, and gray profiles made of a single tone curve. This
+//! covers most display, camera and working space profiles. LUT-based profiles, like most CMYK
+//! ones, are rejected.
+//!
+//! <https://drafts.csswg.org/css-color-5/#at-profile>
+
+use super::component::ColorComponent;
+use super::parsing::NumberOrPercentageComponent;
+use super::{AbsoluteColor, ColorSpace};
+use crate::values::{normalize, DashedIdent};
+use cssparser::color::OPAQUE;
+use std::fmt::{self, Write};
+use style_traits::{CssWriter, ToCss};
+
+/// The reasons why an ICC profile can't be used as a color profile.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorProfileError {
+    /// The data is not a well-formed ICC profile.
+    Invalid,
+    /// The profile is well-formed, but it is not a matrix/TRC profile.
+    Unsupported,
+}
+
+/// A tone reproduction curve, mapping an encoded component to a linear one.
+///
+/// <https://www.color.org/specification/ICC.1-2022-05.pdf>, sections 10.6 and 10.18.
+#[derive(Clone, Debug, PartialEq)]
+enum ToneCurve {
+    /// `Y = X ^ gamma`.
+    Gamma(f32),
+    /// Equally spaced samples over [0, 1], linearly interpolated.
+    Table(Box<[f32]>),
+    /// One of the five parametric curve functions, with its parameters in the g, a, b, c, d, e,
+    /// f order.
+    Parametric(u16, [f32; 7]),
+}
+
+impl ToneCurve {
+    fn parse(data: &[u8]) -> Result<Self, ColorProfileError> {
+        match data.get(0..4) {
+            Some(b"curv") => {
+                let count = read_u32(data, 8)? as usize;
+                match count {
+                    0 => Ok(ToneCurve::Gamma(1.0)),
+                    1 => Ok(ToneCurve::Gamma(read_u16(data, 12)? as f32 / 256.0)),
+                    _ => {
+                        let samples = (0..count)
+                            .map(|i| Ok(read_u16(data, 12 + 2 * i)? as f32 / 65535.0))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        Ok(ToneCurve::Table(samples.into_boxed_slice()))
+                    },
+                }
+            },
+            Some(b"para") => {
+                let function = read_u16(data, 8)?;
+                let param_count = match function {
+                    0 => 1,
+                    1 => 3,
+                    2 => 4,
+                    3 => 5,
+                    4 => 7,
+                    _ => return Err(ColorProfileError::Invalid),
+                };
+                let mut params = [0.0; 7];
+                for (i, param) in params.iter_mut().enumerate().take(param_count) {
+                    *param = read_s15_fixed16(data, 12 + 4 * i)?;
+                }
+                Ok(ToneCurve::Parametric(function, params))
+            },
+            _ => Err(ColorProfileError::Invalid),
+        }
+    }
+
+    /// Maps an encoded value to a linear one. Values outside of [0, 1] are extended
+    /// symmetrically for functions, and clamped for sampled curves.
+    fn apply(&self, value: f32) -> f32 {
+        let magnitude = value.abs();
+        let result = match *self {
+            ToneCurve::Gamma(gamma) => magnitude.powf(gamma),
+            ToneCurve::Table(ref samples) => {
+                let position = value.clamp(0.0, 1.0) * (samples.len() - 1) as f32;
+                let index = position.floor() as usize;
+                let next = (index + 1).min(samples.len() - 1);
+                let fraction = position - index as f32;
+                return samples[index] + (samples[next] - samples[index]) * fraction;
+            },
+            ToneCurve::Parametric(function, [g, a, b, c, d, e, f]) => {
+                let x = magnitude;
+                let power = |x: f32| (a * x + b).max(0.0).powf(g);
+                match function {
+                    0 => x.powf(g),
+                    1 if x >= -b / a => power(x),
+                    1 => 0.0,
+                    2 if x >= -b / a => power(x) + c,
+                    2 => c,
+                    3 if x >= d => power(x),
+                    3 => c * x,
+                    _ if x >= d => power(x) + e,
+                    _ => c * x + f,
+                }
+            },
+        };
+        result.copysign(value)
+    }
+}
+
+/// How the profile maps its components to the profile connection space.
+#[derive(Clone, Debug, PartialEq)]
+enum Transform {
+    /// Three tone curves, followed by a matrix whose columns are the XYZ values of the red,
+    /// green and blue colorants.
+    Rgb([ToneCurve; 3], [[f32; 3]; 3]),
+    /// A single tone curve, scaling the white point of the profile connection space.
+    Gray(ToneCurve, [f32; 3]),
+}
+
+/// A color profile that components of a custom color space can be converted from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorProfile {
+    transform: Transform,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, ColorProfileError> {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+        None => Err(ColorProfileError::Invalid),
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, ColorProfileError> {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err(ColorProfileError::Invalid),
+    }
+}
+
+fn read_s15_fixed16(data: &[u8], offset: usize) -> Result<f32, ColorProfileError> {
+    Ok(read_u32(data, offset)? as i32 as f32 / 65536.0)
+}
+
+fn read_xyz(data: &[u8], offset: usize) -> Result<[f32; 3], ColorProfileError> {
+    Ok([
+        read_s15_fixed16(data, offset)?,
+        read_s15_fixed16(data, offset + 4)?,
+        read_s15_fixed16(data, offset + 8)?,
+    ])
+}
+
+impl ColorProfile {
+    /// Reads a color profile from the contents of an ICC profile.
+    pub fn from_icc(data: &[u8]) -> Result<Self, ColorProfileError> {
+        const HEADER_SIZE: usize = 128;
+        const TAG_ENTRY_SIZE: usize = 12;
+
+        if data.len() < HEADER_SIZE || &data[36..40] != b"acsp" {
+            return Err(ColorProfileError::Invalid);
+        }
+        // Matrix/TRC profiles always connect through XYZ.
+        if &data[20..24] != b"XYZ " {
+            return Err(ColorProfileError::Unsupported);
+        }
+
+        let tag_count = read_u32(data, HEADER_SIZE)? as usize;
+        if tag_count > (data.len() - HEADER_SIZE - 4) / TAG_ENTRY_SIZE {
+            return Err(ColorProfileError::Invalid);
+        }
+        let find_tag = |signature: &[u8; 4]| -> Option<Result<&[u8], ColorProfileError>> {
+            (0..tag_count).find_map(|i| {
+                let entry = HEADER_SIZE + 4 + i * TAG_ENTRY_SIZE;
+                if data.get(entry..entry + 4)? != &signature[..] {
+                    return None;
+                }
+                let tag = read_u32(data, entry + 4).and_then(|offset| {
+                    let start = offset as usize;
+                    let size = read_u32(data, entry + 8)? as usize;
+                    start
+                        .checked_add(size)
+                        .and_then(|end| data.get(start..end))
+                        .ok_or(ColorProfileError::Invalid)
+                });
+                Some(tag)
+            })
+        };
+        let curve = |signature: &[u8; 4]| match find_tag(signature) {
+            Some(tag) => ToneCurve::parse(tag?),
+            None => Err(ColorProfileError::Unsupported),
+        };
+        let colorant = |signature: &[u8; 4]| match find_tag(signature) {
+            Some(tag) => {
+                let tag = tag?;
+                if tag.get(0..4) != Some(&b"XYZ "[..]) {
+                    return Err(ColorProfileError::Invalid);
+                }
+                read_xyz(tag, 8)
+            },
+            None => Err(ColorProfileError::Unsupported),
+        };
+
+        let transform = match &data[16..20] {
+            b"RGB " => {
+                let curves = [curve(b"rTRC")?, curve(b"gTRC")?, curve(b"bTRC")?];
+                let red = colorant(b"rXYZ")?;
+                let green = colorant(b"gXYZ")?;
+                let blue = colorant(b"bXYZ")?;
+                let matrix = [
+                    [red[0], green[0], blue[0]],
+                    [red[1], green[1], blue[1]],
+                    [red[2], green[2], blue[2]],
+                ];
+                Transform::Rgb(curves, matrix)
+            },
+            b"GRAY" => Transform::Gray(curve(b"kTRC")?, read_xyz(data, 68)?),
+            _ => return Err(ColorProfileError::Unsupported),
+        };
+        Ok(Self { transform })
+    }
+
+    /// Returns the number of components of colors in this profile.
+    pub fn component_count(&self) -> usize {
+        match self.transform {
+            Transform::Rgb(..) => 3,
+            Transform::Gray(..) => 1,
+        }
+    }
+
+    /// Converts the components of a color in this profile to XYZ, relative to a D50 white
+    /// point. Missing components are treated as zero, and extra ones are ignored.
+    pub fn to_xyz_d50(&self, components: &[f32]) -> [f32; 3] {
+        let component = |i: usize| components.get(i).copied().unwrap_or(0.0);
+        match self.transform {
+            Transform::Rgb(ref curves, ref matrix) => {
+                let linear = [
+                    curves[0].apply(component(0)),
+                    curves[1].apply(component(1)),
+                    curves[2].apply(component(2)),
+                ];
+                let row = |r: &[f32; 3]| r[0] * linear[0] + r[1] * linear[1] + r[2] * linear[2];
+                [row(&matrix[0]), row(&matrix[1]), row(&matrix[2])]
+            },
+            Transform::Gray(ref curve, ref white) => {
+                let linear = curve.apply(component(0));
+                [white[0] * linear, white[1] * linear, white[2] * linear]
+            },
+        }
+    }
+}
+
+/// A color in a custom color space, specified with `color(--name ...)`.
+///
+/// This can't be resolved until the profile named by the `@color-profile` rule has been loaded,
+/// so it is kept as-is until computed-value time.
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+pub struct ProfiledColor {
+    /// The name of the `@color-profile` rule.
+    pub profile: DashedIdent,
+    /// The components of the color, interpreted by the profile.
+    pub components: crate::OwnedSlice<ColorComponent<NumberOrPercentageComponent>>,
+    /// The alpha component.
+    pub alpha: ColorComponent<NumberOrPercentageComponent>,
+}
+
+impl ProfiledColor {
+    /// Resolves the color with the loaded profile, if any. Colors whose profile isn't available
+    /// are opaque black.
+    pub fn resolve(&self, profile: Option<&ColorProfile>) -> AbsoluteColor {
+        let Some(profile) = profile else {
+            return AbsoluteColor::BLACK;
+        };
+        let components = self
+            .components
+            .iter()
+            .map(|c| match c.resolve(None) {
+                Ok(Some(value)) => normalize(value.to_number(1.0)),
+                _ => 0.0,
+            })
+            .collect::<Vec<_>>();
+        let alpha = match self.alpha.resolve(None) {
+            Ok(alpha) => alpha.map(|a| normalize(a.to_number(OPAQUE)).clamp(0.0, OPAQUE)),
+            Err(()) => Some(OPAQUE),
+        };
+        let [x, y, z] = profile.to_xyz_d50(&components);
+        AbsoluteColor::new(ColorSpace::XyzD50, x, y, z, alpha)
+    }
+}
+
+impl ToCss for ProfiledColor {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        dest.write_str("color(")?;
+        self.profile.to_css(dest)?;
+        for component in self.components.iter() {
+            dest.write_char(' ')?;
+            component.to_css(dest)?;
+        }
+        let is_opaque = match self.alpha {
+            ColorComponent::Value(ref value) => value.to_number(OPAQUE) == OPAQUE,
+            ColorComponent::AlphaOmitted => true,
+            _ => false,
+        };
+        if !is_opaque {
+            dest.write_str(" / ")?;
+            self.alpha.to_css(dest)?;
+        }
+        dest.write_char(')')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const D50: [f32; 3] = [0.9642, 1.0, 0.8249];
+
+    fn s15_fixed16(value: f32) -> [u8; 4] {
+        ((value * 65536.0).round() as i32).to_be_bytes()
+    }
+
+    fn xyz_tag(xyz: [f32; 3]) -> Vec<u8> {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        for value in xyz {
+            tag.extend_from_slice(&s15_fixed16(value));
+        }
+        tag
+    }
+
+    fn curve_tag(count: u32, samples: &[u16]) -> Vec<u8> {
+        let mut tag = b"curv\0\0\0\0".to_vec();
+        tag.extend_from_slice(&count.to_be_bytes());
+        for sample in samples {
+            tag.extend_from_slice(&sample.to_be_bytes());
+        }
+        tag
+    }
+
+    fn gamma_tag(gamma: f32) -> Vec<u8> {
+        curve_tag(1, &[(gamma * 256.0) as u16])
+    }
+
+    /// Builds a profile with the given data color space and tags, laid out after the tag table.
+    fn profile(color_space: &[u8; 4], tags: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0; 128];
+        data[16..20].copy_from_slice(color_space);
+        data[20..24].copy_from_slice(b"XYZ ");
+        data[36..40].copy_from_slice(b"acsp");
+        for (i, value) in D50.iter().enumerate() {
+            data[68 + 4 * i..72 + 4 * i].copy_from_slice(&s15_fixed16(*value));
+        }
+        data.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+        let mut offset = data.len() + 12 * tags.len();
+        for &(signature, ref tag) in tags {
+            data.extend_from_slice(signature);
+            data.extend_from_slice(&(offset as u32).to_be_bytes());
+            data.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+            offset += tag.len();
+        }
+        for &(_, ref tag) in tags {
+            data.extend_from_slice(tag);
+        }
+        data
+    }
+
+    const RED: [f32; 3] = [0.4361, 0.2225, 0.0139];
+    const GREEN: [f32; 3] = [0.3851, 0.7169, 0.0971];
+    const BLUE: [f32; 3] = [0.1431, 0.0606, 0.7141];
+
+    fn rgb_profile(red_curve: Vec<u8>) -> Vec<u8> {
+        profile(
+            b"RGB ",
+            &[
+                (b"rXYZ", xyz_tag(RED)),
+                (b"gXYZ", xyz_tag(GREEN)),
+                (b"bXYZ", xyz_tag(BLUE)),
+                (b"gTRC", gamma_tag(1.0)),
+                (b"bTRC", gamma_tag(1.0)),
+                (b"rTRC", red_curve),
+            ],
+        )
+    }
+
+    fn assert_xyz_eq(actual: [f32; 3], expected: [f32; 3]) {
+        for (actual, expected) in actual.iter().zip(expected.iter()) {
+            assert!(
+                (actual - expected).abs() < 1e-4,
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn rgb_matrix_trc() {
+        let profile = ColorProfile::from_icc(&rgb_profile(gamma_tag(2.0))).unwrap();
+        assert_eq!(profile.component_count(), 3);
+        let white = profile.to_xyz_d50(&[1.0, 1.0, 1.0]);
+        let expected_white = [0, 1, 2].map(|i| RED[i] + GREEN[i] + BLUE[i]);
+        assert_xyz_eq(white, expected_white);
+        // The red curve squares its input, the others are linear.
+        let color = profile.to_xyz_d50(&[0.5, 0.5, 0.0]);
+        let expected = [0, 1, 2].map(|i| RED[i] * 0.25 + GREEN[i] * 0.5);
+        assert_xyz_eq(color, expected);
+    }
+
+    #[test]
+    fn gray() {
+        let profile =
+            ColorProfile::from_icc(&profile(b"GRAY", &[(b"kTRC", gamma_tag(1.0))])).unwrap();
+        assert_eq!(profile.component_count(), 1);
+        assert_xyz_eq(profile.to_xyz_d50(&[0.5]), D50.map(|value| value * 0.5));
+    }
+
+    #[test]
+    fn truncated_or_out_of_range_tags() {
+        let data = rgb_profile(gamma_tag(1.0));
+        assert_eq!(
+            ColorProfile::from_icc(&data[..100]),
+            Err(ColorProfileError::Invalid)
+        );
+        // The last tag doesn't fit in the data anymore.
+        assert_eq!(
+            ColorProfile::from_icc(&data[..data.len() - 1]),
+            Err(ColorProfileError::Invalid)
+        );
+
+        let mut data = rgb_profile(gamma_tag(1.0));
+        // Point the first tag past the end of the data.
+        data[136..140].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(
+            ColorProfile::from_icc(&data),
+            Err(ColorProfileError::Invalid)
+        );
+
+        let mut data = rgb_profile(gamma_tag(1.0));
+        // More tags than the tag table can hold.
+        data[128..132].copy_from_slice(&1000u32.to_be_bytes());
+        assert_eq!(
+            ColorProfile::from_icc(&data),
+            Err(ColorProfileError::Invalid)
+        );
+    }
+
+    #[test]
+    fn oversized_curve_count() {
+        let data = rgb_profile(curve_tag(u32::MAX, &[0, 65535]));
+        assert_eq!(
+            ColorProfile::from_icc(&data),
+            Err(ColorProfileError::Invalid)
+        );
+    }
+
+    #[test]
+    fn lut_profile() {
+        let data = profile(b"RGB ", &[(b"A2B0", b"mft2\0\0\0\0".to_vec())]);
+        assert_eq!(
+            ColorProfile::from_icc(&data),
+            Err(ColorProfileError::Unsupported)
+        );
+        let data = profile(b"CMYK", &[(b"A2B0", b"mft2\0\0\0\0".to_vec())]);
+        assert_eq!(
+            ColorProfile::from_icc(&data),
+            Err(ColorProfileError::Unsupported)
+        );
+    }
+}
@@ -9,27 +9,39 @@
 use crate::applicable_declarations::CascadePriority;
 use crate::custom_properties_map::CustomPropertiesMap;
 use crate::media_queries::Device;
+use crate::computed_value_flags::ComputedValueFlags;
 use crate::properties::{
     CSSWideKeyword, CustomDeclaration, CustomDeclarationValue, LonghandId, LonghandIdSet,
-    PropertyDeclaration,
+    PropertyDeclaration, PropertyId,
 };
 use crate::properties_and_values::{
     registry::PropertyRegistrationData,
-    syntax::data_type::DependentDataTypes,
+    syntax::{data_type::DependentDataTypes, Descriptor},
     value::{
         AllowComputationallyDependent, ComputedValue as ComputedRegisteredValue,
         SpecifiedValue as SpecifiedRegisteredValue,
     },
 };
+use crate::parser::ParserContext;
+use crate::queries::condition::Operator;
+use crate::queries::{FeatureType, QueryCondition};
 use crate::selector_map::{PrecomputedHashMap, PrecomputedHashSet};
-use crate::stylesheets::UrlExtraData;
+use crate::stylesheets::supports_rule::SupportsCondition;
+use crate::stylesheets::{Origin, UrlExtraData};
 use crate::stylist::Stylist;
+use crate::values::computed::random::RandomValueProvider;
 use crate::values::computed::{self, ToComputedValue};
-use crate::values::specified::FontRelativeLength;
-use crate::Atom;
+use crate::values::specified::calc::{
+    query_progress_feature_type, MathFunction, RandomValueSharing, TreeCountingFunction,
+};
+use crate::values::CSSFloat;
+use crate::values::specified::{Angle, FontRelativeLength, NoCalcLength, Resolution, Time};
+use crate::{Atom, LocalName};
+use malloc_size_of::{MallocShallowSizeOf, MallocSizeOf, MallocSizeOfOps};
 use cssparser::{
     CowRcStr, Delimiter, Parser, ParserInput, SourcePosition, Token, TokenSerializationType,
 };
+use selectors::kleene_value::KleeneValue;
 use selectors::parser::SelectorParseErrorKind;
 use servo_arc::Arc;
 use smallvec::SmallVec;
@@ -38,14 +50,27 @@ use std::collections::hash_map::Entry;
 use std::fmt::{self, Write};
 use std::ops::{Index, IndexMut};
 use std::{cmp, num};
-use style_traits::{CssWriter, ParseError, StyleParseErrorKind, ToCss};
+use style_traits::{CssWriter, ParseError, ParsingMode, StyleParseErrorKind, ToCss};
 
 /// The environment from which to get `env` function values.
 ///
 /// TODO(emilio): If this becomes a bit more complex we should probably move it
 /// to the `media_queries` module, or something.
-#[derive(Debug, MallocSizeOf)]
-pub struct CssEnvironment;
+#[derive(Debug, Default)]
+pub struct CssEnvironment {
+    /// Lengths, in CSS pixels, of the environment variables provided by the
+    /// embedder at runtime, like `safe-area-inset-top`, `keyboard-inset-height`
+    /// or `titlebar-area-width`.
+    embedder_lengths: PrecomputedHashMap<Atom, f32>,
+}
+
+impl MallocSizeOf for CssEnvironment {
+    fn size_of(&self, ops: &mut MallocSizeOfOps) -> usize {
+        // Atoms are shared and lengths are plain floats, so only the table
+        // itself needs measuring.
+        self.embedder_lengths.shallow_size_of(ops)
+    }
+}
 
 type EnvironmentEvaluator = fn(device: &Device, url_data: &UrlExtraData) -> VariableValue;
 
@@ -98,9 +123,15 @@ fn get_content_preferred_color_scheme(device: &Device, url_data: &UrlExtraData)
 }
 
 #[cfg(feature = "servo")]
-fn get_content_preferred_color_scheme(_device: &Device, url_data: &UrlExtraData) -> VariableValue {
-    // TODO: Add an implementation for Servo.
-    VariableValue::ident("light", url_data)
+fn get_content_preferred_color_scheme(device: &Device, url_data: &UrlExtraData) -> VariableValue {
+    use crate::queries::values::PrefersColorScheme;
+    VariableValue::ident(
+        match device.color_scheme() {
+            PrefersColorScheme::Light => "light",
+            PrefersColorScheme::Dark => "dark",
+        },
+        url_data,
+    )
 }
 
 fn get_scrollbar_inline_size(device: &Device, url_data: &UrlExtraData) -> VariableValue {
@@ -186,11 +217,41 @@ static CHROME_ENVIRONMENT_VARIABLES: [EnvironmentVariable; 9] = [
 ];
 
 impl CssEnvironment {
+    /// Returns the length, in CSS pixels, that the embedder provided for a
+    /// given environment variable, if any.
+    #[inline]
+    pub fn embedder_length(&self, name: &Atom) -> Option<f32> {
+        self.embedder_lengths.get(name).copied()
+    }
+
+    /// Sets the length, in CSS pixels, of a given environment variable.
+    ///
+    /// Returns whether the value changed, in which case the embedder should
+    /// restyle the elements that depend on environment variables, see
+    /// `invalidation::flags::invalidate_for_flag`. Non-finite values are
+    /// ignored.
+    pub fn set_embedder_length(&mut self, name: Atom, value: f32) -> bool {
+        if !value.is_finite() {
+            return false;
+        }
+        self.embedder_lengths.insert(name, value) != Some(value)
+    }
+
+    /// Removes the embedder-provided value of a given environment variable.
+    ///
+    /// Returns whether there was a value to remove.
+    pub fn remove_embedder_length(&mut self, name: &Atom) -> bool {
+        self.embedder_lengths.remove(name).is_some()
+    }
+
     #[inline]
     fn get(&self, name: &Atom, device: &Device, url_data: &UrlExtraData) -> Option<VariableValue> {
         if let Some(var) = ENVIRONMENT_VARIABLES.iter().find(|var| var.name == *name) {
             return Some((var.evaluator)(device, url_data));
         }
+        if let Some(length) = self.embedder_length(name) {
+            return Some(VariableValue::pixels(length, url_data));
+        }
         if !url_data.chrome_rules_enabled() {
             return None;
         }
@@ -234,14 +295,21 @@ pub struct VariableValue {
 
     /// var(), env(), or non-custom property (e.g. through `em`) references.
     references: References,
+
+    /// Whether this value was substituted, directly or not, from an `attr()` function. We track
+    /// this per value rather than per token, which is stricter than the spec, but safe.
+    ///
+    /// <https://drafts.csswg.org/css-values-5/#attr-security>
+    attr_tainted: bool,
 }
 
 trivial_to_computed_value!(VariableValue);
 
-// For all purposes, we want values to be considered equal if their css text is equal.
+// For all purposes, we want values to be considered equal if their css text is equal, as long as
+// they can be used in the same places.
 impl PartialEq for VariableValue {
     fn eq(&self, other: &Self) -> bool {
-        self.css == other.css
+        self.css == other.css && self.attr_tainted == other.attr_tainted
     }
 }
 
@@ -285,7 +353,7 @@ impl ComputedCustomProperties {
 
     /// Insert a custom property in the corresponding inherited/non_inherited
     /// map, depending on whether the inherit flag is set or unset.
-    fn insert(
+    pub(crate) fn insert(
         &mut self,
         registration: &PropertyRegistrationData,
         name: &Name,
@@ -296,7 +364,7 @@ impl ComputedCustomProperties {
 
     /// Remove a custom property from the corresponding inherited/non_inherited
     /// map, depending on whether the inherit flag is set or unset.
-    fn remove(&mut self, registration: &PropertyRegistrationData, name: &Name) {
+    pub(crate) fn remove(&mut self, registration: &PropertyRegistrationData, name: &Name) {
         self.map_mut(registration).remove(name);
     }
 
@@ -444,25 +512,145 @@ struct VariableFallback {
     last_token_type: TokenSerializationType,
 }
 
+/// The kind of arbitrary substitution function a reference comes from, along with the data
+/// specific to it.
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+enum SubstitutionFunctionKind {
+    Var,
+    Env,
+    Attr {
+        /// How the value of the attribute is interpreted.
+        attribute_type: AttributeType,
+    },
+    If {
+        /// The branches of the function.
+        branches: Box<[IfBranch]>,
+    },
+    RandomItem {
+        /// The caching options of the function.
+        sharing: RandomValueSharing,
+        /// The items to pick from.
+        items: Box<[RandomItem]>,
+    },
+}
+
+/// How the value of an attribute referenced by `attr()` is interpreted.
+///
+/// <https://drafts.csswg.org/css-values-5/#attr-types>
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+enum AttributeType {
+    /// No type was specified, the value is substituted as a CSS string.
+    String,
+    /// `raw-string`: the value is substituted as-is, as a list of tokens.
+    RawString,
+    /// `type(<syntax>)`: the value is parsed and computed against the syntax.
+    Type(Box<Descriptor>),
+    /// `<attr-unit>`: the value is parsed as a number, and the unit appended.
+    Unit(Box<str>),
+}
+
+/// A single test in the condition of an `if()` branch.
+///
+/// <https://drafts.csswg.org/css-values-5/#typedef-if-test>
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+enum IfTest {
+    /// `style(--name)` or `style(--name: value)`.
+    Style {
+        name: Name,
+        value: Option<Box<str>>,
+    },
+    /// `media(<media-query>)`.
+    Media(QueryCondition),
+    /// `supports(<supports-condition>)`, which is evaluated at parse time, since its result
+    /// doesn't depend on the element.
+    Supports(bool),
+}
+
+/// The condition of an `if()` branch.
+///
+/// <https://drafts.csswg.org/css-values-5/#typedef-if-condition>
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+enum IfCondition {
+    /// The `else` keyword, which always matches.
+    Else,
+    /// A single test.
+    Test(IfTest),
+    /// A negated condition.
+    Not(Box<IfCondition>),
+    /// A set of conditions joined by `and` or `or`.
+    Operation(Box<[IfCondition]>, Operator),
+    /// An unknown condition, which never matches.
+    GeneralEnclosed,
+}
+
+/// A branch of an `if()` function, with the position of its value in the
+/// declaration's CSS.
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+struct IfBranch {
+    condition: IfCondition,
+    start: usize,
+    end: usize,
+    first_token_type: TokenSerializationType,
+    last_token_type: TokenSerializationType,
+}
+
+/// An item of a `random-item()` function, with its position in the declaration's CSS.
 #[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
-struct VarOrEnvReference {
+struct RandomItem {
+    start: usize,
+    end: usize,
+    first_token_type: TokenSerializationType,
+    last_token_type: TokenSerializationType,
+}
+
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+struct SubstitutionFunctionReference {
     name: Name,
     start: usize,
     end: usize,
     fallback: Option<VariableFallback>,
     prev_token_type: TokenSerializationType,
     next_token_type: TokenSerializationType,
-    is_var: bool,
+    kind: SubstitutionFunctionKind,
+}
+
+impl SubstitutionFunctionReference {
+    fn is_var(&self) -> bool {
+        matches!(self.kind, SubstitutionFunctionKind::Var)
+    }
+
+    /// Calls `f` with the name of each custom property this reference depends
+    /// on, either via `var()` or via `style()` tests in `if()` conditions.
+    fn each_custom_property_dependency(&self, mut f: impl FnMut(&Name)) {
+        match self.kind {
+            SubstitutionFunctionKind::Var => f(&self.name),
+            SubstitutionFunctionKind::If { ref branches } => {
+                for branch in branches.iter() {
+                    branch.condition.each_style_test_name(&mut f);
+                }
+            },
+            SubstitutionFunctionKind::Env |
+            SubstitutionFunctionKind::Attr { .. } |
+            SubstitutionFunctionKind::RandomItem { .. } => {},
+        }
+    }
 }
 
 /// A struct holding information about the external references to that a custom
 /// property value may have.
 #[derive(Clone, Debug, Default, MallocSizeOf, PartialEq, ToShmem)]
 struct References {
-    refs: Vec<VarOrEnvReference>,
+    refs: Vec<SubstitutionFunctionReference>,
     non_custom_references: NonCustomReferences,
     any_env: bool,
     any_var: bool,
+    any_attr: bool,
+    any_tree_counting: bool,
+    any_random: bool,
+    any_query_progress: bool,
+    any_relative_length_in_math: bool,
+    /// How many math functions we're nested in, only non-zero while parsing.
+    math_function_depth: u8,
 }
 
 impl References {
@@ -487,6 +675,7 @@ impl VariableValue {
             first_token_type: Default::default(),
             url_data: url_data.clone(),
             references: Default::default(),
+            attr_tainted: false,
         }
     }
 
@@ -504,6 +693,7 @@ impl VariableValue {
             first_token_type,
             last_token_type,
             references: Default::default(),
+            attr_tainted: false,
         }
     }
 
@@ -551,7 +741,7 @@ impl VariableValue {
     /// Parse a custom property value.
     pub fn parse<'i, 't>(
         input: &mut Parser<'i, 't>,
-        url_data: &UrlExtraData,
+        context: &ParserContext,
     ) -> Result<Self, ParseError<'i>> {
         input.skip_whitespace();
 
@@ -559,6 +749,7 @@ impl VariableValue {
         let mut missing_closing_characters = String::new();
         let start_position = input.position();
         let (first_token_type, last_token_type) = parse_declaration_value(
+            context,
             input,
             start_position,
             &mut references,
@@ -580,10 +771,11 @@ impl VariableValue {
 
         Ok(Self {
             css,
-            url_data: url_data.clone(),
+            url_data: context.url_data.clone(),
             first_token_type,
             last_token_type,
             references,
+            attr_tainted: false,
         })
     }
 
@@ -657,6 +849,7 @@ impl VariableValue {
             first_token_type: token_type,
             last_token_type: token_type,
             references: Default::default(),
+            attr_tainted: false,
         }
     }
 
@@ -670,22 +863,87 @@ impl VariableValue {
     pub fn has_references(&self) -> bool {
         self.references.has_references()
     }
+
+    /// Returns whether this value contains any `sibling-index()` or `sibling-count()` function.
+    pub fn uses_tree_counting_functions(&self) -> bool {
+        self.references.any_tree_counting
+    }
+
+    /// Returns whether this value contains any `random()` function.
+    pub fn uses_random_functions(&self) -> bool {
+        self.references.any_random
+    }
+
+    /// Returns whether this value contains any `media-progress()` or `container-progress()`
+    /// function.
+    pub fn uses_query_progress_functions(&self) -> bool {
+        self.references.any_query_progress
+    }
+
+    /// Returns whether this value contains any relative length in a math function, which may be
+    /// divided into another type, e.g. `calc(1em / 1px)`.
+    pub fn uses_relative_lengths_in_math_functions(&self) -> bool {
+        self.references.any_relative_length_in_math
+    }
+
+    /// Returns whether this value was substituted from an `attr()` function, in which case it
+    /// can't be used as a URL.
+    pub fn is_attr_tainted(&self) -> bool {
+        self.attr_tainted
+    }
+
+    /// Marks this value as substituted, or not, from an `attr()` function.
+    pub(crate) fn set_attr_tainted(&mut self, attr_tainted: bool) {
+        self.attr_tainted = attr_tainted;
+    }
+
+    /// Calls `f` with each `media()` condition of the `if()` functions in this value.
+    pub fn each_if_media_condition(&self, mut f: impl FnMut(&QueryCondition)) {
+        for reference in &self.references.refs {
+            if let SubstitutionFunctionKind::If { ref branches } = reference.kind {
+                for branch in branches.iter() {
+                    branch.condition.each_media_condition(&mut f);
+                }
+            }
+        }
+    }
+
+    /// Calls `f` with the name of each attribute referenced from an `attr()` function in this
+    /// value.
+    pub fn each_referenced_attribute(&self, mut f: impl FnMut(&Atom)) {
+        if !self.references.any_attr {
+            return;
+        }
+        for reference in &self.references.refs {
+            if let SubstitutionFunctionKind::Attr { .. } = reference.kind {
+                f(&reference.name);
+            }
+        }
+    }
 }
 
 /// <https://drafts.csswg.org/css-syntax-3/#typedef-declaration-value>
 fn parse_declaration_value<'i, 't>(
+    context: &ParserContext,
     input: &mut Parser<'i, 't>,
     input_start: SourcePosition,
     references: &mut References,
     missing_closing_characters: &mut String,
 ) -> Result<(TokenSerializationType, TokenSerializationType), ParseError<'i>> {
     input.parse_until_before(Delimiter::Bang | Delimiter::Semicolon, |input| {
-        parse_declaration_value_block(input, input_start, references, missing_closing_characters)
+        parse_declaration_value_block(
+            context,
+            input,
+            input_start,
+            references,
+            missing_closing_characters,
+        )
     })
 }
 
 /// Like parse_declaration_value, but accept `!` and `;` since they are only invalid at the top level.
 fn parse_declaration_value_block<'i, 't>(
+    context: &ParserContext,
     input: &mut Parser<'i, 't>,
     input_start: SourcePosition,
     references: &mut References,
@@ -713,6 +971,7 @@ fn parse_declaration_value_block<'i, 't>(
             () => {
                 input.parse_nested_block(|input| {
                     parse_declaration_value_block(
+                        context,
                         input,
                         input_start,
                         references,
@@ -762,15 +1021,113 @@ fn parse_declaration_value_block<'i, 't>(
                 let e = StyleParseErrorKind::UnbalancedCloseCurlyBracketInDeclarationValueBlock;
                 return Err(input.new_custom_error(e));
             },
+            Token::Function(ref name)
+                if name.eq_ignore_ascii_case("if") &&
+                    static_prefs::pref!("layout.css.if-function.enabled") =>
+            {
+                let our_ref_index = references.refs.len();
+                // Like for var(), reserve our slot before parsing the branches, to keep the
+                // references in source order.
+                let start = token_start.byte_index() - input_start.byte_index();
+                references.refs.push(SubstitutionFunctionReference {
+                    name: atom!(""),
+                    start,
+                    // To be fixed up after parsing the branches and auto-closing.
+                    end: start,
+                    prev_token_type,
+                    // To be fixed up (if needed) on the next loop iteration via prev_reference_index.
+                    next_token_type: TokenSerializationType::Nothing,
+                    fallback: None,
+                    // To be fixed up after parsing the branches.
+                    kind: SubstitutionFunctionKind::If {
+                        branches: Default::default(),
+                    },
+                });
+                let branches = input.parse_nested_block(|input| {
+                    parse_if_branches(
+                        context,
+                        input,
+                        input_start,
+                        references,
+                        missing_closing_characters,
+                    )
+                })?;
+                check_closed!(")");
+                prev_reference_index = Some(our_ref_index);
+                let reference = &mut references.refs[our_ref_index];
+                reference.end = input.position().byte_index() - input_start.byte_index() +
+                    missing_closing_characters.len();
+                reference.kind = SubstitutionFunctionKind::If { branches };
+                // style() tests depend on other custom properties, just like var().
+                let mut has_style_tests = false;
+                reference.each_custom_property_dependency(|_| has_style_tests = true);
+                references.any_var |= has_style_tests;
+            },
+            Token::Function(ref name)
+                if name.eq_ignore_ascii_case("random-item") &&
+                    static_prefs::pref!("layout.css.random-function.enabled") =>
+            {
+                let our_ref_index = references.refs.len();
+                // Like for if(), reserve our slot before parsing the items.
+                let start = token_start.byte_index() - input_start.byte_index();
+                references.refs.push(SubstitutionFunctionReference {
+                    name: atom!(""),
+                    start,
+                    // To be fixed up after parsing the items and auto-closing.
+                    end: start,
+                    prev_token_type,
+                    // To be fixed up (if needed) on the next loop iteration via prev_reference_index.
+                    next_token_type: TokenSerializationType::Nothing,
+                    fallback: None,
+                    // To be fixed up after parsing the items.
+                    kind: SubstitutionFunctionKind::RandomItem {
+                        sharing: Default::default(),
+                        items: Default::default(),
+                    },
+                });
+                let (sharing, items) = input.parse_nested_block(|input| {
+                    parse_random_items(
+                        context,
+                        input,
+                        input_start,
+                        references,
+                        missing_closing_characters,
+                    )
+                })?;
+                check_closed!(")");
+                prev_reference_index = Some(our_ref_index);
+                let reference = &mut references.refs[our_ref_index];
+                reference.end = input.position().byte_index() - input_start.byte_index() +
+                    missing_closing_characters.len();
+                reference.kind = SubstitutionFunctionKind::RandomItem { sharing, items };
+            },
             Token::Function(ref name) => {
-                let is_var = name.eq_ignore_ascii_case("var");
-                if is_var || name.eq_ignore_ascii_case("env") {
+                // If parsing the function fails after all the whole result is discarded, so it's
+                // fine to note the references upfront.
+                let kind = if name.eq_ignore_ascii_case("var") {
+                    references.any_var = true;
+                    Some(SubstitutionFunctionKind::Var)
+                } else if name.eq_ignore_ascii_case("env") {
+                    references.any_env = true;
+                    Some(SubstitutionFunctionKind::Env)
+                } else if name.eq_ignore_ascii_case("attr") &&
+                    static_prefs::pref!("layout.css.attr.enabled")
+                {
+                    references.any_attr = true;
+                    // To be fixed up after parsing the attribute name.
+                    Some(SubstitutionFunctionKind::Attr {
+                        attribute_type: AttributeType::String,
+                    })
+                } else {
+                    None
+                };
+                if let Some(mut kind) = kind {
                     let our_ref_index = references.refs.len();
                     let fallback = input.parse_nested_block(|input| {
                         // TODO(emilio): For env() this should be <custom-ident> per spec, but no other browser does
                         // that, see https://github.com/w3c/csswg-drafts/issues/3262.
                         let name = input.expect_ident()?;
-                        let name = Atom::from(if is_var {
+                        let name = Atom::from(if kind == SubstitutionFunctionKind::Var {
                             match parse_name(name.as_ref()) {
                                 Ok(name) => name,
                                 Err(()) => {
@@ -783,12 +1140,18 @@ fn parse_declaration_value_block<'i, 't>(
                         } else {
                             name.as_ref()
                         });
+                        if let SubstitutionFunctionKind::Attr {
+                            ref mut attribute_type,
+                        } = kind
+                        {
+                            *attribute_type = parse_attribute_type(context, input)?;
+                        }
 
                         // We want the order of the references to match source order. So we need to reserve our slot
                         // now, _before_ parsing our fallback. Note that we don't care if parsing fails after all, since
                         // if this fails we discard the whole result anyways.
                         let start = token_start.byte_index() - input_start.byte_index();
-                        references.refs.push(VarOrEnvReference {
+                        references.refs.push(SubstitutionFunctionReference {
                             name,
                             start,
                             // To be fixed up after parsing fallback and auto-closing via our_ref_index.
@@ -798,7 +1161,7 @@ fn parse_declaration_value_block<'i, 't>(
                             next_token_type: TokenSerializationType::Nothing,
                             // To be fixed up after parsing fallback.
                             fallback: None,
-                            is_var,
+                            kind,
                         });
 
                         let mut fallback = None;
@@ -811,6 +1174,7 @@ fn parse_declaration_value_block<'i, 't>(
                             // NOTE(emilio): Intentionally using parse_declaration_value rather than
                             // parse_declaration_value_block, since that's what parse_fallback used to do.
                             let (first, last) = parse_declaration_value(
+                                context,
                                 input,
                                 input_start,
                                 references,
@@ -827,6 +1191,7 @@ fn parse_declaration_value_block<'i, 't>(
                             // tokens, but make sure to not consume tokens that would otherwise be
                             // invalid, by calling reset().
                             parse_declaration_value_block(
+                                context,
                                 input,
                                 input_start,
                                 references,
@@ -842,13 +1207,29 @@ fn parse_declaration_value_block<'i, 't>(
                     reference.end = input.position().byte_index() - input_start.byte_index() +
                         missing_closing_characters.len();
                     reference.fallback = fallback;
-                    if is_var {
-                        references.any_var = true;
-                    } else {
-                        references.any_env = true;
-                    }
                 } else {
+                    // Tree-counting functions don't need substitution, but they can't always be
+                    // resolved at parse time, so note them to defer parsing the declaration to
+                    // computed-value time.
+                    if TreeCountingFunction::from_function_name(name).is_some() {
+                        references.any_tree_counting = true;
+                    }
+                    // Likewise for random(), which needs a base value from the element.
+                    if name.eq_ignore_ascii_case("random") {
+                        references.any_random = true;
+                    }
+                    // And for media-progress() and container-progress(), which can't be
+                    // resolved in all contexts at parse time.
+                    if query_progress_feature_type(name).is_some() {
+                        references.any_query_progress = true;
+                    }
+                    // And for relative lengths in math functions, in case they're divided into
+                    // a type that we otherwise resolve at parse time.
+                    let is_math_function = MathFunction::from_ident(name).is_ok() &&
+                        static_prefs::pref!("layout.css.typed-arithmetic.enabled");
+                    references.math_function_depth += is_math_function as u8;
                     nested!();
+                    references.math_function_depth -= is_math_function as u8;
                     check_closed!(")");
                 }
             },
@@ -883,6 +1264,12 @@ fn parse_declaration_value_block<'i, 't>(
                 references
                     .non_custom_references
                     .insert(NonCustomReferences::from_unit(value));
+                if references.math_function_depth > 0 &&
+                    matches!(token, Token::Dimension { .. }) &&
+                    NoCalcLength::is_relative_unit(value)
+                {
+                    references.any_relative_length_in_math = true;
+                }
                 let is_unquoted_url = matches!(token, Token::UnquotedUrl(_));
                 if value.ends_with("�") && input.slice_from(token_start).ends_with("\\") {
                     // Unescaped backslash at EOF in these contexts is interpreted as U+FFFD
@@ -901,6 +1288,372 @@ fn parse_declaration_value_block<'i, 't>(
     Ok((first_token_type, last_token_type))
 }
 
+/// Parses the optional `<attr-type>` of an `attr()` function, right after the
+/// attribute name.
+///
+/// <https://drafts.csswg.org/css-values-5/#attr-notation>
+fn parse_attribute_type<'i, 't>(
+    context: &ParserContext,
+    input: &mut Parser<'i, 't>,
+) -> Result<AttributeType, ParseError<'i>> {
+    if let Ok(ty) = input.try_parse(|input| -> Result<_, ParseError<'i>> {
+        input.expect_function_matching("type")?;
+        input.parse_nested_block(|input| {
+            let start = input.position();
+            while input.next().is_ok() {}
+            let syntax = input.slice_from(start).trim();
+            let descriptor = Descriptor::from_str(syntax, /* save_specified = */ false)
+                .map_err(|_| input.new_custom_error(StyleParseErrorKind::UnspecifiedError))?;
+            if descriptor.is_universal() {
+                return Ok(AttributeType::RawString);
+            }
+            Ok(AttributeType::Type(Box::new(descriptor)))
+        })
+    }) {
+        return Ok(ty);
+    }
+    if input
+        .try_parse(|input| input.expect_ident_matching("raw-string"))
+        .is_ok()
+    {
+        return Ok(AttributeType::RawString);
+    }
+    if input.try_parse(|input| input.expect_delim('%')).is_ok() {
+        return Ok(AttributeType::Unit("%".into()));
+    }
+    if let Ok(unit) = input.try_parse(|input| -> Result<_, ParseError<'i>> {
+        let location = input.current_source_location();
+        let unit = input.expect_ident_cloned()?;
+        if !is_attribute_unit(context, &unit) {
+            return Err(location.new_unexpected_token_error(Token::Ident(unit)));
+        }
+        Ok(unit)
+    }) {
+        return Ok(AttributeType::Unit(unit.as_ref().into()));
+    }
+    Ok(AttributeType::String)
+}
+
+/// Returns whether the given identifier is an `<attr-unit>`, that is, the unit of some CSS
+/// dimension.
+fn is_attribute_unit(context: &ParserContext, unit: &str) -> bool {
+    NoCalcLength::parse_dimension(context, 0., unit).is_ok() ||
+        Angle::parse_dimension(0., unit, /* was_calc = */ false).is_ok() ||
+        Time::parse_dimension(0., unit).is_ok() ||
+        Resolution::parse_dimension(0., unit).is_ok() ||
+        unit.eq_ignore_ascii_case("fr")
+}
+
+/// Parses the branches of an `if()` function, within its nested block.
+///
+/// <https://drafts.csswg.org/css-values-5/#if-notation>
+fn parse_if_branches<'i, 't>(
+    context: &ParserContext,
+    input: &mut Parser<'i, 't>,
+    input_start: SourcePosition,
+    references: &mut References,
+    missing_closing_characters: &mut String,
+) -> Result<Box<[IfBranch]>, ParseError<'i>> {
+    let mut branches = vec![];
+    loop {
+        input.skip_whitespace();
+        if input.is_exhausted() && !branches.is_empty() {
+            break;
+        }
+        let condition = IfCondition::parse(context, input)?;
+        input.expect_colon()?;
+        input.skip_whitespace();
+        let start = input.position().byte_index() - input_start.byte_index();
+        let missing_before = missing_closing_characters.len();
+        let (first_token_type, last_token_type) = parse_declaration_value(
+            context,
+            input,
+            input_start,
+            references,
+            missing_closing_characters,
+        )?;
+        let end = input.position().byte_index() - input_start.byte_index() +
+            missing_closing_characters.len() -
+            missing_before;
+        branches.push(IfBranch {
+            condition,
+            start,
+            end,
+            first_token_type,
+            last_token_type,
+        });
+        if input.try_parse(|input| input.expect_semicolon()).is_err() {
+            input.expect_exhausted()?;
+            break;
+        }
+    }
+    Ok(branches.into_boxed_slice())
+}
+
+/// Parses the arguments of a `random-item()` function, within its nested block.
+///
+/// <https://drafts.csswg.org/css-values-5/#funcdef-random-item>
+fn parse_random_items<'i, 't>(
+    context: &ParserContext,
+    input: &mut Parser<'i, 't>,
+    input_start: SourcePosition,
+    references: &mut References,
+    missing_closing_characters: &mut String,
+) -> Result<(RandomValueSharing, Box<[RandomItem]>), ParseError<'i>> {
+    input.skip_whitespace();
+    let sharing = RandomValueSharing::parse(input)?;
+    input.expect_comma()?;
+    let mut items = vec![];
+    loop {
+        input.skip_whitespace();
+        let start = input.position().byte_index() - input_start.byte_index();
+        let missing_before = missing_closing_characters.len();
+        let (first_token_type, last_token_type) =
+            input.parse_until_before(Delimiter::Comma, |input| {
+                parse_declaration_value(
+                    context,
+                    input,
+                    input_start,
+                    references,
+                    missing_closing_characters,
+                )
+            })?;
+        let end = input.position().byte_index() - input_start.byte_index() +
+            missing_closing_characters.len() -
+            missing_before;
+        items.push(RandomItem {
+            start,
+            end,
+            first_token_type,
+            last_token_type,
+        });
+        if input.try_parse(|input| input.expect_comma()).is_err() {
+            input.expect_exhausted()?;
+            break;
+        }
+    }
+    Ok((sharing, items.into_boxed_slice()))
+}
+
+impl IfCondition {
+    fn parse<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("else")).is_ok() {
+            return Ok(IfCondition::Else);
+        }
+        Self::parse_boolean_expression(context, input)
+    }
+
+    /// <https://drafts.csswg.org/css-values-5/#typedef-boolean-expr>
+    fn parse_boolean_expression<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("not")).is_ok() {
+            let inner_condition = Self::parse_in_parens(context, input)?;
+            return Ok(IfCondition::Not(Box::new(inner_condition)));
+        }
+
+        let first_condition = Self::parse_in_parens(context, input)?;
+        let operator = match input.try_parse(Operator::parse) {
+            Ok(op) => op,
+            Err(..) => return Ok(first_condition),
+        };
+
+        let mut conditions = vec![first_condition, Self::parse_in_parens(context, input)?];
+        let delim = match operator {
+            Operator::And => "and",
+            Operator::Or => "or",
+        };
+        loop {
+            if input.try_parse(|i| i.expect_ident_matching(delim)).is_err() {
+                return Ok(IfCondition::Operation(conditions.into_boxed_slice(), operator));
+            }
+            conditions.push(Self::parse_in_parens(context, input)?);
+        }
+    }
+
+    fn parse_in_parens<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        input.skip_whitespace();
+        let location = input.current_source_location();
+        let test = match *input.next()? {
+            Token::ParenthesisBlock => {
+                let nested = input.try_parse(|input| {
+                    input.parse_nested_block(|input| Self::parse_boolean_expression(context, input))
+                });
+                if let Ok(nested) = nested {
+                    return Ok(nested);
+                }
+                None
+            },
+            Token::Function(ref name) => {
+                let name = name.clone();
+                input.try_parse(|input| {
+                    input.parse_nested_block(|input| IfTest::parse_function(context, &name, input))
+                })
+                .ok()
+            },
+            ref t => return Err(location.new_unexpected_token_error(t.clone())),
+        };
+        if let Some(test) = test {
+            return Ok(IfCondition::Test(test));
+        }
+        input.parse_nested_block(|input| input.expect_no_error_token().map_err(Into::into))?;
+        Ok(IfCondition::GeneralEnclosed)
+    }
+
+    fn each_style_test_name(&self, f: &mut impl FnMut(&Name)) {
+        match *self {
+            IfCondition::Test(IfTest::Style { ref name, .. }) => f(name),
+            IfCondition::Not(ref condition) => condition.each_style_test_name(f),
+            IfCondition::Operation(ref conditions, _) => {
+                for condition in conditions.iter() {
+                    condition.each_style_test_name(f);
+                }
+            },
+            IfCondition::Else | IfCondition::Test(..) | IfCondition::GeneralEnclosed => {},
+        }
+    }
+
+    fn each_media_condition(&self, f: &mut impl FnMut(&QueryCondition)) {
+        match *self {
+            IfCondition::Test(IfTest::Media(ref condition)) => f(condition),
+            IfCondition::Not(ref condition) => condition.each_media_condition(f),
+            IfCondition::Operation(ref conditions, _) => {
+                for condition in conditions.iter() {
+                    condition.each_media_condition(f);
+                }
+            },
+            IfCondition::Else | IfCondition::Test(..) | IfCondition::GeneralEnclosed => {},
+        }
+    }
+
+    fn matches(
+        &self,
+        custom_properties: &ComputedCustomProperties,
+        stylist: &Stylist,
+        url_data: &UrlExtraData,
+        computed_context: &computed::Context,
+    ) -> KleeneValue {
+        match *self {
+            IfCondition::Else => KleeneValue::True,
+            IfCondition::GeneralEnclosed => KleeneValue::Unknown,
+            IfCondition::Test(ref test) => {
+                KleeneValue::from(test.matches(custom_properties, stylist, url_data, computed_context))
+            },
+            IfCondition::Not(ref condition) => {
+                !condition.matches(custom_properties, stylist, url_data, computed_context)
+            },
+            IfCondition::Operation(ref conditions, op) => {
+                let matches =
+                    |c: &Self| c.matches(custom_properties, stylist, url_data, computed_context);
+                match op {
+                    Operator::And => KleeneValue::any_false(conditions.iter(), matches),
+                    Operator::Or => KleeneValue::any(conditions.iter(), matches),
+                }
+            },
+        }
+    }
+}
+
+impl IfTest {
+    fn parse_function<'i, 't>(
+        context: &ParserContext,
+        name: &str,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        fn rest<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Box<str>, ParseError<'i>> {
+            let start = input.position();
+            input.expect_no_error_token()?;
+            Ok(input.slice_from(start).trim().into())
+        }
+        Ok(match_ignore_ascii_case! { name,
+            "style" => {
+                let location = input.current_source_location();
+                let ident = input.expect_ident()?;
+                let name = match parse_name(ident.as_ref()) {
+                    Ok(name) => Atom::from(name),
+                    Err(()) => {
+                        let ident = ident.clone();
+                        return Err(location.new_custom_error(
+                            SelectorParseErrorKind::UnexpectedIdent(ident),
+                        ));
+                    },
+                };
+                let value = if input.try_parse(|input| input.expect_colon()).is_ok() {
+                    Some(rest(input)?)
+                } else {
+                    input.expect_exhausted()?;
+                    None
+                };
+                IfTest::Style { name, value }
+            },
+            "media" => IfTest::Media(QueryCondition::parse_in_parenthesis_block(
+                context,
+                input,
+                FeatureType::Media,
+            )?),
+            "supports" => IfTest::Supports(SupportsCondition::parse(input)?.eval(context)),
+            _ => return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError)),
+        })
+    }
+
+    fn matches(
+        &self,
+        custom_properties: &ComputedCustomProperties,
+        stylist: &Stylist,
+        url_data: &UrlExtraData,
+        computed_context: &computed::Context,
+    ) -> bool {
+        match *self {
+            IfTest::Style {
+                ref name,
+                ref value,
+            } => {
+                let registration = stylist.get_custom_property_registration(name);
+                let Some(current) = custom_properties.get(registration, name) else {
+                    return false;
+                };
+                let current = current.to_variable_value();
+                let equals = |expected: &str| {
+                    if registration.syntax.is_universal() {
+                        return current.css.trim() == expected.trim();
+                    }
+                    compute_value(
+                        expected,
+                        url_data,
+                        registration,
+                        computed_context,
+                        /* attr_tainted = */ false,
+                    )
+                    .is_ok_and(|expected| expected.to_variable_value().css == current.css)
+                };
+                match *value {
+                    Some(ref value) => equals(value),
+                    // Without a value, the test matches if the property has a non-initial
+                    // value.
+                    None => match registration.initial_value {
+                        Some(ref initial) => !equals(&initial.css),
+                        None => true,
+                    },
+                }
+            },
+            IfTest::Media(ref condition) => {
+                let device = computed_context.device();
+                computed::Context::for_media_query_evaluation(device, device.quirks_mode(), |cx| {
+                    condition.matches(cx).to_bool(/* unknown = */ false)
+                })
+            },
+            IfTest::Supports(matches) => matches,
+        }
+    }
+}
+
 /// A struct that takes care of encapsulating the cascade process for custom properties.
 pub struct CustomPropertiesBuilder<'a, 'b: 'a> {
     seen: PrecomputedHashSet<&'a Name>,
@@ -1130,7 +1883,7 @@ impl<'a, 'b: 'a> CustomPropertiesBuilder<'a, 'b> {
             .refs
             .iter()
             .filter_map(|reference| {
-                if !reference.is_var {
+                if !reference.is_var() {
                     return None;
                 }
                 let registration = self
@@ -1213,6 +1966,7 @@ impl<'a, 'b: 'a> CustomPropertiesBuilder<'a, 'b> {
                         &value.url_data,
                         registration,
                         self.computed_context,
+                        value.is_attr_tainted(),
                     ).ok()
                 } else {
                     None
@@ -1580,15 +2334,14 @@ fn substitute_all(
             // Visit other custom properties...
             // FIXME: Maybe avoid visiting the same var twice if not needed?
             for next in &v.references.refs {
-                if !next.is_var {
-                    continue;
-                }
-                visit_link(
-                    VarType::Custom(next.name.clone()),
-                    context,
-                    &mut lowlink,
-                    &mut self_ref,
-                );
+                next.each_custom_property_dependency(|name| {
+                    visit_link(
+                        VarType::Custom(name.clone()),
+                        context,
+                        &mut lowlink,
+                        &mut self_ref,
+                    );
+                });
             }
 
             // ... Then non-custom properties.
@@ -1707,7 +2460,11 @@ fn substitute_all(
                 )
                 .is_some() ||
                     v.references.refs.iter().any(|reference| {
-                        reference.is_var && deferred.get(&reference.name).is_some()
+                        let mut depends_on_deferred = false;
+                        reference.each_custom_property_dependency(|name| {
+                            depends_on_deferred |= deferred.get(name).is_some();
+                        });
+                        depends_on_deferred
                     });
 
                 if defer {
@@ -1784,6 +2541,7 @@ fn handle_invalid_at_computed_value_time(
                 &initial_value.url_data,
                 registration,
                 computed_context,
+                /* attr_tainted = */ false,
             ) {
                 custom_properties.insert(registration, name, initial_value);
                 return;
@@ -1811,6 +2569,7 @@ fn substitute_references_if_needed_and_apply(
 
     let inherited = computed_context.inherited_custom_properties();
     let url_data = &value.url_data;
+    computed_context.set_random_property(PropertyId::Custom(name.clone()));
     let value = match substitute_internal(
         value,
         custom_properties,
@@ -1888,6 +2647,7 @@ struct UniversalSubstitution<'a> {
     css: Cow<'a, str>,
     first_token_type: TokenSerializationType,
     last_token_type: TokenSerializationType,
+    attr_tainted: bool,
 }
 
 impl<'a> UniversalSubstitution<'a> {
@@ -1896,6 +2656,7 @@ impl<'a> UniversalSubstitution<'a> {
             css: Cow::from(v.css),
             first_token_type: v.first_token_type,
             last_token_type: v.last_token_type,
+            attr_tainted: v.attr_tainted,
         }
     }
 }
@@ -1910,6 +2671,7 @@ impl<'a> Substitution<'a> {
             css: Cow::Borrowed(css),
             first_token_type,
             last_token_type,
+            attr_tainted: false,
         })
     }
 
@@ -1940,6 +2702,7 @@ impl<'a> Substitution<'a> {
                     last_token_type: substitution.last_token_type,
                     url_data: url_data.clone(),
                     references: Default::default(),
+                    attr_tainted: substitution.attr_tainted,
                 });
                 ComputedRegisteredValue::universal(value)
             },
@@ -1953,6 +2716,7 @@ fn compute_value(
     url_data: &UrlExtraData,
     registration: &PropertyRegistrationData,
     computed_context: &computed::Context,
+    attr_tainted: bool,
 ) -> Result<ComputedRegisteredValue, ()> {
     debug_assert!(!registration.syntax.is_universal());
 
@@ -1965,6 +2729,7 @@ fn compute_value(
         url_data,
         computed_context,
         AllowComputationallyDependent::Yes,
+        attr_tainted,
     )
 }
 
@@ -1992,7 +2757,7 @@ fn do_substitute_chunk<'a>(
     registration: &PropertyRegistrationData,
     stylist: &Stylist,
     computed_context: &computed::Context,
-    references: &mut std::iter::Peekable<std::slice::Iter<VarOrEnvReference>>,
+    references: &mut std::iter::Peekable<std::slice::Iter<SubstitutionFunctionReference>>,
 ) -> Result<Substitution<'a>, ()> {
     if start == end {
         // Empty string. Easy.
@@ -2005,7 +2770,13 @@ fn do_substitute_chunk<'a>(
     {
         let result = &css[start..end];
         if !registration.syntax.is_universal() {
-            let computed_value = compute_value(result, url_data, registration, computed_context)?;
+            let computed_value = compute_value(
+                result,
+                url_data,
+                registration,
+                computed_context,
+                /* attr_tainted = */ false,
+            )?;
             return Ok(Substitution::Computed(computed_value));
         }
         return Ok(Substitution::new(result, first_token_type, last_token_type));
@@ -2033,6 +2804,7 @@ fn do_substitute_chunk<'a>(
             references,
         )?;
         let substitution = substitution.into_universal();
+        substituted.attr_tainted |= substitution.attr_tainted;
 
         // Optimize the property: var(--...) case to avoid allocating at all.
         if reference.start == start && reference.end == end && registration.syntax.is_universal() {
@@ -2053,7 +2825,13 @@ fn do_substitute_chunk<'a>(
     }
     if !registration.syntax.is_universal() {
         let computed_value =
-            compute_value(&substituted.css, url_data, registration, computed_context)?;
+            compute_value(
+                &substituted.css,
+                url_data,
+                registration,
+                computed_context,
+                substituted.attr_tainted,
+            )?;
         return Ok(Substitution::Computed(computed_value));
     }
     Ok(Substitution::from_value(substituted))
@@ -2063,54 +2841,100 @@ fn substitute_one_reference<'a>(
     css: &'a str,
     url_data: &UrlExtraData,
     custom_properties: &'a ComputedCustomProperties,
-    reference: &VarOrEnvReference,
+    reference: &SubstitutionFunctionReference,
     stylist: &Stylist,
     computed_context: &computed::Context,
-    references: &mut std::iter::Peekable<std::slice::Iter<VarOrEnvReference>>,
+    references: &mut std::iter::Peekable<std::slice::Iter<SubstitutionFunctionReference>>,
 ) -> Result<Substitution<'a>, ()> {
     let registration;
-    if reference.is_var {
-        registration = stylist.get_custom_property_registration(&reference.name);
-        if let Some(v) = custom_properties.get(registration, &reference.name) {
-            #[cfg(debug_assertions)]
-            debug_assert!(v.is_parsed(registration), "Should be already computed");
-            if registration.syntax.is_universal() {
-                // Skip references that are inside the outer variable (in fallback for example).
-                while references
-                    .next_if(|next_ref| next_ref.end <= reference.end)
-                    .is_some()
-                {}
-            } else {
-                // We need to validate the fallback if any, since invalid fallback should
-                // invalidate the whole variable.
-                if let Some(ref fallback) = reference.fallback {
-                    let _ = do_substitute_chunk(
-                        css,
-                        fallback.start.get(),
-                        reference.end - 1, // Don't include the closing parenthesis.
-                        fallback.first_token_type,
-                        fallback.last_token_type,
-                        url_data,
-                        custom_properties,
-                        registration,
-                        stylist,
-                        computed_context,
-                        references,
-                    )?;
+    let substitution = match reference.kind {
+        SubstitutionFunctionKind::If { ref branches } => {
+            return substitute_if(
+                css,
+                url_data,
+                custom_properties,
+                reference,
+                branches,
+                stylist,
+                computed_context,
+                references,
+            );
+        },
+        SubstitutionFunctionKind::RandomItem {
+            ref sharing,
+            ref items,
+        } => {
+            return substitute_random_item(
+                css,
+                url_data,
+                custom_properties,
+                reference,
+                sharing,
+                items,
+                stylist,
+                computed_context,
+                references,
+            );
+        },
+        SubstitutionFunctionKind::Var => {
+            registration = stylist.get_custom_property_registration(&reference.name);
+            if let Some(v) = custom_properties.get(registration, &reference.name) {
+                #[cfg(debug_assertions)]
+                debug_assert!(v.is_parsed(registration), "Should be already computed");
+                if registration.syntax.is_universal() {
+                    // Skip references that are inside the outer variable (in fallback for example).
+                    while references
+                        .next_if(|next_ref| next_ref.end <= reference.end)
+                        .is_some()
+                    {}
+                } else {
+                    // We need to validate the fallback if any, since invalid fallback should
+                    // invalidate the whole variable.
+                    if let Some(ref fallback) = reference.fallback {
+                        let _ = do_substitute_chunk(
+                            css,
+                            fallback.start.get(),
+                            reference.end - 1, // Don't include the closing parenthesis.
+                            fallback.first_token_type,
+                            fallback.last_token_type,
+                            url_data,
+                            custom_properties,
+                            registration,
+                            stylist,
+                            computed_context,
+                            references,
+                        )?;
+                    }
                 }
+                return Ok(Substitution::Computed(v.clone()));
             }
-            return Ok(Substitution::Computed(v.clone()));
-        }
-    } else {
-        registration = PropertyRegistrationData::unregistered();
-        let device = stylist.device();
-        if let Some(v) = device.environment().get(&reference.name, device, url_data) {
-            while references
-                .next_if(|next_ref| next_ref.end <= reference.end)
-                .is_some()
-            {}
-            return Ok(Substitution::from_value(v));
-        }
+            None
+        },
+        SubstitutionFunctionKind::Env => {
+            registration = PropertyRegistrationData::unregistered();
+            computed_context
+                .builder
+                .add_flags(ComputedValueFlags::DEPENDS_ON_ENVIRONMENT);
+            let device = stylist.device();
+            device
+                .environment()
+                .get(&reference.name, device, url_data)
+                .map(Substitution::from_value)
+        },
+        SubstitutionFunctionKind::Attr { ref attribute_type } => {
+            registration = PropertyRegistrationData::unregistered();
+            computed_context
+                .builder
+                .add_flags(ComputedValueFlags::USES_ATTR_FUNCTION);
+            substitute_attribute(&reference.name, attribute_type, url_data, computed_context)
+        },
+    };
+    if let Some(substitution) = substitution {
+        while references
+            .next_if(|next_ref| next_ref.end <= reference.end)
+            .is_some()
+        {}
+        return Ok(substitution);
     }
 
     let Some(ref fallback) = reference.fallback else {
@@ -2132,7 +2956,173 @@ fn substitute_one_reference<'a>(
     )
 }
 
-/// Replace `var()` and `env()` functions. Return `Err(..)` for invalid at computed time.
+/// Substitutes an `if()` reference with the value of its first matching branch, or with nothing if
+/// no branch matches.
+fn substitute_if<'a>(
+    css: &'a str,
+    url_data: &UrlExtraData,
+    custom_properties: &'a ComputedCustomProperties,
+    reference: &SubstitutionFunctionReference,
+    branches: &[IfBranch],
+    stylist: &Stylist,
+    computed_context: &computed::Context,
+    references: &mut std::iter::Peekable<std::slice::Iter<SubstitutionFunctionReference>>,
+) -> Result<Substitution<'a>, ()> {
+    let branch = branches.iter().find(|branch| {
+        branch
+            .condition
+            .matches(custom_properties, stylist, url_data, computed_context) ==
+            KleeneValue::True
+    });
+    let mut substitution = Substitution::default();
+    if let Some(branch) = branch {
+        // Skip references in the values of previous branches.
+        while references
+            .next_if(|next_ref| next_ref.end <= branch.start)
+            .is_some()
+        {}
+        substitution = do_substitute_chunk(
+            css,
+            branch.start,
+            branch.end,
+            branch.first_token_type,
+            branch.last_token_type,
+            url_data,
+            custom_properties,
+            PropertyRegistrationData::unregistered(),
+            stylist,
+            computed_context,
+            references,
+        )?;
+    }
+    // Skip references in the remaining branches.
+    while references
+        .next_if(|next_ref| next_ref.end <= reference.end)
+        .is_some()
+    {}
+    Ok(substitution)
+}
+
+/// Substitutes a `random-item()` reference with the value of one of its items, picked by the base
+/// random value of the function.
+fn substitute_random_item<'a>(
+    css: &'a str,
+    url_data: &UrlExtraData,
+    custom_properties: &'a ComputedCustomProperties,
+    reference: &SubstitutionFunctionReference,
+    sharing: &RandomValueSharing,
+    items: &[RandomItem],
+    stylist: &Stylist,
+    computed_context: &computed::Context,
+    references: &mut std::iter::Peekable<std::slice::Iter<SubstitutionFunctionReference>>,
+) -> Result<Substitution<'a>, ()> {
+    let count = items.len();
+    let base = computed_context.random_base_value(sharing, "random-item");
+    let item = &items[((base * count as CSSFloat) as usize).min(count - 1)];
+    // Skip references in the previous items.
+    while references
+        .next_if(|next_ref| next_ref.end <= item.start)
+        .is_some()
+    {}
+    let substitution = do_substitute_chunk(
+        css,
+        item.start,
+        item.end,
+        item.first_token_type,
+        item.last_token_type,
+        url_data,
+        custom_properties,
+        PropertyRegistrationData::unregistered(),
+        stylist,
+        computed_context,
+        references,
+    )?;
+    // Skip references in the remaining items.
+    while references
+        .next_if(|next_ref| next_ref.end <= reference.end)
+        .is_some()
+    {}
+    Ok(substitution)
+}
+
+/// Returns the substitution for an `attr()` reference, or `None` if the attribute is missing or
+/// its value doesn't match the attribute type, in which case the fallback should be used.
+///
+/// The substitution is attr-tainted, so that it can't be used as a URL.
+fn substitute_attribute<'a>(
+    name: &Name,
+    attribute_type: &AttributeType,
+    url_data: &UrlExtraData,
+    computed_context: &computed::Context,
+) -> Option<Substitution<'a>> {
+    let local_name = LocalName::from(name.as_ref());
+    let value = computed_context.attribute_provider.get_attr(&local_name)?;
+    let mut value = match *attribute_type {
+        AttributeType::String => {
+            VariableValue::from_token(Token::QuotedString(value.as_str().into()), url_data)
+        },
+        AttributeType::RawString => {
+            let context = ParserContext::new(
+                Origin::Author,
+                url_data,
+                None,
+                ParsingMode::DEFAULT,
+                computed_context.quirks_mode,
+                /* namespaces = */ Default::default(),
+                None,
+                None,
+            );
+            let mut input = ParserInput::new(&value);
+            let mut input = Parser::new(&mut input);
+            let value = VariableValue::parse(&mut input, &context).ok()?;
+            // TODO: Substitute arbitrary substitution functions in the attribute value too.
+            if value.has_references() {
+                return None;
+            }
+            value
+        },
+        AttributeType::Type(ref syntax) => {
+            let mut input = ParserInput::new(&value);
+            let mut input = Parser::new(&mut input);
+            let value = SpecifiedRegisteredValue::parse(
+                &mut input,
+                syntax,
+                url_data,
+                AllowComputationallyDependent::Yes,
+                /* attr_tainted = */ true,
+            )
+            .ok()?;
+            return Some(Substitution::Computed(
+                value.to_computed_value(computed_context),
+            ));
+        },
+        AttributeType::Unit(ref unit) => {
+            let mut input = ParserInput::new(&value);
+            let mut input = Parser::new(&mut input);
+            let number = input.expect_number().ok()?;
+            input.expect_exhausted().ok()?;
+            let token = if &**unit == "%" {
+                Token::Percentage {
+                    has_sign: false,
+                    unit_value: number / 100.,
+                    int_value: None,
+                }
+            } else {
+                Token::Dimension {
+                    has_sign: false,
+                    value: number,
+                    int_value: None,
+                    unit: CowRcStr::from(&**unit),
+                }
+            };
+            VariableValue::from_token(token, url_data)
+        },
+    };
+    value.set_attr_tainted(true);
+    Some(Substitution::from_value(value))
+}
+
+/// Replace `var()`, `env()` and `attr()` functions. Return `Err(..)` for invalid at computed time.
 fn substitute_internal<'a>(
     variable_value: &'a VariableValue,
     custom_properties: &'a ComputedCustomProperties,
@@ -2156,14 +3146,29 @@ fn substitute_internal<'a>(
     )
 }
 
-/// Replace var() and env() functions, returning the resulting CSS string.
+/// The result of substituting the functions in a value.
+pub struct SubstitutionResult<'a> {
+    /// The resulting CSS string.
+    pub css: Cow<'a, str>,
+    /// Whether any part of the result came from an `attr()` function, in which case it can't be
+    /// used as a URL.
+    pub attr_tainted: bool,
+}
+
+/// Replace var(), env() and attr() functions, returning the resulting CSS string.
 pub fn substitute<'a>(
     variable_value: &'a VariableValue,
     custom_properties: &'a ComputedCustomProperties,
     stylist: &Stylist,
     computed_context: &computed::Context,
-) -> Result<Cow<'a, str>, ()> {
-    debug_assert!(variable_value.has_references());
+) -> Result<SubstitutionResult<'a>, ()> {
+    debug_assert!(
+        variable_value.has_references() ||
+            variable_value.uses_tree_counting_functions() ||
+            variable_value.uses_random_functions() ||
+            variable_value.uses_query_progress_functions() ||
+            variable_value.uses_relative_lengths_in_math_functions()
+    );
     let v = substitute_internal(
         variable_value,
         custom_properties,
@@ -2172,5 +3177,8 @@ pub fn substitute<'a>(
         computed_context,
     )?;
     let v = v.into_universal();
-    Ok(v.css)
+    Ok(SubstitutionResult {
+        css: v.css,
+        attr_tainted: v.attr_tainted,
+    })
 }
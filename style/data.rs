@@ -217,6 +217,13 @@ impl ElementStyles {
         usage
     }
 
+    /// Whether the primary style or any eager pseudo-element style of this
+    /// element has any of the given flags.
+    pub fn has_flag(&self, flag: ComputedValueFlags) -> bool {
+        let has_flag = |style: &ComputedValues| style.flags.intersects(flag);
+        has_flag(self.primary()) || self.pseudos.as_array().iter().flatten().any(|s| has_flag(s))
+    }
+
     #[cfg(feature = "gecko")]
     fn size_of_excluding_cvs(&self, _ops: &mut MallocSizeOfOps) -> usize {
         // As the method name suggests, we don't measures the ComputedValues
@@ -25,8 +25,10 @@ use atomic_refcell::{AtomicRef, AtomicRefMut};
 use dom::ElementState;
 use selectors::matching::{ElementSelectorFlags, QuirksMode, VisitedHandlingMode};
 use selectors::sink::Push;
+use selectors::NthIndexCache;
 use selectors::{Element as SelectorsElement, OpaqueElement};
 use servo_arc::{Arc, ArcBorrow};
+use std::cell::RefCell;
 use std::fmt;
 use std::fmt::Debug;
 use std::hash::Hash;
@@ -548,6 +550,13 @@ pub trait TElement:
     where
         F: FnMut(&LocalName);
 
+    /// Returns the value of the attribute with the given local name in the
+    /// null namespace, if present. This is used to substitute `attr()`
+    /// functions.
+    fn get_attr(&self, _local_name: &LocalName) -> Option<String> {
+        None
+    }
+
     /// Internal iterator for the part names that this element exports for a
     /// given part name.
     fn each_exported_part<F>(&self, _name: &AtomIdent, _callback: F)
@@ -923,6 +932,151 @@ pub trait TElement:
     }
 }
 
+/// Something that attribute values can be read from, in order to substitute
+/// `attr()` functions during the cascade.
+pub trait AttributeProvider {
+    /// Returns the value of the attribute with the given local name, if any.
+    fn get_attr(&self, local_name: &LocalName) -> Option<String>;
+}
+
+impl<E: TElement> AttributeProvider for E {
+    fn get_attr(&self, local_name: &LocalName) -> Option<String> {
+        TElement::get_attr(self, local_name)
+    }
+}
+
+/// An attribute provider for cascades that don't happen on behalf of an
+/// element, like when computing animation values or media queries.
+pub struct DummyAttributeProvider;
+
+impl AttributeProvider for DummyAttributeProvider {
+    fn get_attr(&self, _local_name: &LocalName) -> Option<String> {
+        None
+    }
+}
+
+/// Something that knows the position of an element among its siblings, in
+/// order to resolve the `sibling-index()` and `sibling-count()` functions.
+///
+/// <https://drafts.csswg.org/css-values-5/#tree-counting>
+pub trait TreeCountingProvider {
+    /// Returns the 1-based index of the element among its element siblings.
+    fn sibling_index(&self) -> u32;
+
+    /// Returns the number of element siblings of the element, including itself.
+    fn sibling_count(&self) -> u32;
+
+    /// Returns the element being styled, if any, which identifies it across restyles.
+    fn element(&self) -> Option<OpaqueNode>;
+}
+
+/// The tree-counting provider for an element being styled.
+///
+/// The indices it computes are shared with `:nth-child()` and
+/// `:nth-last-child()` through the nth-index cache of the traversal, so that
+/// resolving tree-counting functions for all the children of an element takes
+/// linear rather than quadratic time.
+pub struct ElementTreeCountingProvider<'a, E: TElement> {
+    element: E,
+    nth_index_cache: RefCell<&'a mut NthIndexCache>,
+}
+
+impl<'a, E: TElement> ElementTreeCountingProvider<'a, E> {
+    /// Creates a tree-counting provider for `element`.
+    pub fn new(element: E, nth_index_cache: &'a mut NthIndexCache) -> Self {
+        Self {
+            element,
+            nth_index_cache: RefCell::new(nth_index_cache),
+        }
+    }
+
+    /// Returns the 1-based index of `element` among its siblings, counting
+    /// from the end if `is_from_end` is true.
+    ///
+    /// This mirrors what selector matching does for `:nth-child()`: the
+    /// traversal mostly goes from left to right, so in both cases we look for a
+    /// cached index among the previous siblings.
+    fn index(&self, element: E, is_from_end: bool) -> u32 {
+        let mut nth_index_cache = self.nth_index_cache.borrow_mut();
+        let cache = nth_index_cache.get::<SelectorImpl>(false, is_from_end, &[]);
+        let opaque = element.opaque();
+        if let Some(index) = cache.lookup(opaque) {
+            return index as u32;
+        }
+
+        let mut index = 1;
+        let mut current = element.prev_sibling_element();
+        let mut from_cache = false;
+        while let Some(sibling) = current {
+            if let Some(sibling_index) = cache.lookup(sibling.opaque()) {
+                index = if is_from_end {
+                    sibling_index - index
+                } else {
+                    sibling_index + index
+                };
+                from_cache = true;
+                break;
+            }
+            index += 1;
+            current = sibling.prev_sibling_element();
+        }
+
+        if is_from_end && !from_cache {
+            index = 1;
+            let mut current = element.next_sibling_element();
+            while let Some(sibling) = current {
+                index += 1;
+                current = sibling.next_sibling_element();
+            }
+        }
+
+        cache.insert(opaque, index);
+        index as u32
+    }
+}
+
+impl<'a, E: TElement> TreeCountingProvider for ElementTreeCountingProvider<'a, E> {
+    fn sibling_index(&self) -> u32 {
+        // Inserting or removing an earlier sibling changes our index, which is
+        // exactly what `:nth-child()` needs too.
+        self.element
+            .apply_selector_flags(ElementSelectorFlags::HAS_SLOW_SELECTOR_LATER_SIBLINGS);
+        self.index(self.element, /* is_from_end = */ false)
+    }
+
+    fn sibling_count(&self) -> u32 {
+        // Any insertion or removal changes the count for all the siblings,
+        // like for `:nth-last-child()`.
+        self.element
+            .apply_selector_flags(ElementSelectorFlags::HAS_SLOW_SELECTOR);
+        self.index(self.element, /* is_from_end = */ false) +
+            self.index(self.element, /* is_from_end = */ true) -
+            1
+    }
+
+    fn element(&self) -> Option<OpaqueNode> {
+        Some(self.element.as_node().opaque())
+    }
+}
+
+/// A tree-counting provider for cascades that don't happen on behalf of an
+/// element, which behaves as if the element was an only child.
+pub struct DummyTreeCountingProvider;
+
+impl TreeCountingProvider for DummyTreeCountingProvider {
+    fn sibling_index(&self) -> u32 {
+        1
+    }
+
+    fn sibling_count(&self) -> u32 {
+        1
+    }
+
+    fn element(&self) -> Option<OpaqueNode> {
+        None
+    }
+}
+
 /// TNode and TElement aren't Send because we want to be careful and explicit
 /// about our parallel traversal. However, there are certain situations
 /// (including but not limited to the traversal) where we need to send DOM
@@ -28,6 +28,8 @@ pub enum ContextualParseError<'a> {
     UnsupportedFontFeatureValuesDescriptor(&'a str, ParseError<'a>),
     /// A font palette values descriptor was not recognized.
     UnsupportedFontPaletteValuesDescriptor(&'a str, ParseError<'a>),
+    /// A color profile descriptor declaration was not recognized.
+    UnsupportedColorProfileDescriptor(&'a str, ParseError<'a>),
     /// A keyframe rule was not valid.
     InvalidKeyframeRule(&'a str, ParseError<'a>),
     /// A font feature values rule was not valid.
@@ -165,6 +167,14 @@ impl<'a> fmt::Display for ContextualParseError<'a> {
                 )?;
                 parse_error_to_str(err, f)
             },
+            ContextualParseError::UnsupportedColorProfileDescriptor(decl, ref err) => {
+                write!(
+                    f,
+                    "Unsupported @color-profile descriptor declaration: '{}', ",
+                    decl
+                )?;
+                parse_error_to_str(err, f)
+            },
             ContextualParseError::InvalidKeyframeRule(rule, ref err) => {
                 write!(f, "Invalid keyframe rule: '{}', ", rule)?;
                 parse_error_to_str(err, f)
@@ -9,6 +9,7 @@ use crate::gecko_bindings::structs;
 use crate::media_queries::{Device, MediaType};
 use crate::queries::feature::{AllowsRanges, Evaluator, FeatureFlags, QueryFeatureDescription};
 use crate::queries::values::{Orientation, PrefersColorScheme};
+pub use crate::queries::values::ColorGamut;
 use crate::values::computed::{CSSPixelLength, Context, Ratio, Resolution};
 use crate::values::specified::color::ForcedColors;
 use crate::values::AtomString;
@@ -144,20 +145,6 @@ fn eval_monochrome(context: &Context) -> i32 {
     unsafe { bindings::Gecko_MediaFeatures_GetMonochromeBitsPerPixel(context.device().document()) }
 }
 
-/// Values for the color-gamut media feature.
-/// This implements PartialOrd so that lower values will correctly match
-/// higher capabilities.
-#[derive(Clone, Copy, Debug, FromPrimitive, Parse, PartialEq, PartialOrd, ToCss)]
-#[repr(u8)]
-pub enum ColorGamut {
-    /// The sRGB gamut.
-    Srgb,
-    /// The gamut specified by the Display P3 Color Space.
-    P3,
-    /// The gamut specified by the ITU-R Recommendation BT.2020 Color Space.
-    Rec2020,
-}
-
 /// https://drafts.csswg.org/mediaqueries-4/#color-gamut
 fn eval_color_gamut(context: &Context, query_value: Option<ColorGamut>) -> bool {
     let query_value = match query_value {
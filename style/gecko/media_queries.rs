@@ -4,6 +4,7 @@
 
 //! Gecko's media-query device and expression representation.
 
+use crate::color::profile::ColorProfile;
 use crate::color::AbsoluteColor;
 use crate::context::QuirksMode;
 use crate::custom_properties::CssEnvironment;
@@ -16,6 +17,7 @@ use crate::media_queries::MediaType;
 use crate::properties::ComputedValues;
 use crate::string_cache::Atom;
 use crate::values::computed::font::GenericFontFamily;
+use crate::values::computed::random::RandomBaseValues;
 use crate::values::computed::{ColorScheme, Length, NonNegativeLength};
 use crate::values::specified::color::{ColorSchemeFlags, ForcedColors, SystemColor};
 use crate::values::specified::font::{FONT_MEDIUM_LINE_HEIGHT_PX, FONT_MEDIUM_PX};
@@ -70,6 +72,8 @@ pub struct Device {
     /// The CssEnvironment object responsible of getting CSS environment
     /// variables.
     environment: CssEnvironment,
+    /// The state used to resolve `random()` and `random-item()` functions.
+    random_base_values: RandomBaseValues,
 }
 
 impl fmt::Debug for Device {
@@ -109,7 +113,8 @@ impl Device {
             used_font_metrics: AtomicBool::new(false),
             used_viewport_size: AtomicBool::new(false),
             used_dynamic_viewport_size: AtomicBool::new(false),
-            environment: CssEnvironment,
+            environment: CssEnvironment::default(),
+            random_base_values: RandomBaseValues::default(),
         }
     }
 
@@ -119,6 +124,24 @@ impl Device {
         &self.environment
     }
 
+    /// Returns the color profile loaded for the `@color-profile` rule with the given name, if
+    /// any. Gecko doesn't support color profiles yet.
+    #[inline]
+    pub fn color_profile(&self, _name: &Atom) -> Option<&ColorProfile> {
+        None
+    }
+
+    /// Get the state used to resolve `random()` and `random-item()` functions.
+    #[inline]
+    pub fn random_base_values(&self) -> &RandomBaseValues {
+        &self.random_base_values
+    }
+
+    /// Set the seed of the values of `random()` and `random-item()` functions.
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.random_base_values.set_seed(seed);
+    }
+
     /// Returns the computed line-height for the font in a given computed values instance.
     ///
     /// If you pass down an element, then the used line-height is returned.
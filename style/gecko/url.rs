@@ -17,7 +17,7 @@ use std::collections::HashMap;
 use std::fmt::{self, Write};
 use std::mem::ManuallyDrop;
 use std::sync::RwLock;
-use style_traits::{CssWriter, ParseError, ToCss};
+use style_traits::{CssWriter, ParseError, StyleParseErrorKind, ToCss};
 use to_shmem::{SharedMemoryBuilder, ToShmem};
 
 /// A CSS url() value for gecko.
@@ -66,6 +66,9 @@ impl CssUrl {
         input: &mut Parser<'i, 't>,
         cors_mode: CorsMode,
     ) -> Result<Self, ParseError<'i>> {
+        if !context.allows_urls() {
+            return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
         let url = input.expect_url()?;
         Ok(Self::parse_from_string(
             url.as_ref().to_owned(),
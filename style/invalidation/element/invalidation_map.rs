@@ -6,7 +6,8 @@
 
 use crate::context::QuirksMode;
 use crate::selector_map::{
-    MaybeCaseInsensitiveHashMap, PrecomputedHashMap, SelectorMap, SelectorMapEntry,
+    MaybeCaseInsensitiveHashMap, PrecomputedHashMap, PrecomputedHashSet, SelectorMap,
+    SelectorMapEntry,
 };
 use crate::selector_parser::{NonTSPseudoClass, SelectorImpl};
 use crate::values::AtomIdent;
@@ -287,6 +288,9 @@ pub struct InvalidationMap {
     pub other_attribute_affecting_selectors: LocalNameDependencyMap,
     /// A map of CSS custom states
     pub custom_state_affecting_selectors: CustomStateDependencyMap,
+    /// The attributes referenced from `attr()` functions in declarations. Elements whose style
+    /// substituted an `attr()` function need to be restyled when any of these change.
+    pub attr_function_dependencies: PrecomputedHashSet<LocalName>,
 }
 
 /// Tree-structural pseudoclasses that we care about for (Relative selector) invalidation.
@@ -404,6 +408,7 @@ impl InvalidationMap {
             document_state_selectors: Vec::new(),
             other_attribute_affecting_selectors: LocalNameDependencyMap::default(),
             custom_state_affecting_selectors: CustomStateDependencyMap::default(),
+            attr_function_dependencies: PrecomputedHashSet::default(),
         }
     }
 
@@ -422,7 +427,8 @@ impl InvalidationMap {
                 .fold(0, |accum, (_, ref v)| accum + v.len()) +
             self.custom_state_affecting_selectors
                 .iter()
-                .fold(0, |accum, (_, ref v)| accum + v.len())
+                .fold(0, |accum, (_, ref v)| accum + v.len()) +
+            self.attr_function_dependencies.len()
     }
 
     /// Clears this map, leaving it empty.
@@ -433,6 +439,7 @@ impl InvalidationMap {
         self.document_state_selectors.clear();
         self.other_attribute_affecting_selectors.clear();
         self.custom_state_affecting_selectors.clear();
+        self.attr_function_dependencies.clear();
     }
 
     /// Shrink the capacity of hash maps if needed.
@@ -442,6 +449,7 @@ impl InvalidationMap {
         self.state_affecting_selectors.shrink_if_needed();
         self.other_attribute_affecting_selectors.shrink_if_needed();
         self.custom_state_affecting_selectors.shrink_if_needed();
+        self.attr_function_dependencies.shrink_if_needed();
     }
 }
 
@@ -5,6 +5,7 @@
 //! An invalidation processor for style changes due to state and attribute
 //! changes.
 
+use crate::computed_value_flags::ComputedValueFlags;
 use crate::context::SharedStyleContext;
 use crate::data::ElementData;
 use crate::dom::{TElement, TNode};
@@ -17,9 +18,10 @@ use crate::invalidation::element::invalidator::{Invalidation, InvalidationProces
 use crate::invalidation::element::restyle_hints::RestyleHint;
 use crate::selector_map::SelectorMap;
 use crate::selector_parser::Snapshot;
+use crate::shared_lock::SharedRwLockReadGuard;
 use crate::stylesheets::origin::OriginSet;
 use crate::values::AtomIdent;
-use crate::{Atom, WeakAtom};
+use crate::{Atom, LocalName, WeakAtom};
 use dom::ElementState;
 use selectors::attr::CaseSensitivity;
 use selectors::kleene_value::KleeneValue;
@@ -29,6 +31,31 @@ use selectors::matching::{
 };
 use smallvec::SmallVec;
 
+/// Returns whether the style attribute of the element references any of the changed attributes
+/// from an `attr()` function. Style attributes aren't part of any invalidation map, so they need
+/// to be checked separately.
+fn style_attribute_references_changed_attribute<E: TElement>(
+    element: E,
+    snapshot: &Snapshot,
+    guard: &SharedRwLockReadGuard,
+) -> bool {
+    let Some(block) = element.style_attribute() else {
+        return false;
+    };
+    let mut referenced = SmallVec::<[LocalName; 4]>::new();
+    block.read_with(guard).each_unsubstituted_value(|value| {
+        value.each_referenced_attribute(|name| referenced.push(LocalName::from(name.as_ref())))
+    });
+    if referenced.is_empty() {
+        return false;
+    }
+    let mut changed = false;
+    snapshot.each_attr_changed(|attribute| {
+        changed |= referenced.contains(attribute);
+    });
+    changed
+}
+
 /// The collector implementation.
 struct Collector<'a, 'b: 'a, 'selectors: 'a, E>
 where
@@ -48,6 +75,7 @@ where
     state_changes: ElementState,
     descendant_invalidations: &'a mut DescendantInvalidationLists<'selectors>,
     sibling_invalidations: &'a mut InvalidationVector<'selectors>,
+    uses_attr_function: bool,
     invalidates_self: bool,
 }
 
@@ -352,6 +380,10 @@ where
                 custom_states_added: &custom_states_added,
                 descendant_invalidations,
                 sibling_invalidations,
+                uses_attr_function: self.data.has_styles() &&
+                    self.data
+                        .styles
+                        .has_flag(ComputedValueFlags::USES_ATTR_FUNCTION),
                 invalidates_self: false,
             };
 
@@ -373,6 +405,14 @@ where
                 collector.collect_dependencies_in_invalidation_map(data.invalidation_map());
             }
 
+            if collector.uses_attr_function && !collector.invalidates_self {
+                collector.invalidates_self = style_attribute_references_changed_attribute(
+                    element,
+                    snapshot,
+                    self.shared_context.guards.author,
+                );
+            }
+
             collector.invalidates_self
         };
 
@@ -480,6 +520,9 @@ where
         }
 
         self.snapshot.each_attr_changed(|attribute| {
+            if self.uses_attr_function && map.attr_function_dependencies.contains(attribute) {
+                self.invalidates_self = true;
+            }
             if let Some(deps) = map.other_attribute_affecting_selectors.get(attribute) {
                 for dep in deps {
                     self.scan_dependency(dep);
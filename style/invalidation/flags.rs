@@ -0,0 +1,78 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Invalidates style of all elements whose styles have some computed value
+//! flags.
+
+use crate::computed_value_flags::ComputedValueFlags;
+use crate::data::ElementStyles;
+use crate::dom::{TElement, TNode};
+use crate::invalidation::element::restyle_hints::RestyleHint;
+
+/// Invalidates style of all elements whose styles have any of the given flags,
+/// like `ComputedValueFlags::DEPENDS_ON_ENVIRONMENT` when the environment or
+/// the media features change.
+///
+/// Returns whether any element was invalidated.
+pub fn invalidate_for_flag<E>(root: E, flag: ComputedValueFlags) -> bool
+where
+    E: TElement,
+{
+    debug!("invalidation::flags::invalidate_for_flag({:?}, {:?})", root, flag);
+    invalidate_recursively(root, &|styles| {
+        if styles.has_flag(flag) {
+            RestyleHint::RECASCADE_SELF
+        } else {
+            RestyleHint::empty()
+        }
+    })
+}
+
+/// Invalidates style of all the elements in the subtree of `element` with the
+/// hint that `hint_for_styles` returns for their styles.
+pub(super) fn invalidate_recursively<E>(
+    element: E,
+    hint_for_styles: &dyn Fn(&ElementStyles) -> RestyleHint,
+) -> bool
+where
+    E: TElement,
+{
+    let mut data = match element.mutate_data() {
+        Some(data) => data,
+        None => return false,
+    };
+
+    if data.hint.will_recascade_subtree() {
+        debug!("invalidate_recursively: {:?} was already invalid", element);
+        return false;
+    }
+
+    let hint = if data.has_styles() {
+        hint_for_styles(&data.styles)
+    } else {
+        RestyleHint::empty()
+    };
+    let invalidated = !hint.is_empty();
+    if invalidated {
+        debug!("invalidate_recursively: {:?} invalidated with {:?}", element, hint);
+        data.hint.insert(hint);
+    }
+
+    let mut any_children_invalid = false;
+    for child in element.traversal_children() {
+        if let Some(child) = child.as_element() {
+            any_children_invalid |= invalidate_recursively(child, hint_for_styles);
+        }
+    }
+
+    if any_children_invalid {
+        debug!(
+            "invalidate_recursively: Children of {:?} changed, setting dirty descendants",
+            element
+        );
+        unsafe { element.set_dirty_descendants() }
+    }
+
+    invalidated || any_children_invalid
+}
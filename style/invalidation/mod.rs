@@ -5,6 +5,7 @@
 //! Different bits of code related to invalidating style.
 
 pub mod element;
+pub mod flags;
 pub mod media_queries;
 pub mod stylesheets;
 pub mod viewport_units;
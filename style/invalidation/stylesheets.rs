@@ -636,7 +636,11 @@ impl StylesheetInvalidationSet {
                     // Do nothing, this animation can't affect the style of existing elements.
                 }
             },
-            CounterStyle(..) | Property(..) | FontFeatureValues(..) | FontPaletteValues(..) => {
+            CounterStyle(..) |
+            Property(..) |
+            FontFeatureValues(..) |
+            FontPaletteValues(..) |
+            ColorProfile(..) => {
                 debug!(" > Found unsupported rule, marking the whole subtree invalid.");
                 self.invalidate_fully();
             },
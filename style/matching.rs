@@ -364,7 +364,6 @@ trait PrivateMatchMethods: TElement {
         }
     }
 
-    #[cfg(feature = "gecko")]
     fn maybe_resolve_starting_style(
         &self,
         context: &mut StyleContext<Self>,
@@ -578,10 +577,16 @@ trait PrivateMatchMethods: TElement {
         use crate::animation::AnimationSetKey;
         use crate::dom::TDocument;
 
+        let starting_values = self.maybe_resolve_starting_style(
+            context,
+            old_styles.primary.as_ref(),
+            new_resolved_styles,
+        );
         let style_changed = self.process_animations_for_style(
             context,
             &mut old_styles.primary,
             new_resolved_styles.primary_style_mut(),
+            starting_values,
             /* pseudo_element = */ None,
         );
 
@@ -668,6 +673,7 @@ trait PrivateMatchMethods: TElement {
             context,
             &mut old_style,
             &mut style,
+            /* starting_values = */ None,
             Some(pseudo_element.clone()),
         );
 
@@ -728,10 +734,15 @@ trait PrivateMatchMethods: TElement {
         context: &mut StyleContext<Self>,
         old_values: &mut Option<Arc<ComputedValues>>,
         new_values: &mut Arc<ComputedValues>,
+        starting_values: Option<Arc<ComputedValues>>,
         pseudo_element: Option<PseudoElement>,
     ) -> bool {
         use crate::animation::{AnimationSetKey, AnimationState};
 
+        // The before-change style is the starting style if we don't have a valid
+        // previous style, that is, if the element is new or was `display: none`.
+        let before_change_values = starting_values.as_ref().or(old_values.as_ref());
+
         // We need to call this before accessing the `ElementAnimationSet` from the
         // map because this call will do a RwLock::read().
         let needs_animations_update = self.needs_animations_update(
@@ -743,7 +754,7 @@ trait PrivateMatchMethods: TElement {
 
         let might_need_transitions_update = self.might_need_transitions_update(
             context,
-            old_values.as_deref(),
+            before_change_values.map(|s| &**s),
             new_values,
             pseudo_element,
         );
@@ -764,8 +775,12 @@ trait PrivateMatchMethods: TElement {
 
         // Starting animations is expensive, because we have to recalculate the style
         // for all the keyframes. We only want to do this if we think that there's a
-        // chance that the animations really changed.
-        if needs_animations_update {
+        // chance that the animations really changed, if the timeline ranges they may
+        // refer to changed, or if script created new ones.
+        if needs_animations_update ||
+            animation_set.timeline_ranges_changed() ||
+            animation_set.needs_script_keyframe_resolution()
+        {
             let mut resolver = StyleResolverForElement::new(
                 *self,
                 context,
@@ -784,7 +799,7 @@ trait PrivateMatchMethods: TElement {
         animation_set.update_transitions_for_new_style(
             might_need_transitions_update,
             &shared_context,
-            old_values.as_ref(),
+            before_change_values,
             after_change_style.as_ref().unwrap_or(new_values),
         );
 
@@ -5,9 +5,17 @@
 //! The context within which CSS code is parsed.
 
 use crate::context::QuirksMode;
+use crate::dom::{OpaqueNode, TreeCountingProvider};
 use crate::error_reporting::{ContextualParseError, ParseErrorReporter};
+use crate::queries::feature_expression::{QueryFeatureProgress, QueryProgressProvider};
 use crate::stylesheets::{CssRuleType, CssRuleTypes, Namespaces, Origin, UrlExtraData};
 use crate::use_counters::UseCounters;
+use crate::values::computed::length::RelativeLengthProvider;
+use crate::values::computed::Length;
+use crate::values::computed::random::RandomValueProvider;
+use crate::values::specified::calc::RandomValueSharing;
+use crate::values::specified::NoCalcLength;
+use crate::values::CSSFloat;
 use cssparser::{Parser, SourceLocation, UnicodeRange};
 use selectors::parser::ParseRelative;
 use std::borrow::Cow;
@@ -86,6 +94,17 @@ pub struct ParserContext<'a> {
     pub use_counters: Option<&'a UseCounters>,
     /// Current nesting context.
     pub nesting_context: NestingContext,
+    /// The element tree-counting functions should be resolved against, if we're parsing at
+    /// computed-value time, e.g. after substituting variables.
+    pub tree_counting_provider: Option<&'a dyn TreeCountingProvider>,
+    /// The source of the base values of random functions, if we're parsing at computed-value time.
+    pub random_provider: Option<&'a dyn RandomValueProvider>,
+    /// The evaluator of `media-progress()` and `container-progress()` functions, if we're parsing
+    /// at computed-value time.
+    pub query_progress_provider: Option<&'a dyn QueryProgressProvider>,
+    /// The resolver of relative lengths in calculations that divide them into other types, if
+    /// we're parsing at computed-value time.
+    pub relative_length_provider: Option<&'a dyn RelativeLengthProvider>,
 }
 
 impl<'a> ParserContext<'a> {
@@ -110,6 +129,10 @@ impl<'a> ParserContext<'a> {
             namespaces,
             use_counters,
             nesting_context: NestingContext::new_from_rule(rule_type),
+            tree_counting_provider: None,
+            random_provider: None,
+            query_progress_provider: None,
+            relative_length_provider: None,
         }
     }
 
@@ -175,6 +198,63 @@ impl<'a> ParserContext<'a> {
     pub fn allows_computational_dependence(&self) -> bool {
         self.parsing_mode.allows_computational_dependence()
     }
+
+    /// Whether URLs are allowed, which isn't the case for values substituted from `attr()`.
+    #[inline]
+    pub fn allows_urls(&self) -> bool {
+        self.parsing_mode.allows_urls()
+    }
+
+    /// Returns a copy of this context which resolves the functions that depend on the element
+    /// being styled to placeholder values, in order to validate declarations that can only be
+    /// parsed at computed-value time.
+    pub fn with_placeholder_providers(&self) -> ParserContext<'_> {
+        let mut context = ParserContext {
+            namespaces: Cow::Borrowed(&*self.namespaces),
+            ..*self
+        };
+        context.tree_counting_provider = Some(&PlaceholderProvider);
+        context.random_provider = Some(&PlaceholderProvider);
+        context.query_progress_provider = Some(&PlaceholderProvider);
+        context.relative_length_provider = Some(&PlaceholderProvider);
+        context
+    }
+}
+
+/// Resolves the functions that depend on the element being styled to arbitrary values, see
+/// `ParserContext::with_placeholder_providers`.
+struct PlaceholderProvider;
+
+impl TreeCountingProvider for PlaceholderProvider {
+    fn sibling_index(&self) -> u32 {
+        1
+    }
+
+    fn sibling_count(&self) -> u32 {
+        1
+    }
+
+    fn element(&self) -> Option<OpaqueNode> {
+        None
+    }
+}
+
+impl RandomValueProvider for PlaceholderProvider {
+    fn random_base_value(&self, _: &RandomValueSharing, _: &str) -> CSSFloat {
+        0.
+    }
+}
+
+impl QueryProgressProvider for PlaceholderProvider {
+    fn query_progress(&self, _: &QueryFeatureProgress) -> CSSFloat {
+        0.
+    }
+}
+
+impl RelativeLengthProvider for PlaceholderProvider {
+    fn compute_length(&self, _: &NoCalcLength) -> Length {
+        Length::new(1.)
+    }
 }
 
 /// A trait to abstract parsing of a specified value given a `ParserContext` and
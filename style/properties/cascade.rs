@@ -10,7 +10,7 @@ use crate::computed_value_flags::ComputedValueFlags;
 use crate::custom_properties::{
     CustomPropertiesBuilder, DeferFontRelativeCustomPropertyResolution,
 };
-use crate::dom::TElement;
+use crate::dom::{ElementTreeCountingProvider, TElement, TreeCountingProvider};
 use crate::logical_geometry::WritingMode;
 use crate::properties::{
     property_counts, CSSWideKeyword, ComputedValues, DeclarationImportanceIterator, Importance,
@@ -29,6 +29,7 @@ use crate::stylist::Stylist;
 use crate::values::specified::length::FontBaseSize;
 use crate::values::{computed, specified};
 use fxhash::FxHashMap;
+use selectors::NthIndexCache;
 use servo_arc::Arc;
 use smallvec::SmallVec;
 use std::borrow::Cow;
@@ -73,10 +74,18 @@ pub fn cascade<E>(
     rule_cache: Option<&RuleCache>,
     rule_cache_conditions: &mut RuleCacheConditions,
     element: Option<E>,
+    nth_index_cache: Option<&mut NthIndexCache>,
 ) -> Arc<ComputedValues>
 where
     E: TElement,
 {
+    let mut uncached_nth_indices = NthIndexCache::default();
+    let tree_counting_provider = element.map(|element| {
+        ElementTreeCountingProvider::new(
+            element,
+            nth_index_cache.unwrap_or(&mut uncached_nth_indices),
+        )
+    });
     cascade_rules(
         stylist,
         pseudo,
@@ -90,6 +99,9 @@ where
         rule_cache,
         rule_cache_conditions,
         element,
+        tree_counting_provider
+            .as_ref()
+            .map(|provider| provider as &dyn TreeCountingProvider),
     )
 }
 
@@ -189,6 +201,7 @@ fn cascade_rules<E>(
     rule_cache: Option<&RuleCache>,
     rule_cache_conditions: &mut RuleCacheConditions,
     element: Option<E>,
+    tree_counting_provider: Option<&dyn TreeCountingProvider>,
 ) -> Arc<ComputedValues>
 where
     E: TElement,
@@ -207,6 +220,7 @@ where
         rule_cache,
         rule_cache_conditions,
         element,
+        tree_counting_provider,
     )
 }
 
@@ -225,10 +239,10 @@ pub enum CascadeMode<'a, 'b> {
     },
 }
 
-fn iter_declarations<'builder, 'decls: 'builder>(
+fn iter_declarations<'builder, 'decls: 'builder, 'context: 'builder>(
     iter: impl Iterator<Item = (&'decls PropertyDeclaration, CascadePriority)>,
     declarations: &mut Declarations<'decls>,
-    mut custom_builder: Option<&mut CustomPropertiesBuilder<'builder, 'decls>>,
+    mut custom_builder: Option<&mut CustomPropertiesBuilder<'builder, 'context>>,
 ) {
     for (declaration, priority) in iter {
         if let PropertyDeclaration::Custom(ref declaration) = *declaration {
@@ -263,6 +277,7 @@ pub fn apply_declarations<'a, E, I>(
     rule_cache: Option<&'a RuleCache>,
     rule_cache_conditions: &'a mut RuleCacheConditions,
     element: Option<E>,
+    tree_counting_provider: Option<&'a dyn TreeCountingProvider>,
 ) -> Arc<ComputedValues>
 where
     E: TElement + 'a,
@@ -292,6 +307,12 @@ where
         rule_cache_conditions,
         container_size_query,
     );
+    if let Some(ref element) = element {
+        context.attribute_provider = element;
+    }
+    if let Some(tree_counting_provider) = tree_counting_provider {
+        context.tree_counting_provider = tree_counting_provider;
+    }
 
     context.style().add_flags(cascade_input_flags);
 
@@ -418,15 +439,12 @@ fn tweak_when_ignoring_colors(
     }
 
     // Always honor colors if forced-color-adjust is set to none.
-    #[cfg(feature = "gecko")]
-    {
-        let forced = context
-            .builder
-            .get_inherited_text()
-            .clone_forced_color_adjust();
-        if forced == computed::ForcedColorAdjust::None {
-            return;
-        }
+    let forced = context
+        .builder
+        .get_inherited_text()
+        .clone_forced_color_adjust();
+    if forced == computed::ForcedColorAdjust::None {
+        return;
     }
 
     // Don't override background-color on ::-moz-color-swatch. It is set as an
@@ -825,7 +843,6 @@ impl<'b> Cascade<'b> {
         #[cfg(feature = "gecko")]
         apply!(FontSizeAdjust);
 
-        #[cfg(feature = "gecko")]
         apply!(ForcedColorAdjust);
         // color-scheme needs to be after forced-color-adjust, since it's one of the "skipped in
         // forced-colors-mode" properties.
@@ -1008,6 +1025,7 @@ impl<'b> Cascade<'b> {
             None, // rule_cache
             &mut *context.rule_cache_conditions.borrow_mut(),
             element,
+            Some(context.tree_counting_provider),
         );
         context.builder.visited_style = Some(style);
     }
@@ -1111,7 +1129,8 @@ impl<'b> Cascade<'b> {
             ComputedValueFlags::DEPENDS_ON_SELF_FONT_METRICS |
             ComputedValueFlags::DEPENDS_ON_INHERITED_FONT_METRICS |
             ComputedValueFlags::USES_CONTAINER_UNITS |
-            ComputedValueFlags::USES_VIEWPORT_UNITS;
+            ComputedValueFlags::USES_VIEWPORT_UNITS |
+            ComputedValueFlags::DEPENDS_ON_ENVIRONMENT;
         builder.add_flags(style.flags & bits_to_copy);
 
         true
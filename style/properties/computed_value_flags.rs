@@ -135,6 +135,25 @@ bitflags! {
 
         /// Whether this style considered a scope style rule.
         const CONSIDERED_NONTRIVIAL_SCOPED_STYLE = 1 << 26;
+
+        /// Whether the style substituted any `env()` function or evaluated any
+        /// media feature outside of a media query, through a `media-progress()`
+        /// function, and thus needs to be recomputed when the environment or
+        /// the media features change.
+        const DEPENDS_ON_ENVIRONMENT = 1 << 27;
+
+        /// Whether the style substituted any `attr()` function, and thus needs
+        /// to be recomputed when the referenced attributes change.
+        const USES_ATTR_FUNCTION = 1 << 28;
+
+        /// Whether the style resolved any `sibling-index()` or `sibling-count()`
+        /// function, and thus depends on the position of the element among its
+        /// siblings.
+        const USES_TREE_COUNTING_FUNCTIONS = 1 << 29;
+
+        /// Whether the style resolved any `random()` or `random-item()` function
+        /// whose value is specific to the element.
+        const USES_PER_ELEMENT_RANDOM_VALUES = 1 << 30;
     }
 }
 
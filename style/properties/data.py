@@ -137,7 +137,7 @@ def maybe_moz_logical_alias(engine, side, prop):
 
 def to_rust_ident(name):
     name = name.replace("-", "_")
-    if name in ["static", "super", "box", "move"]:  # Rust keywords
+    if name in ["static", "super", "box", "move", "continue"]:  # Rust keywords
         name += "_"
     return name
 
@@ -7,10 +7,10 @@
 #![deny(missing_docs)]
 
 use super::{
-    property_counts, AllShorthand, ComputedValues, LogicalGroupSet, LonghandIdSet,
-    LonghandIdSetIterator, NonCustomPropertyIdSet, PropertyDeclaration, PropertyDeclarationId,
-    PropertyId, ShorthandId, SourcePropertyDeclaration, SourcePropertyDeclarationDrain,
-    SubpropertiesVec,
+    property_counts, AllShorthand, ComputedValues, CustomDeclarationValue, LogicalGroupSet,
+    LonghandIdSet, LonghandIdSetIterator, NonCustomPropertyIdSet, PropertyDeclaration,
+    PropertyDeclarationId, PropertyId, ShorthandId, SourcePropertyDeclaration,
+    SourcePropertyDeclarationDrain, SubpropertiesVec,
 };
 use crate::context::QuirksMode;
 use crate::custom_properties;
@@ -425,6 +425,24 @@ impl PropertyDeclarationBlock {
         AnimationValueIterator::new(self, context, style, default_values)
     }
 
+    /// Calls `f` with the value of each declaration in this block that still needs its
+    /// substitution functions replaced.
+    pub fn each_unsubstituted_value(&self, mut f: impl FnMut(&custom_properties::VariableValue)) {
+        for declaration in self.declarations.iter() {
+            let value = match *declaration {
+                PropertyDeclaration::WithVariables(ref declaration) => {
+                    &declaration.value.variable_value
+                },
+                PropertyDeclaration::Custom(ref declaration) => match declaration.value {
+                    CustomDeclarationValue::Unparsed(ref value) => &**value,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            f(value);
+        }
+    }
+
     /// Returns whether this block contains any declaration with `!important`.
     ///
     /// This is based on the `declarations_importance` bit-vector,
@@ -12,15 +12,14 @@
 #[cfg(feature = "gecko")] use crate::gecko_bindings::structs::nsCSSPropertyID;
 use crate::properties::{
     longhands::{
-        self, visibility::computed_value::T as Visibility,
+        self, content_visibility::computed_value::T as ContentVisibility,
+        visibility::computed_value::T as Visibility,
     },
     CSSWideKeyword, LonghandId, NonCustomPropertyIterator,
     PropertyDeclaration, PropertyDeclarationId,
 };
-#[cfg(feature = "gecko")] use crate::properties::{
-    longhands::content_visibility::computed_value::T as ContentVisibility,
-    NonCustomPropertyId,
-};
+#[cfg(feature = "gecko")] use crate::properties::NonCustomPropertyId;
+#[cfg(feature = "servo")] use crate::properties::longhands::display::computed_value::T as Display;
 use std::ptr;
 use std::mem;
 use fxhash::FxHashMap;
@@ -35,6 +34,7 @@ use crate::values::distance::{ComputeSquaredDistance, SquaredDistance};
 use crate::values::generics::effects::Filter;
 use void::{self, Void};
 use crate::properties_and_values::value::CustomAnimatedValue;
+use crate::stylist::Stylist;
 
 /// Convert nsCSSPropertyID to TransitionProperty
 #[cfg(feature = "gecko")]
@@ -437,7 +437,7 @@ impl AnimationValue {
     /// SERVO ONLY: This doesn't properly handle things like updating 'em' units
     /// when animated font-size.
     #[cfg(feature = "servo")]
-    pub fn set_in_style_for_servo(&self, style: &mut ComputedValues) {
+    pub fn set_in_style_for_servo(&self, style: &mut ComputedValues, stylist: &Stylist) {
         match self {
             % for prop in data.longhands:
             % if prop.animatable and not prop.logical:
@@ -454,13 +454,15 @@ impl AnimationValue {
             AnimationValue::${prop.camel_case}(..) => unreachable!(),
             % endif
             % endfor
-            AnimationValue::Custom(..) => unreachable!(),
+            AnimationValue::Custom(ref value) => {
+                value.set_in_custom_properties(&mut style.custom_properties, stylist);
+            }
         }
     }
 
     /// As above, but a stub for Gecko.
     #[cfg(feature = "gecko")]
-    pub fn set_in_style_for_servo(&self, _: &mut ComputedValues) {
+    pub fn set_in_style_for_servo(&self, _: &mut ComputedValues, _: &Stylist) {
     }
 }
 
@@ -472,6 +474,28 @@ fn animate_discrete<T: Clone>(this: &T, other: &T, procedure: Procedure) -> Resu
     }
 }
 
+/// Discrete animation for values where `hidden` stops rendering the element,
+/// like `display: none`. When interpolating between `hidden` and any other
+/// value, `hidden` is only used at the endpoints, so that entry and exit
+/// animations keep the element rendered while they run.
+fn animate_discrete_with_hidden_endpoints<T: Clone + PartialEq>(
+    this: &T,
+    other: &T,
+    hidden: &T,
+    procedure: Procedure,
+) -> Result<T, ()> {
+    if let Procedure::Interpolate { .. } = procedure {
+        let (this_weight, other_weight) = procedure.weights();
+        if *this == *hidden && *other != *hidden {
+            return Ok(if other_weight > 0.0 { other.clone() } else { this.clone() });
+        }
+        if *this != *hidden && *other == *hidden {
+            return Ok(if this_weight > 0.0 { this.clone() } else { other.clone() });
+        }
+    }
+    animate_discrete(this, other, procedure)
+}
+
 impl Animate for AnimationValue {
     fn animate(&self, other: &Self, procedure: Procedure) -> Result<Self, ()> {
         Ok(unsafe {
@@ -484,7 +508,17 @@ impl Animate for AnimationValue {
             }
 
             match *self {
-                <% keyfunc = lambda x: (x.animated_type(), x.animation_type == "discrete") %>
+                <%
+                    # These are discretely animatable, but have their own Animate
+                    # implementation.
+                    keyfunc = lambda x: (
+                        x.animated_type(),
+                        x.animation_type == "discrete" and not (
+                            x.name == "content-visibility" or
+                            (engine == "servo" and x.name == "display")
+                        ),
+                    )
+                %>
                 % for (ty, discrete), props in groupby(animated, key=keyfunc):
                 ${" |\n".join("{}(ref this)".format(prop.camel_case) for prop in props)} => {
                     let other_repr =
@@ -608,41 +642,19 @@ impl ToAnimatedZero for Visibility {
 }
 
 /// <https://drafts.csswg.org/css-contain-3/#content-visibility-animation>
-#[cfg(feature = "gecko")]
 impl Animate for ContentVisibility {
     #[inline]
     fn animate(&self, other: &Self, procedure: Procedure) -> Result<Self, ()> {
-        match procedure {
-            Procedure::Interpolate { .. } => {
-                let (this_weight, other_weight) = procedure.weights();
-                match (*self, *other) {
-                    (ContentVisibility::Hidden, _) => {
-                        Ok(if other_weight > 0.0 { *other } else { *self })
-                    },
-                    (_, ContentVisibility::Hidden) => {
-                        Ok(if this_weight > 0.0 { *self } else { *other })
-                    },
-                    _ => Err(()),
-                }
-            },
-            _ => Err(()),
-        }
-    }
-}
-
-#[cfg(feature = "gecko")]
-impl ComputeSquaredDistance for ContentVisibility {
-    #[inline]
-    fn compute_squared_distance(&self, other: &Self) -> Result<SquaredDistance, ()> {
-        Ok(SquaredDistance::from_sqrt(if *self == *other { 0. } else { 1. }))
+        animate_discrete_with_hidden_endpoints(self, other, &ContentVisibility::Hidden, procedure)
     }
 }
 
-#[cfg(feature = "gecko")]
-impl ToAnimatedZero for ContentVisibility {
+/// <https://drafts.csswg.org/css-display-4/#display-animation>
+#[cfg(feature = "servo")]
+impl Animate for Display {
     #[inline]
-    fn to_animated_zero(&self) -> Result<Self, ()> {
-        Err(())
+    fn animate(&self, other: &Self, procedure: Procedure) -> Result<Self, ()> {
+        animate_discrete_with_hidden_endpoints(self, other, &Display::None, procedure)
     }
 }
 
@@ -113,7 +113,7 @@ ${helpers.single_keyword(
     saturation color luminosity""",
     gecko_enum_prefix="StyleBlend",
     vector=True,
-    engines="gecko",
+    engines="gecko servo",
     animation_type="discrete",
     gecko_inexhaustive=True,
     spec="https://drafts.fxtf.org/compositing/#background-blend-mode",
@@ -486,7 +486,9 @@ ${helpers.predefined_type(
     "content-visibility",
     "ContentVisibility",
     "computed::ContentVisibility::Visible",
-    engines="gecko",
+    engines="gecko servo",
+    servo_pref="layout.css.content-visibility.enabled",
+    animation_type="discrete",
     spec="https://drafts.csswg.org/css-contain/#content-visibility",
     affects="layout",
 )}
@@ -554,7 +556,7 @@ ${helpers.predefined_type(
     "will-change",
     "WillChange",
     "computed::WillChange::auto()",
-    engines="gecko",
+    engines="gecko servo",
     animation_type="none",
     spec="https://drafts.csswg.org/css-will-change/#will-change",
     affects="layout",
@@ -602,11 +604,29 @@ ${helpers.predefined_type(
     "-webkit-line-clamp",
     "LineClamp",
     "computed::LineClamp::none()",
-    engines="gecko",
+    engines="gecko servo",
     spec="https://drafts.csswg.org/css-overflow-3/#line-clamp",
     affects="layout",
 )}
 
+${helpers.predefined_type(
+    "max-lines",
+    "LineClamp",
+    "computed::LineClamp::none()",
+    engines="servo",
+    spec="https://drafts.csswg.org/css-overflow-4/#max-lines",
+    affects="layout",
+)}
+
+${helpers.single_keyword(
+    "continue",
+    "auto discard collapse -webkit-legacy",
+    engines="servo",
+    animation_type="discrete",
+    spec="https://drafts.csswg.org/css-overflow-4/#continue",
+    affects="layout",
+)}
+
 ${helpers.predefined_type(
     "scrollbar-gutter",
     "ScrollbarGutter",
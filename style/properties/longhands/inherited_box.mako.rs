@@ -98,3 +98,12 @@ ${helpers.single_keyword(
     spec="https://drafts.csswg.org/css-images/#propdef-image-orientation",
     affects="layout",
 )}
+
+${helpers.single_keyword(
+    "interpolate-size",
+    "numeric-only allow-keywords",
+    engines="servo",
+    animation_type="discrete",
+    spec="https://drafts.csswg.org/css-values-5/#interpolate-size",
+    affects="",
+)}
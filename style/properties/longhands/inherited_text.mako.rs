@@ -357,11 +357,21 @@ ${helpers.predefined_type(
     affects="layout",
 )}
 
+${helpers.predefined_type(
+    "block-ellipsis",
+    "BlockEllipsis",
+    "computed::BlockEllipsis::None",
+    engines="servo",
+    animation_type="discrete",
+    spec="https://drafts.csswg.org/css-overflow-4/#block-ellipsis",
+    affects="layout",
+)}
+
 ${helpers.predefined_type(
     "forced-color-adjust",
     "ForcedColorAdjust",
     "computed::ForcedColorAdjust::Auto",
-    engines="gecko",
+    engines="gecko servo",
     animation_type="discrete",
     spec="https://drafts.csswg.org/css-color-adjust-1/#forced-color-adjust-prop",
     affects="paint",
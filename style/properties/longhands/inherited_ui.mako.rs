@@ -88,7 +88,6 @@ ${helpers.predefined_type(
     "ColorScheme",
     "specified::color::ColorScheme::normal()",
     engines="gecko servo",
-    servo_pref="layout.unimplemented",
     spec="https://drafts.csswg.org/css-color-adjust/#color-scheme-prop",
     animation_type="discrete",
     ignored_when_colors_disabled=True,
@@ -311,7 +311,7 @@ ${helpers.predefined_type(
     need_index=True,
     animation_type="none",
     gecko_pref="layout.css.animation-composition.enabled",
-    servo_pref="layout.unimplemented",
+    servo_pref="layout.css.animation-composition.enabled",
     spec="https://drafts.csswg.org/css-animations-2/#animation-composition",
     affects="",
 )}
@@ -347,6 +347,23 @@ ${helpers.predefined_type(
     affects="",
 )}
 
+% for edge in ["start", "end"]:
+${helpers.predefined_type(
+    "animation-range-" + edge,
+    "AnimationRangeValue",
+    "computed::AnimationRangeValue::normal()",
+    engines="servo",
+    servo_pref="layout.css.scroll-driven-animations.enabled",
+    initial_specified_value="specified::AnimationRangeValue::normal()",
+    vector=True,
+    need_index=True,
+    animation_type="none",
+    spec="https://drafts.csswg.org/scroll-animations-1/#animation-range-" + edge,
+    rule_types_allowed=DEFAULT_RULES_EXCEPT_KEYFRAME,
+    affects="",
+)}
+% endfor
+
 ${helpers.predefined_type(
     "scroll-timeline-name",
     "TimelineName",
@@ -326,7 +326,7 @@ impl From<AliasId> for NonCustomPropertyId {
 
 /// Representation of a CSS property, that is, either a longhand, a shorthand, or a custom
 /// property.
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, Hash, PartialEq, Debug)]
 pub enum PropertyId {
     /// An alias for a shorthand property.
     NonCustom(NonCustomPropertyId),
@@ -360,10 +360,7 @@ impl PropertyId {
     pub fn is_animatable(&self) -> bool {
         match self {
             Self::NonCustom(id) => id.is_animatable(),
-            #[cfg(feature = "gecko")]
             Self::Custom(_) => true,
-            #[cfg(feature = "servo")]
-            Self::Custom(_) => false,
         }
     }
 
@@ -701,8 +698,9 @@ fn parse_non_custom_property_declaration_value_into<'i>(
     context: &ParserContext,
     input: &mut Parser<'i, '_>,
     start: &cssparser::ParserState,
-    parse_entirely_into: impl FnOnce(
+    parse_entirely_into: impl Fn(
         &mut SourcePropertyDeclaration,
+        &ParserContext,
         &mut Parser<'i, '_>,
     ) -> Result<(), ParseError<'i>>,
     parsed_wide_keyword: impl FnOnce(&mut SourcePropertyDeclaration, CSSWideKeyword),
@@ -728,7 +726,7 @@ fn parse_non_custom_property_declaration_value_into<'i>(
 
     input.reset(&start);
     input.look_for_var_or_env_functions();
-    let err = match parse_entirely_into(declarations, input) {
+    let err = match parse_entirely_into(declarations, context, input) {
         Ok(()) => {
             input.seen_var_or_env_functions();
             return Ok(());
@@ -752,15 +750,100 @@ fn parse_non_custom_property_declaration_value_into<'i>(
         }
         at_start = false;
     }
-    if !input.seen_var_or_env_functions() || invalid {
+    let seen_var_or_env = input.seen_var_or_env_functions();
+    if invalid {
         return Err(err);
     }
+    // The tokenizer only keeps track of var() and env(), so look for the other functions that
+    // may prevent parsing the value before computed-value time, to avoid parsing it as a
+    // variable value otherwise.
+    if !seen_var_or_env {
+        input.reset(start);
+        if !contains_computed_time_functions(input) {
+            return Err(err);
+        }
+    }
     input.reset(start);
-    let value = custom_properties::VariableValue::parse(input, &context.url_data)?;
+    let value = match custom_properties::VariableValue::parse(input, context) {
+        Ok(value) => value,
+        Err(e) => return Err(if seen_var_or_env { e } else { err }),
+    };
+    if !seen_var_or_env && !value.has_references() {
+        if !value.uses_tree_counting_functions() &&
+            !value.uses_random_functions() &&
+            !value.uses_query_progress_functions() &&
+            !value.uses_relative_lengths_in_math_functions()
+        {
+            return Err(err);
+        }
+        // The value may be valid once the functions that depend on the element are resolved,
+        // so check whether it is with placeholder values for them, so that invalid values are
+        // still rejected at parse time.
+        input.reset(start);
+        let placeholder_context = context.with_placeholder_providers();
+        let mut placeholder_declarations = SourcePropertyDeclaration::default();
+        if parse_entirely_into(&mut placeholder_declarations, &placeholder_context, input).is_err()
+        {
+            return Err(err);
+        }
+    }
     parsed_custom(declarations, value);
     Ok(())
 }
 
+/// Returns whether the rest of the input contains any function, other than var() and env(),
+/// that may prevent parsing a value before computed-value time.
+fn contains_computed_time_functions(input: &mut Parser) -> bool {
+    while let Ok(token) = input.next() {
+        let has_nested_block = match *token {
+            cssparser::Token::Function(ref name) => {
+                if is_computed_time_function(name) {
+                    return true;
+                }
+                true
+            },
+            cssparser::Token::ParenthesisBlock |
+            cssparser::Token::SquareBracketBlock |
+            cssparser::Token::CurlyBracketBlock => true,
+            _ => false,
+        };
+        if has_nested_block &&
+            input
+                .parse_nested_block(|input| {
+                    Ok::<_, ParseError>(contains_computed_time_functions(input))
+                })
+                .unwrap_or(false)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+fn is_computed_time_function(name: &str) -> bool {
+    use crate::values::specified::calc::{
+        query_progress_feature_type, MathFunction, TreeCountingFunction,
+    };
+
+    if name.eq_ignore_ascii_case("attr") {
+        return static_prefs::pref!("layout.css.attr.enabled");
+    }
+    if name.eq_ignore_ascii_case("if") {
+        return static_prefs::pref!("layout.css.if-function.enabled");
+    }
+    if name.eq_ignore_ascii_case("random") || name.eq_ignore_ascii_case("random-item") {
+        return static_prefs::pref!("layout.css.random-function.enabled");
+    }
+    if TreeCountingFunction::from_function_name(name).is_some() {
+        return static_prefs::pref!("layout.css.tree-counting-functions.enabled");
+    }
+    if query_progress_feature_type(name).is_some() {
+        return static_prefs::pref!("layout.css.progress-functions.enabled");
+    }
+    MathFunction::from_ident(name).is_ok() &&
+        static_prefs::pref!("layout.css.typed-arithmetic.enabled")
+}
+
 impl PropertyDeclaration {
     fn with_variables_from_shorthand(&self, shorthand: ShorthandId) -> Option<&str> {
         match *self {
@@ -851,7 +934,7 @@ impl PropertyDeclaration {
                 let value = match input.try_parse(CSSWideKeyword::parse) {
                     Ok(keyword) => CustomDeclarationValue::CSSWideKeyword(keyword),
                     Err(()) => CustomDeclarationValue::Unparsed(Arc::new(
-                        custom_properties::VariableValue::parse(input, &context.url_data)?,
+                        custom_properties::VariableValue::parse(input, context)?,
                     )),
                 };
                 declarations.push(PropertyDeclaration::Custom(CustomDeclaration {
@@ -869,7 +952,7 @@ impl PropertyDeclaration {
                     context,
                     input,
                     &start,
-                    |declarations, input| {
+                    |declarations, context, input| {
                         let decl = input
                             .parse_entirely(|input| longhand_id.parse_value(context, input))?;
                         declarations.push(decl);
@@ -897,7 +980,9 @@ impl PropertyDeclaration {
                     &start,
                     // Not using parse_entirely here: each ShorthandId::parse_into function needs
                     // to do so *before* pushing to `declarations`.
-                    |declarations, input| shorthand_id.parse_into(declarations, context, input),
+                    |declarations, context, input| {
+                        shorthand_id.parse_into(declarations, context, input)
+                    },
                     |declarations, wk| {
                         if shorthand_id == ShorthandId::All {
                             declarations.all_shorthand = AllShorthand::CSSWideKeyword(wk)
@@ -1089,10 +1174,7 @@ impl<'a> PropertyDeclarationId<'a> {
     pub fn is_animatable(&self) -> bool {
         match self {
             Self::Longhand(id) => id.is_animatable(),
-            #[cfg(feature = "gecko")]
             PropertyDeclarationId::Custom(_) => true,
-            #[cfg(feature = "servo")]
-            PropertyDeclarationId::Custom(_) => false,
         }
     }
 
@@ -1104,6 +1186,9 @@ impl<'a> PropertyDeclarationId<'a> {
             // TODO(bug 1885995): Refine this.
             #[cfg(feature = "gecko")]
             Self::Custom(_) => true,
+            // Registered custom properties interpolate according to their syntax, and
+            // values with the universal syntax fail to interpolate, which makes
+            // transitions fall back to discrete animation for those.
             #[cfg(feature = "servo")]
             Self::Custom(_) => false,
         }
@@ -1424,15 +1509,22 @@ impl UnparsedValue {
             }
         }
 
-        let css = match custom_properties::substitute(
+        // Random values are cached per property, which for shorthands is the shorthand itself, so
+        // that all the longhands resolve to the same values.
+        computed_context.set_random_property(match self.from_shorthand {
+            Some(shorthand_id) => PropertyId::NonCustom(shorthand_id.into()),
+            None => PropertyId::NonCustom(longhand_id.into()),
+        });
+        let substitution = match custom_properties::substitute(
             &self.variable_value,
             custom_properties,
             stylist,
             computed_context,
         ) {
-            Ok(css) => css,
+            Ok(substitution) => substitution,
             Err(..) => return invalid_at_computed_value_time(),
         };
+        let css = substitution.css;
 
         // As of this writing, only the base URL is used for property
         // values.
@@ -1444,16 +1536,27 @@ impl UnparsedValue {
         // whether you want to do this!
         //
         // FIXME(emilio): ParsingMode is slightly fishy...
-        let context = ParserContext::new(
+        let parsing_mode = if substitution.attr_tainted {
+            ParsingMode::DISALLOW_URLS
+        } else {
+            ParsingMode::DEFAULT
+        };
+        let mut context = ParserContext::new(
             Origin::Author,
             &self.variable_value.url_data,
             None,
-            ParsingMode::DEFAULT,
+            parsing_mode,
             computed_context.quirks_mode,
             /* namespaces = */ Default::default(),
             None,
             None,
         );
+        // Tree-counting functions can be resolved now, either from our own value or from the
+        // substituted variables.
+        context.tree_counting_provider = Some(computed_context);
+        context.random_provider = Some(computed_context);
+        context.query_progress_provider = Some(computed_context);
+        context.relative_length_provider = Some(computed_context);
 
         let mut input = ParserInput::new(&css);
         let mut input = Parser::new(&mut input);
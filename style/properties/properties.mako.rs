@@ -1650,7 +1650,9 @@ pub mod style_structs {
                 self.animation_fill_mode_iter().eq(other.animation_fill_mode_iter()) &&
                 self.animation_iteration_count_iter().eq(other.animation_iteration_count_iter()) &&
                 self.animation_play_state_iter().eq(other.animation_play_state_iter()) &&
-                self.animation_timing_function_iter().eq(other.animation_timing_function_iter())
+                self.animation_timing_function_iter().eq(other.animation_timing_function_iter()) &&
+                self.animation_range_start_iter().eq(other.animation_range_start_iter()) &&
+                self.animation_range_end_iter().eq(other.animation_range_end_iter())
             }
 
         % elif style_struct.name == "Column":
@@ -1760,6 +1762,21 @@ impl ComputedValues {
         self.clone_display().is_contents()
     }
 
+    /// Returns true if the display property is changed from 'none' to others.
+    pub fn is_display_property_changed_from_none(
+        &self,
+        old_values: Option<<&ComputedValues>
+    ) -> bool {
+        use crate::properties::longhands::display::computed_value::T as Display;
+
+        old_values.map_or(false, |old| {
+            let old_display_style = old.get_box().clone_display();
+            let new_display_style = self.get_box().clone_display();
+            old_display_style == Display::None &&
+            new_display_style != Display::None
+        })
+    }
+
     /// Gets a reference to the rule node. Panic if no rule node exists.
     pub fn rules(&self) -> &StrongRuleNode {
         self.rules.as_ref().unwrap()
@@ -2256,6 +2273,99 @@ impl ComputedValuesInner {
         // Neither perspective nor transform present
         false
     }
+
+    /// Whether any of the transform-related properties is not `none`.
+    fn has_transform_or_perspective(&self) -> bool {
+        use crate::values::generics::transform::{Rotate, Scale, Translate};
+
+        let box_ = self.get_box();
+        !box_.transform.0.is_empty() ||
+            !matches!(box_.translate, Translate::None) ||
+            !matches!(box_.rotate, Rotate::None) ||
+            !matches!(box_.scale, Scale::None) ||
+            !matches!(box_.perspective, computed::Perspective::None)
+    }
+
+    /// Whether this style establishes a containing block for all of its
+    /// descendants, including fixed-position ones.
+    ///
+    /// <https://drafts.csswg.org/css-transforms-1/#containing-block-for-all-descendants>
+    pub fn establishes_containing_block_for_all_descendants(&self) -> bool {
+        use crate::values::specified::box_::WillChangeBits;
+
+        let effects = self.get_effects();
+        self.has_transform_or_perspective() ||
+            !effects.filter.0.is_empty() ||
+            !effects.backdrop_filter.0.is_empty() ||
+            self.get_box().will_change.bits().intersects(
+                WillChangeBits::TRANSFORM |
+                    WillChangeBits::PERSPECTIVE |
+                    WillChangeBits::FIXPOS_CB_NON_SVG,
+            )
+    }
+
+    /// Whether this style establishes a containing block for its absolutely
+    /// positioned descendants.
+    ///
+    /// <https://drafts.csswg.org/css-will-change/#will-change>
+    pub fn establishes_containing_block_for_absolute_descendants(&self) -> bool {
+        use crate::values::specified::box_::WillChangeBits;
+
+        let box_ = self.get_box();
+        box_.position != computed::PositionProperty::Static ||
+            box_.will_change.bits().intersects(WillChangeBits::POSITION) ||
+            self.establishes_containing_block_for_all_descendants()
+    }
+
+    /// Whether this style establishes a stacking context, taking `will-change`
+    /// into account.
+    ///
+    /// Note that flex and grid items with a non-`auto` `z-index` also create a
+    /// stacking context, but that depends on the parent, so it's up to layout
+    /// to check that.
+    ///
+    /// <https://www.w3.org/TR/CSS22/zindex.html>
+    pub fn establishes_stacking_context(&self) -> bool {
+        use crate::computed_values::isolation::T as Isolation;
+        use crate::computed_values::mix_blend_mode::T as MixBlendMode;
+        use crate::values::generics::basic_shape::ClipPath;
+        use crate::values::specified::box_::WillChangeBits;
+
+        let box_ = self.get_box();
+        let position = box_.position;
+        if matches!(
+            position,
+            computed::PositionProperty::Fixed | computed::PositionProperty::Sticky
+        ) {
+            return true;
+        }
+
+        let will_change = box_.will_change.bits();
+        if will_change.intersects(
+            WillChangeBits::STACKING_CONTEXT_UNCONDITIONAL |
+                WillChangeBits::TRANSFORM |
+                WillChangeBits::OPACITY |
+                WillChangeBits::PERSPECTIVE,
+        ) {
+            return true;
+        }
+
+        if position != computed::PositionProperty::Static &&
+            (!self.get_position().z_index.is_auto() ||
+                will_change.intersects(WillChangeBits::Z_INDEX))
+        {
+            return true;
+        }
+
+        let effects = self.get_effects();
+        effects.opacity < 1.0 ||
+            !effects.filter.0.is_empty() ||
+            !effects.backdrop_filter.0.is_empty() ||
+            effects.mix_blend_mode != MixBlendMode::Normal ||
+            box_.isolation == Isolation::Isolate ||
+            !matches!(self.get_svg().clip_path, ClipPath::None) ||
+            self.has_transform_or_perspective()
+    }
 }
 
 /// A reference to a style struct of the parent, or our own style struct.
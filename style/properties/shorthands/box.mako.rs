@@ -237,3 +237,88 @@ ${helpers.two_properties_shorthand(
         }
     }
 </%helpers:shorthand>
+
+<%helpers:shorthand
+    engines="servo"
+    name="line-clamp"
+    sub_properties="max-lines block-ellipsis continue"
+    spec="https://drafts.csswg.org/css-overflow-4/#line-clamp"
+>
+    use crate::parser::Parse;
+    use crate::properties::longhands::continue_::SpecifiedValue as Continue;
+    use crate::values::specified::box_::LineClamp;
+    use crate::values::specified::text::BlockEllipsis;
+
+    pub fn parse_value<'i>(
+        context: &ParserContext,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<Longhands, ParseError<'i>> {
+        // none | [ <integer [1,∞]> || <'block-ellipsis'> ]
+        if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+            return Ok(expanded! {
+                max_lines: LineClamp::none(),
+                block_ellipsis: BlockEllipsis::None,
+                continue_: Continue::Auto,
+            });
+        }
+
+        let mut max_lines = None;
+        let mut block_ellipsis = None;
+        loop {
+            if max_lines.is_none() {
+                if let Ok(value) = input.try_parse(|input| LineClamp::parse(context, input)) {
+                    if value.is_none() {
+                        return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+                    }
+                    max_lines = Some(value);
+                    continue;
+                }
+            }
+            if block_ellipsis.is_none() {
+                if let Ok(value) = input.try_parse(|input| BlockEllipsis::parse(context, input)) {
+                    block_ellipsis = Some(value);
+                    continue;
+                }
+            }
+            break;
+        }
+
+        if max_lines.is_none() && block_ellipsis.is_none() {
+            return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
+
+        Ok(expanded! {
+            max_lines: max_lines.unwrap_or_else(LineClamp::none),
+            block_ellipsis: block_ellipsis.unwrap_or(BlockEllipsis::Auto),
+            continue_: Continue::Collapse,
+        })
+    }
+
+    impl<'a> ToCss for LonghandsToSerialize<'a> {
+        fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result where W: fmt::Write {
+            match *self.continue_ {
+                Continue::Auto => {
+                    if !self.max_lines.is_none() || *self.block_ellipsis != BlockEllipsis::None {
+                        return Ok(());
+                    }
+                    dest.write_str("none")
+                },
+                Continue::Collapse => {
+                    let has_max_lines = !self.max_lines.is_none();
+                    if has_max_lines {
+                        self.max_lines.to_css(dest)?;
+                    }
+                    if *self.block_ellipsis != BlockEllipsis::Auto || !has_max_lines {
+                        if has_max_lines {
+                            dest.write_char(' ')?;
+                        }
+                        self.block_ellipsis.to_css(dest)?;
+                    }
+                    Ok(())
+                },
+                // Not representable with the shorthand.
+                _ => Ok(()),
+            }
+        }
+    }
+</%helpers:shorthand>
@@ -534,3 +534,75 @@ macro_rules! try_parse_one {
         }
     }
 </%helpers:shorthand>
+
+<%helpers:shorthand
+    engines="servo"
+    name="animation-range"
+    sub_properties="animation-range-start animation-range-end"
+    servo_pref="layout.css.scroll-driven-animations.enabled",
+    spec="https://drafts.csswg.org/scroll-animations-1/#animation-range"
+>
+    use crate::values::specified::AnimationRangeValue;
+
+    /// The `animation-range-end` value implied by the given `animation-range-start`
+    /// value when it's omitted: the end of the same named range, or `normal`.
+    fn implied_range_end(start: &AnimationRangeValue) -> AnimationRangeValue {
+        match *start {
+            AnimationRangeValue::Named(name, _) => AnimationRangeValue::Named(name, None),
+            _ => AnimationRangeValue::Normal,
+        }
+    }
+
+    pub fn parse_value<'i>(
+        context: &ParserContext,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<Longhands, ParseError<'i>> {
+        use crate::properties::longhands::{animation_range_end, animation_range_start};
+
+        let mut starts = Vec::with_capacity(1);
+        let mut ends = Vec::with_capacity(1);
+        input.parse_comma_separated(|input| {
+            let start = animation_range_start::single_value::parse(context, input)?;
+            let end = input
+                .try_parse(|i| animation_range_end::single_value::parse(context, i))
+                .unwrap_or_else(|_| implied_range_end(&start));
+
+            starts.push(start);
+            ends.push(end);
+
+            Ok(())
+        })?;
+
+        Ok(expanded! {
+            animation_range_start: animation_range_start::SpecifiedValue(starts.into()),
+            animation_range_end: animation_range_end::SpecifiedValue(ends.into()),
+        })
+    }
+
+    impl<'a> ToCss for LonghandsToSerialize<'a>  {
+        fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result where W: fmt::Write {
+            // If any value list length is differs then we don't do a shorthand serialization
+            // either.
+            let len = self.animation_range_start.0.len();
+            if len != self.animation_range_end.0.len() {
+                return Ok(());
+            }
+
+            for i in 0..len {
+                if i != 0 {
+                    dest.write_str(", ")?;
+                }
+
+                let start = &self.animation_range_start.0[i];
+                start.to_css(dest)?;
+
+                let end = &self.animation_range_end.0[i];
+                if *end != implied_range_end(start) {
+                    dest.write_char(' ')?;
+                    end.to_css(dest)?;
+                }
+            }
+            Ok(())
+        }
+    }
+</%helpers:shorthand>
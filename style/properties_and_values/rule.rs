@@ -272,6 +272,7 @@ impl PropertyRegistration {
             &self.url_data,
             computed_context,
             AllowComputationallyDependent::No,
+            /* attr_tainted = */ false,
         ) {
             Ok(computed) => Ok(computed),
             Err(_) => Err(()),
@@ -320,6 +321,7 @@ impl PropertyRegistration {
             syntax,
             &initial.url_data,
             AllowComputationallyDependent::No,
+            /* attr_tainted = */ false,
         ) {
             Ok(_) => {},
             Err(_) => return Err(PropertyRegistrationError::InvalidInitialValue),
@@ -406,6 +408,6 @@ impl Parse for InitialValue {
         input: &mut Parser<'i, 't>,
     ) -> Result<Self, ParseError<'i>> {
         input.skip_whitespace();
-        Ok(Arc::new(SpecifiedValue::parse(input, &context.url_data)?))
+        Ok(Arc::new(SpecifiedValue::parse(input, context)?))
     }
 }
@@ -23,7 +23,7 @@ mod ascii;
 pub mod data_type;
 
 /// <https://drafts.css-houdini.org/css-properties-values-api-1/#parsing-syntax>
-#[derive(Debug, Clone, Default, MallocSizeOf, PartialEq)]
+#[derive(Debug, Clone, Default, MallocSizeOf, PartialEq, ToShmem)]
 pub struct Descriptor {
     /// The parsed components, if any.
     /// TODO: Could be a Box<[]> if that supported const construction.
@@ -171,7 +171,7 @@ impl ToCss for Multiplier {
 }
 
 /// <https://drafts.css-houdini.org/css-properties-values-api-1/#syntax-component>
-#[derive(Clone, Debug, MallocSizeOf, PartialEq)]
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
 pub struct Component {
     name: ComponentName,
     multiplier: Option<Multiplier>,
@@ -217,7 +217,7 @@ impl ToCss for Component {
 }
 
 /// <https://drafts.css-houdini.org/css-properties-values-api-1/#syntax-component-name>
-#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToCss)]
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToCss, ToShmem)]
 pub enum ComponentName {
     /// <https://drafts.css-houdini.org/css-properties-values-api-1/#data-type-name>
     DataType(DataType),
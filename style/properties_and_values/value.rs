@@ -208,6 +208,9 @@ pub struct Value<Component> {
     /// necessary to uncompute registered custom properties.
     #[css(skip)]
     url_data: UrlExtraData,
+    /// Whether the value was computed from the result of an `attr()` function.
+    #[css(skip)]
+    attr_tainted: bool,
 }
 
 impl<Component: Animate> Animate for Value<Component> {
@@ -216,6 +219,7 @@ impl<Component: Animate> Animate for Value<Component> {
         Ok(Value {
             v,
             url_data: self.url_data.clone(),
+            attr_tainted: self.attr_tainted || other.attr_tainted,
         })
     }
 }
@@ -223,14 +227,23 @@ impl<Component: Animate> Animate for Value<Component> {
 impl<Component> Value<Component> {
     /// Creates a new registered custom property value.
     pub fn new(v: ValueInner<Component>, url_data: UrlExtraData) -> Self {
-        Self { v, url_data }
+        Self {
+            v,
+            url_data,
+            attr_tainted: false,
+        }
     }
 
     /// Creates a new registered custom property value presumed to have universal syntax.
     pub fn universal(var: Arc<ComputedPropertyValue>) -> Self {
         let url_data = var.url_data.clone();
+        let attr_tainted = var.is_attr_tainted();
         let v = ValueInner::Universal(var);
-        Self { v, url_data }
+        Self {
+            v,
+            url_data,
+            attr_tainted,
+        }
     }
 }
 
@@ -264,6 +277,7 @@ impl SpecifiedValue {
         url_data: &UrlExtraData,
         context: &computed::Context,
         allow_computationally_dependent: AllowComputationallyDependent,
+        attr_tainted: bool,
     ) -> Result<ComputedValue, ()> {
         debug_assert!(!registration.syntax.is_universal(), "Shouldn't be needed");
         let Ok(value) = Self::parse(
@@ -271,6 +285,7 @@ impl SpecifiedValue {
             &registration.syntax,
             url_data,
             allow_computationally_dependent,
+            attr_tainted,
         ) else {
             return Err(());
         };
@@ -279,18 +294,40 @@ impl SpecifiedValue {
     }
 
     /// Parse and validate a registered custom property value according to its syntax descriptor,
-    /// and check for computational independence.
+    /// and check for computational independence. Values substituted from `attr()` can't contain
+    /// URLs.
     pub fn parse<'i, 't>(
-        mut input: &mut CSSParser<'i, 't>,
+        input: &mut CSSParser<'i, 't>,
         syntax: &Descriptor,
         url_data: &UrlExtraData,
         allow_computationally_dependent: AllowComputationallyDependent,
+        attr_tainted: bool,
     ) -> Result<Self, StyleParseError<'i>> {
+        use self::AllowComputationallyDependent::*;
+        let mut parsing_mode = match allow_computationally_dependent {
+            No => ParsingMode::DISALLOW_COMPUTATIONALLY_DEPENDENT,
+            Yes => ParsingMode::DEFAULT,
+        };
+        if attr_tainted {
+            parsing_mode.insert(ParsingMode::DISALLOW_URLS);
+        }
+        let context = &ParserContext::new(
+            Origin::Author,
+            url_data,
+            Some(CssRuleType::Style),
+            parsing_mode,
+            QuirksMode::NoQuirks,
+            /* namespaces = */ Default::default(),
+            None,
+            None,
+        );
         if syntax.is_universal() {
-            let parsed = ComputedPropertyValue::parse(&mut input, url_data)?;
+            let mut parsed = ComputedPropertyValue::parse(input, context)?;
+            parsed.set_attr_tainted(attr_tainted);
             return Ok(SpecifiedValue {
                 v: ValueInner::Universal(Arc::new(parsed)),
                 url_data: url_data.clone(),
+                attr_tainted,
             });
         }
 
@@ -298,7 +335,7 @@ impl SpecifiedValue {
         let mut multiplier = None;
         {
             let mut parser = Parser::new(syntax, &mut values, &mut multiplier);
-            parser.parse(&mut input, url_data, allow_computationally_dependent)?;
+            parser.parse(input, context)?;
         }
         let v = if let Some(multiplier) = multiplier {
             ValueInner::List(ComponentList {
@@ -311,6 +348,7 @@ impl SpecifiedValue {
         Ok(Self {
             v,
             url_data: url_data.clone(),
+            attr_tainted,
         })
     }
 }
@@ -355,12 +393,14 @@ impl ComputedValue {
             return (**value).clone();
         }
         let serialization_types = self.serialization_types();
-        ComputedPropertyValue::new(
+        let mut value = ComputedPropertyValue::new(
             self.to_css_string(),
             &self.url_data,
             serialization_types.0,
             serialization_types.1,
-        )
+        );
+        value.set_attr_tainted(self.attr_tainted);
+        value
     }
 }
 
@@ -399,24 +439,8 @@ impl<'a> Parser<'a> {
     fn parse<'i, 't>(
         &mut self,
         input: &mut CSSParser<'i, 't>,
-        url_data: &UrlExtraData,
-        allow_computationally_dependent: AllowComputationallyDependent,
+        context: &ParserContext,
     ) -> Result<(), StyleParseError<'i>> {
-        use self::AllowComputationallyDependent::*;
-        let parsing_mode = match allow_computationally_dependent {
-            No => ParsingMode::DISALLOW_COMPUTATIONALLY_DEPENDENT,
-            Yes => ParsingMode::DEFAULT,
-        };
-        let ref context = ParserContext::new(
-            Origin::Author,
-            url_data,
-            Some(CssRuleType::Style),
-            parsing_mode,
-            QuirksMode::NoQuirks,
-            /* namespaces = */ Default::default(),
-            None,
-            None,
-        );
         for component in self.syntax.components.iter() {
             let result = input.try_parse(|input| {
                 input.parse_entirely(|input| {
@@ -584,8 +608,9 @@ impl<'a> Parser<'a> {
 pub struct CustomAnimatedValue {
     /// The name of the custom property.
     pub(crate) name: crate::custom_properties::Name,
-    /// The computed value of the custom property.
-    value: ComputedValue,
+    /// The computed value of the custom property, or `None` if it is the
+    /// guaranteed-invalid value.
+    value: Option<ComputedValue>,
 }
 
 impl Animate for CustomAnimatedValue {
@@ -593,10 +618,13 @@ impl Animate for CustomAnimatedValue {
         if self.name != other.name {
             return Err(());
         }
-        let value = self.value.animate(&other.value, procedure)?;
+        // The guaranteed-invalid value can only be animated discretely.
+        let (Some(value), Some(other_value)) = (&self.value, &other.value) else {
+            return Err(());
+        };
         Ok(Self {
             name: self.name.clone(),
-            value,
+            value: Some(value.animate(other_value, procedure)?),
         })
     }
 }
@@ -608,7 +636,16 @@ impl CustomAnimatedValue {
     ) -> Self {
         Self {
             name: name.clone(),
-            value: value.clone(),
+            value: Some(value.clone()),
+        }
+    }
+
+    /// The guaranteed-invalid value of the given custom property, which is
+    /// what a custom property that is absent from a style computes to.
+    pub(crate) fn guaranteed_invalid(name: &crate::custom_properties::Name) -> Self {
+        Self {
+            name: name.clone(),
+            value: None,
         }
     }
 
@@ -630,10 +667,7 @@ impl CustomAnimatedValue {
                     .get_custom_property_registration(&declaration.name);
                 if registration.syntax.is_universal() {
                     // FIXME: Do we need to perform substitution here somehow?
-                    ComputedValue {
-                        v: ValueInner::Universal(Arc::clone(value)),
-                        url_data: value.url_data.clone(),
-                    }
+                    ComputedValue::universal(Arc::clone(value))
                 } else {
                     let mut input = cssparser::ParserInput::new(&value.css);
                     let mut input = CSSParser::new(&mut input);
@@ -643,12 +677,8 @@ impl CustomAnimatedValue {
                         &value.url_data,
                         context,
                         AllowComputationallyDependent::Yes,
-                    ).unwrap_or_else(|_| {
-                        ComputedValue {
-                            v: ValueInner::Universal(Arc::clone(value)),
-                            url_data: value.url_data.clone(),
-                        }
-                    })
+                        value.is_attr_tainted(),
+                    ).unwrap_or_else(|_| ComputedValue::universal(Arc::clone(value)))
                 }
             }
             properties::CustomDeclarationValue::Parsed(ref v) => v.to_computed_value(context),
@@ -658,14 +688,34 @@ impl CustomAnimatedValue {
         };
         Some(Self {
             name: declaration.name.clone(),
-            value: computed_value,
+            value: Some(computed_value),
         })
     }
 
+    /// Set this value in the given custom properties, as the result of an
+    /// animation or transition.
+    pub(crate) fn set_in_custom_properties(
+        &self,
+        custom_properties: &mut crate::custom_properties::ComputedCustomProperties,
+        stylist: &crate::stylist::Stylist,
+    ) {
+        let registration = stylist.get_custom_property_registration(&self.name);
+        match self.value {
+            Some(ref value) => custom_properties.insert(registration, &self.name, value.clone()),
+            None => custom_properties.remove(registration, &self.name),
+        }
+    }
+
     pub(crate) fn to_declaration(&self) -> properties::PropertyDeclaration {
+        let value = match self.value {
+            Some(ref value) => value.to_declared_value(),
+            None => properties::CustomDeclarationValue::CSSWideKeyword(
+                properties::CSSWideKeyword::Initial,
+            ),
+        };
         properties::PropertyDeclaration::Custom(properties::CustomDeclaration {
             name: self.name.clone(),
-            value: self.value.to_declared_value(),
+            value,
         })
     }
 }
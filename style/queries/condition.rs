@@ -181,7 +181,9 @@ impl QueryCondition {
         }
     }
 
-    fn parse_in_parenthesis_block<'i>(
+    /// Parse a feature expression or a condition where we've already consumed the parenthesis,
+    /// or the equivalent function token.
+    pub fn parse_in_parenthesis_block<'i>(
         context: &ParserContext,
         input: &mut Parser<'i, '_>,
         feature_type: FeatureType,
@@ -7,9 +7,11 @@
 
 use super::feature::{Evaluator, QueryFeatureDescription};
 use super::feature::{FeatureFlags, KeywordDiscriminant};
+use crate::computed_value_flags::ComputedValueFlags;
 use crate::parser::{Parse, ParserContext};
 use crate::str::{starts_with_ignore_ascii_case, string_as_ascii_lowercase};
 use crate::values::computed::{self, Ratio, ToComputedValue};
+use crate::values::generics::calc;
 use crate::values::specified::{Integer, Length, Number, Resolution};
 use crate::values::{AtomString, CSSFloat};
 use crate::{Atom, Zero};
@@ -655,6 +657,159 @@ impl QueryFeatureExpression {
     }
 }
 
+/// The feature and range of a `media-progress()` or `container-progress()` function, which
+/// measures the progress of the value of the feature from the start to the end of the range.
+///
+/// <https://drafts.csswg.org/css-values-5/#media-progress-func>
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+pub struct QueryFeatureProgress {
+    feature_type: FeatureType,
+    feature_index: usize,
+    start: QueryExpressionValue,
+    end: QueryExpressionValue,
+}
+
+impl ToCss for QueryFeatureProgress {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        dest.write_str(match self.feature_type {
+            FeatureType::Media => "media-progress(",
+            FeatureType::Container => "container-progress(",
+        })?;
+        write!(dest, "{}", self.feature().name)?;
+        dest.write_str(", ")?;
+        self.start.to_progress_css(dest)?;
+        dest.write_str(", ")?;
+        self.end.to_progress_css(dest)?;
+        dest.write_char(')')
+    }
+}
+
+impl QueryFeatureProgress {
+    /// Parses the arguments of the function, within its nested block:
+    ///
+    /// ```
+    /// <mf-name>, <calc-sum>, <calc-sum>
+    /// ```
+    ///
+    /// TODO: Support the `of <container-name>` argument of `container-progress()`.
+    pub fn parse_arguments<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+        feature_type: FeatureType,
+    ) -> Result<Self, ParseError<'i>> {
+        let location = input.current_source_location();
+        let (feature_index, range) =
+            QueryFeatureExpression::parse_feature_name(context, input, feature_type)?;
+        let feature = &feature_type.features()[feature_index];
+        // Only ranged features with a numeric value make sense here. For containers, that
+        // means the size features, which we can resolve from the size query of the element.
+        let numeric = match feature.evaluator {
+            Evaluator::OptionalLength(..) => true,
+            Evaluator::Length(..) |
+            Evaluator::Integer(..) |
+            Evaluator::Float(..) |
+            Evaluator::Resolution(..) => feature_type == FeatureType::Media,
+            _ => false,
+        };
+        if range.is_some() || !feature.allows_ranges() || !numeric {
+            return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
+        input.expect_comma()?;
+        let start = QueryExpressionValue::parse(feature, context, input)?;
+        input.expect_comma()?;
+        let end = QueryExpressionValue::parse(feature, context, input)?;
+        Ok(Self {
+            feature_type,
+            feature_index,
+            start,
+            end,
+        })
+    }
+
+    fn feature(&self) -> &'static QueryFeatureDescription {
+        &self.feature_type.features()[self.feature_index]
+    }
+
+    /// Returns the progress of the feature value for the element being styled.
+    pub fn evaluate(&self, context: &computed::Context) -> CSSFloat {
+        match self.feature_type {
+            FeatureType::Media => {
+                context
+                    .builder
+                    .add_flags(ComputedValueFlags::DEPENDS_ON_ENVIRONMENT);
+                computed::Context::for_media_query_evaluation(
+                    context.device(),
+                    context.quirks_mode,
+                    |context| self.evaluate_media_feature(context),
+                )
+            },
+            FeatureType::Container => self.evaluate_container_feature(context),
+        }
+    }
+
+    fn evaluate_media_feature(&self, context: &computed::Context) -> CSSFloat {
+        let value = match self.feature().evaluator {
+            Evaluator::Length(eval) => eval(context).px(),
+            // If the feature has no value there's no progress to measure.
+            Evaluator::OptionalLength(eval) => match eval(context) {
+                Some(v) => v.px(),
+                None => return 0.,
+            },
+            Evaluator::Integer(eval) => eval(context) as CSSFloat,
+            Evaluator::Float(eval) => eval(context),
+            Evaluator::Resolution(eval) => eval(context).dppx(),
+            _ => unreachable!("Shouldn't have parsed"),
+        };
+        calc::progress(
+            value,
+            self.start.progress_value(context),
+            self.end.progress_value(context),
+        )
+    }
+
+    fn evaluate_container_feature(&self, context: &computed::Context) -> CSSFloat {
+        // Like container units, this depends on the size of the query container.
+        if context.for_non_inherited_property {
+            context.rule_cache_conditions.borrow_mut().set_uncacheable();
+        }
+        context
+            .builder
+            .add_flags(ComputedValueFlags::USES_CONTAINER_UNITS);
+        let size = context.get_container_size_query();
+        let flags = self.feature().flags;
+        let value = if flags.contains(FeatureFlags::CONTAINER_REQUIRES_WIDTH_AXIS) {
+            size.get_container_width(context)
+        } else if flags.contains(FeatureFlags::CONTAINER_REQUIRES_HEIGHT_AXIS) {
+            size.get_container_height(context)
+        } else if flags.contains(FeatureFlags::CONTAINER_REQUIRES_INLINE_AXIS) {
+            size.get_container_inline_size(context)
+        } else {
+            size.get_container_block_size(context)
+        };
+        calc::progress(
+            value.to_f32_px(),
+            self.start.progress_value(context),
+            self.end.progress_value(context),
+        )
+    }
+}
+
+/// Something that can evaluate `media-progress()` and `container-progress()` functions, at
+/// computed-value time.
+pub trait QueryProgressProvider {
+    /// Returns the progress of the feature value for the element being styled.
+    fn query_progress(&self, progress: &QueryFeatureProgress) -> CSSFloat;
+}
+
+impl<'a> QueryProgressProvider for computed::Context<'a> {
+    fn query_progress(&self, progress: &QueryFeatureProgress) -> CSSFloat {
+        progress.evaluate(self)
+    }
+}
+
 /// A value found or expected in a expression.
 ///
 /// FIXME(emilio): How should calc() serialize in the Number / Integer /
@@ -705,6 +860,31 @@ impl QueryExpressionValue {
         }
     }
 
+    fn to_progress_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        match *self {
+            QueryExpressionValue::Length(ref l) => l.to_css(dest),
+            QueryExpressionValue::Integer(v) => v.to_css(dest),
+            QueryExpressionValue::Float(v) => v.to_css(dest),
+            QueryExpressionValue::Resolution(ref r) => r.to_css(dest),
+            _ => unreachable!("Not a progress value"),
+        }
+    }
+
+    /// Returns the value of a `media-progress()` or `container-progress()` range, in the
+    /// canonical unit of the feature.
+    fn progress_value(&self, context: &computed::Context) -> CSSFloat {
+        match *self {
+            QueryExpressionValue::Length(ref l) => l.to_computed_value(context).px(),
+            QueryExpressionValue::Integer(v) => v as CSSFloat,
+            QueryExpressionValue::Float(v) => v,
+            QueryExpressionValue::Resolution(ref r) => r.to_computed_value(context).dppx(),
+            _ => unreachable!("Not a progress value"),
+        }
+    }
+
     fn parse<'i, 't>(
         for_feature: &QueryFeatureDescription,
         context: &ParserContext,
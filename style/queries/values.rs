@@ -4,6 +4,7 @@
 
 //! Common feature values between media and container features.
 
+use crate::color::ColorSpace;
 use app_units::Au;
 use euclid::default::Size2D;
 
@@ -43,3 +44,29 @@ pub enum PrefersColorScheme {
     Light,
     Dark,
 }
+
+/// Values for the color-gamut media feature.
+/// This implements PartialOrd so that lower values will correctly match
+/// higher capabilities.
+#[derive(Clone, Copy, Debug, FromPrimitive, Parse, PartialEq, PartialOrd, ToCss, MallocSizeOf)]
+#[repr(u8)]
+pub enum ColorGamut {
+    /// The sRGB gamut.
+    Srgb,
+    /// The gamut specified by the Display P3 Color Space.
+    P3,
+    /// The gamut specified by the ITU-R Recommendation BT.2020 Color Space.
+    Rec2020,
+}
+
+impl ColorGamut {
+    /// Returns the color space whose gamut this is, which colors are mapped into before
+    /// being displayed.
+    pub fn color_space(&self) -> ColorSpace {
+        match *self {
+            Self::Srgb => ColorSpace::Srgb,
+            Self::P3 => ColorSpace::DisplayP3,
+            Self::Rec2020 => ColorSpace::Rec2020,
+        }
+    }
+}
@@ -4,22 +4,26 @@
 
 //! Servo's media-query device and expression representation.
 
+use crate::color::profile::ColorProfile;
 use crate::color::AbsoluteColor;
 use crate::context::QuirksMode;
 use crate::custom_properties::CssEnvironment;
 use crate::font_metrics::FontMetrics;
 use crate::queries::feature::{AllowsRanges, Evaluator, FeatureFlags, QueryFeatureDescription};
-use crate::queries::values::PrefersColorScheme;
+use crate::queries::values::{ColorGamut, PrefersColorScheme};
 use crate::logical_geometry::WritingMode;
 use crate::media_queries::MediaType;
 use crate::properties::style_structs::Font;
 use crate::properties::ComputedValues;
+use crate::selector_map::PrecomputedHashMap;
 use crate::values::computed::{CSSPixelLength, Context, Length, LineHeight, NonNegativeLength, Resolution};
 use crate::values::computed::font::GenericFontFamily;
-use crate::values::specified::color::{ColorSchemeFlags, ForcedColors};
+use crate::values::computed::random::RandomBaseValues;
+use crate::values::specified::color::{ColorSchemeFlags, ForcedColors, SystemColor};
 use crate::values::specified::font::{FONT_MEDIUM_LINE_HEIGHT_PX, FONT_MEDIUM_PX};
 use crate::values::specified::ViewportVariant;
 use crate::values::KeyframesName;
+use crate::Atom;
 use app_units::{Au, AU_PER_PX};
 use euclid::default::Size2D as UntypedSize2D;
 use euclid::{Scale, SideOffsets2D, Size2D};
@@ -45,6 +49,68 @@ pub trait FontMetricsProvider: Debug + Sync {
     fn base_size_for_generic(&self, generic: GenericFontFamily) -> Length;
 }
 
+/// A trait used by embedders to provide the platform colors used to resolve
+/// system colors, such as `Canvas` or `LinkText`.
+pub trait ThemeProvider: Debug + Sync {
+    /// Returns the value of the given system color for the given color scheme.
+    fn system_color(&self, color: SystemColor, color_scheme: PrefersColorScheme) -> AbsoluteColor;
+}
+
+/// The theme used when the embedder doesn't provide one, loosely matching the
+/// default palettes of other browsers.
+#[derive(Debug)]
+pub struct DefaultThemeProvider;
+
+impl ThemeProvider for DefaultThemeProvider {
+    fn system_color(&self, color: SystemColor, color_scheme: PrefersColorScheme) -> AbsoluteColor {
+        let rgb = |r, g, b| AbsoluteColor::srgb_legacy(r, g, b, 1.0);
+        match color_scheme {
+            PrefersColorScheme::Light => match color {
+                SystemColor::Accentcolor => rgb(0x00, 0x75, 0xff),
+                SystemColor::Accentcolortext => rgb(0xff, 0xff, 0xff),
+                SystemColor::Activetext => rgb(0xee, 0x00, 0x00),
+                SystemColor::Buttonborder => rgb(0x76, 0x76, 0x76),
+                SystemColor::Buttonface => rgb(0xef, 0xef, 0xef),
+                SystemColor::Buttontext => rgb(0x00, 0x00, 0x00),
+                SystemColor::Canvas => rgb(0xff, 0xff, 0xff),
+                SystemColor::Canvastext => rgb(0x00, 0x00, 0x00),
+                SystemColor::Field => rgb(0xff, 0xff, 0xff),
+                SystemColor::Fieldtext => rgb(0x00, 0x00, 0x00),
+                SystemColor::Graytext => rgb(0x6d, 0x6d, 0x6d),
+                SystemColor::Highlight => rgb(0x33, 0x99, 0xff),
+                SystemColor::Highlighttext => rgb(0xff, 0xff, 0xff),
+                SystemColor::Linktext => rgb(0x00, 0x00, 0xee),
+                SystemColor::Mark => rgb(0xff, 0xff, 0x00),
+                SystemColor::Marktext => rgb(0x00, 0x00, 0x00),
+                SystemColor::Selecteditem => rgb(0x33, 0x99, 0xff),
+                SystemColor::Selecteditemtext => rgb(0xff, 0xff, 0xff),
+                SystemColor::Visitedtext => rgb(0x55, 0x1a, 0x8b),
+            },
+            PrefersColorScheme::Dark => match color {
+                SystemColor::Accentcolor => rgb(0x99, 0xc8, 0xff),
+                SystemColor::Accentcolortext => rgb(0x00, 0x00, 0x00),
+                SystemColor::Activetext => rgb(0xff, 0x9e, 0x9e),
+                SystemColor::Buttonborder => rgb(0x6b, 0x6b, 0x6b),
+                SystemColor::Buttonface => rgb(0x6b, 0x6b, 0x6b),
+                SystemColor::Buttontext => rgb(0xff, 0xff, 0xff),
+                SystemColor::Canvas => rgb(0x12, 0x12, 0x12),
+                SystemColor::Canvastext => rgb(0xff, 0xff, 0xff),
+                SystemColor::Field => rgb(0x3b, 0x3b, 0x3b),
+                SystemColor::Fieldtext => rgb(0xff, 0xff, 0xff),
+                SystemColor::Graytext => rgb(0x8e, 0x8e, 0x8e),
+                SystemColor::Highlight => rgb(0x99, 0xc8, 0xff),
+                SystemColor::Highlighttext => rgb(0x00, 0x00, 0x00),
+                SystemColor::Linktext => rgb(0x9e, 0x9e, 0xff),
+                SystemColor::Mark => rgb(0x66, 0x51, 0x2b),
+                SystemColor::Marktext => rgb(0xff, 0xff, 0xff),
+                SystemColor::Selecteditem => rgb(0x99, 0xc8, 0xff),
+                SystemColor::Selecteditemtext => rgb(0x00, 0x00, 0x00),
+                SystemColor::Visitedtext => rgb(0xd0, 0xad, 0xf0),
+            },
+        }
+    }
+}
+
 /// A device is a structure that represents the current media a given document
 /// is displayed in.
 ///
@@ -90,9 +156,24 @@ pub struct Device {
     /// Whether the user prefers light mode or dark mode
     #[ignore_malloc_size_of = "Pure stack type"]
     prefers_color_scheme: PrefersColorScheme,
+    /// Whether the page colors are being forced, for example for high-contrast
+    /// accessibility modes.
+    #[ignore_malloc_size_of = "Pure stack type"]
+    forced_colors: ForcedColors,
+    /// The gamut of the display, which used colors are mapped into.
+    color_gamut: ColorGamut,
+    /// The color profiles loaded by the embedder for `@color-profile` rules, by name.
+    #[ignore_malloc_size_of = "Arc is shared"]
+    color_profiles: PrecomputedHashMap<Atom, Arc<ColorProfile>>,
+    /// An implementation of a trait which provides the system colors.
+    #[ignore_malloc_size_of = "Owned by embedder"]
+    theme_provider: Box<dyn ThemeProvider>,
     /// The CssEnvironment object responsible of getting CSS environment
     /// variables.
     environment: CssEnvironment,
+    /// The state used to resolve `random()` and `random-item()` functions.
+    #[ignore_malloc_size_of = "Pure stack type"]
+    random_base_values: RandomBaseValues,
     /// An implementation of a trait which implements support for querying font metrics.
     #[ignore_malloc_size_of = "Owned by embedder"]
     font_metrics_provider: Box<dyn FontMetricsProvider>,
@@ -124,7 +205,12 @@ impl Device {
             used_font_metrics: AtomicBool::new(false),
             used_viewport_units: AtomicBool::new(false),
             prefers_color_scheme,
-            environment: CssEnvironment,
+            forced_colors: ForcedColors::None,
+            color_gamut: ColorGamut::Srgb,
+            color_profiles: Default::default(),
+            theme_provider: Box::new(DefaultThemeProvider),
+            environment: CssEnvironment::default(),
+            random_base_values: RandomBaseValues::default(),
             font_metrics_provider,
             default_computed_values,
         }
@@ -136,6 +222,26 @@ impl Device {
         &self.environment
     }
 
+    /// Get a mutable reference to the environment used to resolve `env()`
+    /// functions, so that the embedder can provide values at runtime.
+    #[inline]
+    pub fn environment_mut(&mut self) -> &mut CssEnvironment {
+        &mut self.environment
+    }
+
+    /// Get the state used to resolve `random()` and `random-item()` functions.
+    #[inline]
+    pub fn random_base_values(&self) -> &RandomBaseValues {
+        &self.random_base_values
+    }
+
+    /// Set the seed of the values of `random()` and `random-item()` functions, so that embedders
+    /// can get reproducible results, e.g. for testing. The caller is responsible for restyling
+    /// the document afterwards.
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.random_base_values.set_seed(seed);
+    }
+
     /// Return the default computed values for this device.
     pub fn default_computed_values(&self) -> &ComputedValues {
         &self.default_computed_values
@@ -258,8 +364,11 @@ impl Device {
 
     /// Gets the size of the scrollbar in CSS pixels.
     pub fn scrollbar_inline_size(&self) -> CSSPixelLength {
-        // TODO: implement this.
-        CSSPixelLength::new(0.0)
+        CSSPixelLength::new(
+            self.environment
+                .embedder_length(&atom!("scrollbar-inline-size"))
+                .unwrap_or(0.0),
+        )
     }
 
     /// Queries font metrics using the [`FontMetricsProvider`] interface.
@@ -288,17 +397,86 @@ impl Device {
 
     /// Returns whether document colors are enabled.
     pub fn forced_colors(&self) -> ForcedColors {
-        ForcedColors::None
+        self.forced_colors
+    }
+
+    /// Sets whether page colors are being forced.
+    ///
+    /// The embedder is responsible for restyling the document afterwards,
+    /// see `Stylist::media_features_change_changed_style`.
+    pub fn set_forced_colors(&mut self, forced_colors: ForcedColors) {
+        self.forced_colors = forced_colors;
+    }
+
+    /// Returns the gamut of the display.
+    pub fn color_gamut(&self) -> ColorGamut {
+        self.color_gamut
+    }
+
+    /// Sets the gamut of the display.
+    ///
+    /// The embedder is responsible for restyling the document afterwards,
+    /// see `Stylist::media_features_change_changed_style`.
+    pub fn set_color_gamut(&mut self, color_gamut: ColorGamut) {
+        self.color_gamut = color_gamut;
+    }
+
+    /// Returns the used value of a computed color, that is, the color that will be displayed,
+    /// mapped into the gamut of the display.
+    pub fn used_color(&self, color: &AbsoluteColor) -> AbsoluteColor {
+        color.map_to_gamut(self.color_gamut.color_space())
+    }
+
+    /// Returns the color profile loaded for the `@color-profile` rule with the given name, if
+    /// any.
+    pub fn color_profile(&self, name: &Atom) -> Option<&ColorProfile> {
+        self.color_profiles.get(name).map(|profile| &**profile)
+    }
+
+    /// Sets the color profile used by `color()` functions that refer to the `@color-profile` rule
+    /// with the given name, once the embedder has loaded the profile from its `src` descriptor,
+    /// see `Stylist::color_profile_rule`.
+    ///
+    /// The embedder is responsible for restyling the document afterwards.
+    pub fn set_color_profile(&mut self, name: Atom, profile: Arc<ColorProfile>) {
+        self.color_profiles.insert(name, profile);
+    }
+
+    /// Forgets all the loaded color profiles.
+    ///
+    /// The embedder is responsible for restyling the document afterwards.
+    pub fn clear_color_profiles(&mut self) {
+        self.color_profiles.clear();
+    }
+
+    /// Sets the provider used to resolve system colors.
+    ///
+    /// The embedder is responsible for restyling the document afterwards.
+    pub fn set_theme_provider(&mut self, theme_provider: Box<dyn ThemeProvider>) {
+        self.theme_provider = theme_provider;
+    }
+
+    /// Returns the value of a given system color, for the given color-scheme
+    /// value.
+    pub fn system_color(&self, color: SystemColor, color_scheme: ColorSchemeFlags) -> AbsoluteColor {
+        let color_scheme = if self.is_dark_color_scheme(color_scheme) {
+            PrefersColorScheme::Dark
+        } else {
+            PrefersColorScheme::Light
+        };
+        self.theme_provider.system_color(color, color_scheme)
     }
 
     /// Returns the default background color.
     pub fn default_background_color(&self) -> AbsoluteColor {
-        AbsoluteColor::WHITE
+        self.theme_provider
+            .system_color(SystemColor::Canvas, self.prefers_color_scheme)
     }
 
     /// Returns the default foreground color.
     pub fn default_color(&self) -> AbsoluteColor {
-        AbsoluteColor::BLACK
+        self.theme_provider
+            .system_color(SystemColor::Canvastext, self.prefers_color_scheme)
     }
 
     /// Returns the color scheme of this [`Device`].
@@ -306,13 +484,49 @@ impl Device {
         self.prefers_color_scheme
     }
 
-    pub(crate) fn is_dark_color_scheme(&self, _: ColorSchemeFlags) -> bool {
-        false
+    /// Returns the used color scheme for a given `color-scheme` value, taking
+    /// into account the user's preferred color scheme.
+    ///
+    /// <https://drafts.csswg.org/css-color-adjust/#color-scheme-prop>
+    pub fn used_color_scheme(&self, color_scheme: ColorSchemeFlags) -> PrefersColorScheme {
+        let supports_light = color_scheme.intersects(ColorSchemeFlags::LIGHT);
+        let supports_dark = color_scheme.intersects(ColorSchemeFlags::DARK);
+        match (supports_light, supports_dark) {
+            // Both color schemes are supported, so use the preferred one.
+            (true, true) => self.prefers_color_scheme,
+            (false, true) => PrefersColorScheme::Dark,
+            // Either only light is supported, or the value is `normal`, in
+            // which case the page doesn't support dark schemes at all.
+            (_, false) => PrefersColorScheme::Light,
+        }
+    }
+
+    /// Returns whether the used color-scheme for `color-scheme` should be dark.
+    pub(crate) fn is_dark_color_scheme(&self, color_scheme: ColorSchemeFlags) -> bool {
+        self.used_color_scheme(color_scheme) == PrefersColorScheme::Dark
     }
 
     /// Returns safe area insets
     pub fn safe_area_insets(&self) -> SideOffsets2D<f32, CSSPixel> {
-        SideOffsets2D::zero()
+        let inset = |name| self.environment.embedder_length(&name).unwrap_or(0.0);
+        SideOffsets2D::new(
+            inset(atom!("safe-area-inset-top")),
+            inset(atom!("safe-area-inset-right")),
+            inset(atom!("safe-area-inset-bottom")),
+            inset(atom!("safe-area-inset-left")),
+        )
+    }
+
+    /// Sets the safe area insets, returning whether any of them changed.
+    ///
+    /// See `CssEnvironment::set_embedder_length`.
+    pub fn set_safe_area_insets(&mut self, insets: SideOffsets2D<f32, CSSPixel>) -> bool {
+        let environment = &mut self.environment;
+        let mut changed = environment.set_embedder_length(atom!("safe-area-inset-top"), insets.top);
+        changed |= environment.set_embedder_length(atom!("safe-area-inset-right"), insets.right);
+        changed |= environment.set_embedder_length(atom!("safe-area-inset-bottom"), insets.bottom);
+        changed |= environment.set_embedder_length(atom!("safe-area-inset-left"), insets.left);
+        changed
     }
 
     /// Returns true if the given MIME type is supported
@@ -375,8 +589,27 @@ fn eval_prefers_color_scheme(context: &Context, query_value: Option<PrefersColor
     }
 }
 
+/// https://drafts.csswg.org/mediaqueries-5/#forced-colors
+fn eval_forced_colors(context: &Context, query_value: Option<ForcedColors>) -> bool {
+    let forced = context.device().forced_colors();
+    match query_value {
+        Some(query_value) => query_value == forced,
+        None => forced != ForcedColors::None,
+    }
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#color-gamut
+fn eval_color_gamut(context: &Context, query_value: Option<ColorGamut>) -> bool {
+    let query_value = match query_value {
+        Some(v) => v,
+        None => return false,
+    };
+    // Match if our color gamut is at least as wide as the query value
+    query_value <= context.device().color_gamut()
+}
+
 /// A list with all the media features that Servo supports.
-pub static MEDIA_FEATURES: [QueryFeatureDescription; 6] = [
+pub static MEDIA_FEATURES: [QueryFeatureDescription; 8] = [
     feature!(
         atom!("width"),
         AllowsRanges::Yes,
@@ -413,4 +646,16 @@ pub static MEDIA_FEATURES: [QueryFeatureDescription; 6] = [
         keyword_evaluator!(eval_prefers_color_scheme, PrefersColorScheme),
         FeatureFlags::empty(),
     ),
+    feature!(
+        atom!("forced-colors"),
+        AllowsRanges::No,
+        keyword_evaluator!(eval_forced_colors, ForcedColors),
+        FeatureFlags::empty(),
+    ),
+    feature!(
+        atom!("color-gamut"),
+        AllowsRanges::No,
+        keyword_evaluator!(eval_color_gamut, ColorGamut),
+        FeatureFlags::empty(),
+    ),
 ];
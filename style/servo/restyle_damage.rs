@@ -266,5 +266,19 @@ fn compute_damage(old: &ComputedValues, new: &ComputedValues) -> ServoRestyleDam
         damage.insert(ServoRestyleDamage::rebuild_and_reflow());
     }
 
+    // Changes to whether this element establishes a stacking context or a
+    // containing block (which can happen due to `will-change` alone) need the
+    // box tree to be rebuilt. These checks aren't cheap, so skip them if we
+    // are already rebuilding.
+    if !damage.contains(ServoRestyleDamage::rebuild_and_reflow()) &&
+        (old.establishes_stacking_context() != new.establishes_stacking_context() ||
+            old.establishes_containing_block_for_absolute_descendants() !=
+                new.establishes_containing_block_for_absolute_descendants() ||
+            old.establishes_containing_block_for_all_descendants() !=
+                new.establishes_containing_block_for_all_descendants())
+    {
+        damage.insert(ServoRestyleDamage::rebuild_and_reflow());
+    }
+
     damage
 }
@@ -10,7 +10,7 @@ use crate::values::computed::{Context, ToComputedValue};
 use cssparser::Parser;
 use servo_arc::Arc;
 use std::fmt::{self, Write};
-use style_traits::{CssWriter, ParseError, ToCss};
+use style_traits::{CssWriter, ParseError, StyleParseErrorKind, ToCss};
 use to_shmem::{SharedMemoryBuilder, ToShmem};
 use url::Url;
 
@@ -120,6 +120,9 @@ impl CssUrl {
         input: &mut Parser<'i, 't>,
         cors_mode: CorsMode,
     ) -> Result<Self, ParseError<'i>> {
+        if !context.allows_urls() {
+            return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
         let url = input.expect_url()?;
         Ok(Self::parse_from_string(
             url.as_ref().to_owned(),
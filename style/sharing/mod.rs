@@ -682,6 +682,33 @@ impl<E: TElement> StyleSharingCache<E> {
             return;
         }
 
+        // Styles that substituted attr() depend on the element's attributes,
+        // which aren't part of the sharing checks.
+        if style.style().flags.intersects(ComputedValueFlags::USES_ATTR_FUNCTION) {
+            debug!("Failing to insert to the cache: attr() function");
+            return;
+        }
+
+        // Likewise, tree-counting functions depend on the element's position
+        // among its siblings.
+        if style
+            .style()
+            .flags
+            .intersects(ComputedValueFlags::USES_TREE_COUNTING_FUNCTIONS)
+        {
+            debug!("Failing to insert to the cache: tree-counting function");
+            return;
+        }
+
+        if style
+            .style()
+            .flags
+            .intersects(ComputedValueFlags::USES_PER_ELEMENT_RANDOM_VALUES)
+        {
+            debug!("Failing to insert to the cache: per-element random value");
+            return;
+        }
+
         debug!(
             "Inserting into cache: {:?} with parent {:?}",
             element, parent
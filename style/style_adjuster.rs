@@ -156,9 +156,12 @@ impl<'a, 'b: 'a> StyleAdjuster<'a, 'b> {
     /// This makes the element not be a flex container, with all that it
     /// implies, but it should be safe. It matches blink, see
     /// https://bugzilla.mozilla.org/show_bug.cgi?id=1786147#c10
-    #[cfg(feature = "gecko")]
+    ///
+    /// In Servo, which has no `-webkit-box-orient`, the orientation isn't
+    /// checked, and the legacy clamp is then expressed in terms of the
+    /// standard `line-clamp` longhands, so that layout only needs to look at
+    /// those.
     fn adjust_for_webkit_line_clamp(&mut self) {
-        use crate::properties::longhands::_moz_box_orient::computed_value::T as BoxOrient;
         use crate::values::specified::box_::{DisplayInside, DisplayOutside};
         let box_style = self.style.get_box();
         if box_style.clone__webkit_line_clamp().is_none() {
@@ -168,8 +171,12 @@ impl<'a, 'b: 'a> StyleAdjuster<'a, 'b> {
         if disp.inside() != DisplayInside::WebkitBox {
             return;
         }
-        if self.style.get_xul().clone__moz_box_orient() != BoxOrient::Vertical {
-            return;
+        #[cfg(feature = "gecko")]
+        {
+            use crate::properties::longhands::_moz_box_orient::computed_value::T as BoxOrient;
+            if self.style.get_xul().clone__moz_box_orient() != BoxOrient::Vertical {
+                return;
+            }
         }
         let new_display = if disp.outside() == DisplayOutside::Block {
             Display::FlowRoot
@@ -180,6 +187,20 @@ impl<'a, 'b: 'a> StyleAdjuster<'a, 'b> {
         self.style
             .mutate_box()
             .set_adjusted_display(new_display, false);
+
+        #[cfg(feature = "servo")]
+        {
+            use crate::computed_values::continue_::T as Continue;
+            use crate::values::computed::text::BlockEllipsis;
+
+            let line_clamp = self.style.get_box().clone__webkit_line_clamp();
+            let box_style = self.style.mutate_box();
+            box_style.set_max_lines(line_clamp);
+            box_style.set_continue_(Continue::WebkitLegacy);
+            self.style
+                .mutate_inherited_text()
+                .set_block_ellipsis(BlockEllipsis::Auto);
+        }
     }
 
     /// CSS 2.1 section 9.7:
@@ -965,7 +986,6 @@ impl<'a, 'b: 'a> StyleAdjuster<'a, 'b> {
         }
         self.adjust_for_top_layer();
         self.blockify_if_necessary(layout_parent_style, element);
-        #[cfg(feature = "gecko")]
         self.adjust_for_webkit_line_clamp();
         self.adjust_for_position();
         self.adjust_for_overflow();
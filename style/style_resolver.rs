@@ -389,6 +389,7 @@ where
             FirstLineReparenting::No,
             Some(&self.context.thread_local.rule_cache),
             &mut conditions,
+            Some(&mut self.context.thread_local.selector_caches.nth_index),
         );
 
         self.context.thread_local.rule_cache.insert_if_possible(
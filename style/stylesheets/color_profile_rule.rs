@@ -0,0 +1,162 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! The [`@color-profile`][color-profile] at-rule.
+//!
+//! [color-profile]: https://drafts.csswg.org/css-color-5/#at-profile
+
+use crate::error_reporting::ContextualParseError;
+use crate::parser::{Parse, ParserContext};
+use crate::shared_lock::{SharedRwLockReadGuard, ToCssWithGuard};
+use crate::str::CssStringWriter;
+use crate::values::{CssUrl, DashedIdent};
+use cssparser::{
+    AtRuleParser, CowRcStr, DeclarationParser, Parser, QualifiedRuleParser, RuleBodyItemParser,
+    RuleBodyParser, SourceLocation,
+};
+use selectors::parser::SelectorParseErrorKind;
+use std::fmt::{self, Write};
+use style_traits::{CssWriter, ParseError, StyleParseErrorKind, ToCss};
+
+/// The value of the `rendering-intent` descriptor.
+///
+/// Matrix/TRC profiles only have colorimetric transforms, so this doesn't affect how colors
+/// are resolved for now.
+///
+/// <https://drafts.csswg.org/css-color-5/#descdef-color-profile-rendering-intent>
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, MallocSizeOf, Parse, PartialEq, ToCss, ToShmem)]
+#[repr(u8)]
+pub enum RenderingIntent {
+    RelativeColorimetric,
+    AbsoluteColorimetric,
+    Perceptual,
+    Saturation,
+}
+
+/// The [`@color-profile`][color-profile] at-rule.
+///
+/// The embedder is responsible for loading the profile from `src`, and for handing it to the
+/// device, see `Device::set_color_profile`.
+///
+/// [color-profile]: https://drafts.csswg.org/css-color-5/#at-profile
+#[derive(Clone, Debug, PartialEq, ToShmem)]
+pub struct ColorProfileRule {
+    /// The name of the profile, used by `color()`.
+    pub name: DashedIdent,
+    /// The URL of the ICC profile.
+    pub src: Option<CssUrl>,
+    /// The rendering intent to use with the profile.
+    pub rendering_intent: Option<RenderingIntent>,
+    /// The line and column of the rule's source code.
+    pub source_location: SourceLocation,
+}
+
+impl ColorProfileRule {
+    /// Parses a `ColorProfileRule`.
+    pub fn parse(
+        context: &ParserContext,
+        input: &mut Parser,
+        name: DashedIdent,
+        location: SourceLocation,
+    ) -> Self {
+        let mut rule = ColorProfileRule {
+            name,
+            src: None,
+            rendering_intent: None,
+            source_location: location,
+        };
+        let mut parser = ColorProfileDeclarationParser {
+            context,
+            rule: &mut rule,
+        };
+        for declaration in RuleBodyParser::new(input, &mut parser) {
+            if let Err((error, slice)) = declaration {
+                let location = error.location;
+                let error = ContextualParseError::UnsupportedColorProfileDescriptor(slice, error);
+                context.log_css_error(location, error);
+            }
+        }
+        rule
+    }
+
+    /// Prints inside of `@color-profile` block.
+    fn value_to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        if let Some(ref src) = self.src {
+            dest.write_str("src: ")?;
+            src.to_css(dest)?;
+            dest.write_str("; ")?;
+        }
+        if let Some(ref rendering_intent) = self.rendering_intent {
+            dest.write_str("rendering-intent: ")?;
+            rendering_intent.to_css(dest)?;
+            dest.write_str("; ")?;
+        }
+        Ok(())
+    }
+}
+
+impl ToCssWithGuard for ColorProfileRule {
+    fn to_css(&self, _guard: &SharedRwLockReadGuard, dest: &mut CssStringWriter) -> fmt::Result {
+        dest.write_str("@color-profile ")?;
+        self.name.to_css(&mut CssWriter::new(dest))?;
+        dest.write_str(" { ")?;
+        self.value_to_css(&mut CssWriter::new(dest))?;
+        dest.write_char('}')
+    }
+}
+
+/// Parser for declarations in `ColorProfileRule`.
+struct ColorProfileDeclarationParser<'a> {
+    context: &'a ParserContext<'a>,
+    rule: &'a mut ColorProfileRule,
+}
+
+impl<'a, 'i> AtRuleParser<'i> for ColorProfileDeclarationParser<'a> {
+    type Prelude = ();
+    type AtRule = ();
+    type Error = StyleParseErrorKind<'i>;
+}
+
+impl<'a, 'i> QualifiedRuleParser<'i> for ColorProfileDeclarationParser<'a> {
+    type Prelude = ();
+    type QualifiedRule = ();
+    type Error = StyleParseErrorKind<'i>;
+}
+
+impl<'a, 'i> DeclarationParser<'i> for ColorProfileDeclarationParser<'a> {
+    type Declaration = ();
+    type Error = StyleParseErrorKind<'i>;
+
+    fn parse_value<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<(), ParseError<'i>> {
+        match_ignore_ascii_case! { &*name,
+            "src" => {
+                self.rule.src = Some(input.parse_entirely(|i| CssUrl::parse(self.context, i))?)
+            },
+            "rendering-intent" => {
+                self.rule.rendering_intent = Some(input.parse_entirely(RenderingIntent::parse)?)
+            },
+            _ => return Err(input.new_custom_error(SelectorParseErrorKind::UnexpectedIdent(name.clone()))),
+        }
+        Ok(())
+    }
+}
+
+impl<'a, 'i> RuleBodyItemParser<'i, (), StyleParseErrorKind<'i>>
+    for ColorProfileDeclarationParser<'a>
+{
+    fn parse_declarations(&self) -> bool {
+        true
+    }
+    fn parse_qualified(&self) -> bool {
+        false
+    }
+}
@@ -19,6 +19,7 @@ use crate::shared_lock::{Locked, ToCssWithGuard};
 use crate::str::CssStringWriter;
 use crate::stylesheets::rule_parser::VendorPrefix;
 use crate::stylesheets::{CssRuleType, StylesheetContents};
+use crate::values::specified::animation::TimelineRangeName;
 use crate::values::{serialize_percentage, KeyframesName};
 use cssparser::{
     parse_one_rule, AtRuleParser, DeclarationParser, Parser, ParserInput, ParserState,
@@ -27,6 +28,7 @@ use cssparser::{
 use servo_arc::Arc;
 use std::borrow::Cow;
 use std::fmt::{self, Write};
+use style_traits::values::SequenceWriter;
 use style_traits::{CssWriter, ParseError, ParsingMode, StyleParseErrorKind, ToCss};
 
 /// A [`@keyframes`][keyframes] rule.
@@ -152,29 +154,124 @@ impl KeyframePercentage {
     }
 }
 
+/// A single entry of a keyframe selector: a percentage of the animation, or a
+/// percentage of a named timeline range such as `entry 50%`.
+///
+/// https://drafts.csswg.org/scroll-animations-1/#named-range-keyframes
+#[derive(Clone, Copy, Debug, Eq, MallocSizeOf, PartialEq, ToCss, ToShmem)]
+pub struct KeyframeOffset {
+    /// The named timeline range this offset is relative to, if any.
+    pub range_name: Option<TimelineRangeName>,
+    /// The percentage of the animation, or of the named timeline range.
+    pub percentage: KeyframePercentage,
+}
+
+#[cfg(feature = "servo")]
+fn timeline_range_keyframes_enabled() -> bool {
+    style_config::get_bool("layout.css.scroll-driven-animations.enabled")
+}
+
+#[cfg(not(feature = "servo"))]
+fn timeline_range_keyframes_enabled() -> bool {
+    false
+}
+
+impl KeyframeOffset {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<KeyframeOffset, ParseError<'i>> {
+        let range_name = if timeline_range_keyframes_enabled() {
+            input.try_parse(TimelineRangeName::parse).ok()
+        } else {
+            None
+        };
+        let range_name = match range_name {
+            Some(range_name) => range_name,
+            None => {
+                return Ok(KeyframeOffset {
+                    range_name: None,
+                    percentage: KeyframePercentage::parse(input)?,
+                })
+            },
+        };
+
+        // A named timeline range is always followed by a percentage, and the
+        // from/to keywords aren't allowed here.
+        let location = input.current_source_location();
+        let percentage = input.expect_percentage()?;
+        if !(0. ..=1.).contains(&percentage) {
+            return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
+        Ok(KeyframeOffset {
+            range_name: Some(range_name),
+            percentage: KeyframePercentage::new(percentage),
+        })
+    }
+}
+
 /// A keyframes selector is a list of percentages or from/to symbols, which are
-/// converted at parse time to percentages.
-#[derive(Clone, Debug, Eq, PartialEq, ToCss, ToShmem)]
-#[css(comma)]
-pub struct KeyframeSelector(#[css(iterable)] Vec<KeyframePercentage>);
+/// converted at parse time to percentages, optionally relative to a named
+/// timeline range.
+#[derive(Clone, Debug, Eq, PartialEq, ToShmem)]
+pub struct KeyframeSelector {
+    percentages: Vec<KeyframePercentage>,
+    /// The named timeline range of each of the percentages, if any.
+    range_names: Vec<Option<TimelineRangeName>>,
+}
+
+impl ToCss for KeyframeSelector {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        let mut writer = SequenceWriter::new(dest, ", ");
+        for offset in self.offsets() {
+            writer.item(&offset)?;
+        }
+        Ok(())
+    }
+}
 
 impl KeyframeSelector {
-    /// Return the list of percentages this selector contains.
+    /// Return the list of percentages this selector contains. Percentages
+    /// attached to a named timeline range are relative to that range, see
+    /// `offsets`.
     #[inline]
     pub fn percentages(&self) -> &[KeyframePercentage] {
-        &self.0
+        &self.percentages
+    }
+
+    /// Return the offsets this selector contains.
+    pub fn offsets<'a>(&'a self) -> impl Iterator<Item = KeyframeOffset> + 'a {
+        self.percentages
+            .iter()
+            .zip(self.range_names.iter())
+            .map(|(&percentage, &range_name)| KeyframeOffset {
+                range_name,
+                percentage,
+            })
     }
 
     /// A dummy public function so we can write a unit test for this.
     pub fn new_for_unit_testing(percentages: Vec<KeyframePercentage>) -> KeyframeSelector {
-        KeyframeSelector(percentages)
+        KeyframeSelector {
+            range_names: vec![None; percentages.len()],
+            percentages,
+        }
     }
 
     /// Parse a keyframe selector from CSS input.
     pub fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i>> {
-        input
-            .parse_comma_separated(KeyframePercentage::parse)
-            .map(KeyframeSelector)
+        let mut percentages = vec![];
+        let mut range_names = vec![];
+        input.parse_comma_separated(|input| {
+            let offset = KeyframeOffset::parse(input)?;
+            percentages.push(offset.percentage);
+            range_names.push(offset.range_name);
+            Ok(())
+        })?;
+        Ok(KeyframeSelector {
+            percentages,
+            range_names,
+        })
     }
 }
 
@@ -274,8 +371,11 @@ pub enum KeyframesStepValue {
 /// A single step from a keyframe animation.
 #[derive(Clone, Debug, MallocSizeOf)]
 pub struct KeyframesStep {
-    /// The percentage of the animation duration when this step starts.
+    /// The percentage of the animation duration when this step starts, or the
+    /// percentage of `range_name` if set.
     pub start_percentage: KeyframePercentage,
+    /// The named timeline range `start_percentage` is relative to, if any.
+    pub range_name: Option<TimelineRangeName>,
     /// Declarations that will determine the final style during the step, or
     /// `ComputedValues` if this is an autogenerated step.
     pub value: KeyframesStepValue,
@@ -295,6 +395,7 @@ impl KeyframesStep {
     #[inline]
     fn new(
         start_percentage: KeyframePercentage,
+        range_name: Option<TimelineRangeName>,
         value: KeyframesStepValue,
         guard: &SharedRwLockReadGuard,
     ) -> Self {
@@ -320,6 +421,7 @@ impl KeyframesStep {
 
         KeyframesStep {
             start_percentage,
+            range_name,
             value,
             declared_timing_function,
             declared_composition,
@@ -404,6 +506,10 @@ impl KeyframesStep {
 pub struct KeyframesAnimation {
     /// The difference steps of the animation.
     pub steps: Vec<KeyframesStep>,
+    /// The steps attached to a named timeline range, in source order. Their
+    /// position in the animation depends on the timeline, so they're not part
+    /// of `steps`.
+    pub timeline_range_steps: Vec<KeyframesStep>,
     /// The properties that change in this animation.
     pub properties_changed: PropertyDeclarationIdSet,
     /// Vendor prefix type the @keyframes has.
@@ -430,6 +536,8 @@ fn get_animated_properties(
         for declaration in block.normal_declaration_iter() {
             let declaration_id = declaration.id();
 
+            // Gecko doesn't animate 'display' from keyframes.
+            #[cfg(feature = "gecko")]
             if declaration_id == PropertyDeclarationId::Longhand(LonghandId::Display) {
                 continue;
             }
@@ -461,6 +569,7 @@ impl KeyframesAnimation {
     ) -> Self {
         let mut result = KeyframesAnimation {
             steps: vec![],
+            timeline_range_steps: vec![],
             properties_changed: PropertyDeclarationIdSet::default(),
             vendor_prefix,
         };
@@ -476,14 +585,20 @@ impl KeyframesAnimation {
 
         for keyframe in keyframes {
             let keyframe = keyframe.read_with(&guard);
-            for percentage in keyframe.selector.0.iter() {
-                result.steps.push(KeyframesStep::new(
-                    *percentage,
+            for offset in keyframe.selector.offsets() {
+                let step = KeyframesStep::new(
+                    offset.percentage,
+                    offset.range_name,
                     KeyframesStepValue::Declarations {
                         block: keyframe.block.clone(),
                     },
                     guard,
-                ));
+                );
+                if offset.range_name.is_some() {
+                    result.timeline_range_steps.push(step);
+                } else {
+                    result.steps.push(step);
+                }
             }
         }
 
@@ -491,11 +606,12 @@ impl KeyframesAnimation {
         result.steps.sort_by_key(|step| step.start_percentage);
 
         // Prepend autogenerated keyframes if appropriate.
-        if result.steps[0].start_percentage.0 != 0. {
+        if !matches!(result.steps.first(), Some(step) if step.start_percentage.0 == 0.) {
             result.steps.insert(
                 0,
                 KeyframesStep::new(
                     KeyframePercentage::new(0.),
+                    None,
                     KeyframesStepValue::ComputedValues,
                     guard,
                 ),
@@ -505,6 +621,7 @@ impl KeyframesAnimation {
         if result.steps.last().unwrap().start_percentage.0 != 1. {
             result.steps.push(KeyframesStep::new(
                 KeyframePercentage::new(1.),
+                None,
                 KeyframesStepValue::ComputedValues,
                 guard,
             ));
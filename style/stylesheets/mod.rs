@@ -4,6 +4,7 @@
 
 //! Style sheets and their CSS rules.
 
+pub mod color_profile_rule;
 pub mod container_rule;
 mod counter_style_rule;
 mod document_rule;
@@ -51,6 +52,7 @@ use std::mem::{self, ManuallyDrop};
 use style_traits::ParsingMode;
 use to_shmem::{SharedMemoryBuilder, ToShmem};
 
+pub use self::color_profile_rule::ColorProfileRule;
 pub use self::container_rule::ContainerRule;
 pub use self::counter_style_rule::CounterStyleRule;
 pub use self::document_rule::DocumentRule;
@@ -336,6 +338,7 @@ pub enum CssRule {
     FontFace(Arc<Locked<FontFaceRule>>),
     FontFeatureValues(Arc<FontFeatureValuesRule>),
     FontPaletteValues(Arc<FontPaletteValuesRule>),
+    ColorProfile(Arc<ColorProfileRule>),
     CounterStyle(Arc<Locked<CounterStyleRule>>),
     Keyframes(Arc<Locked<KeyframesRule>>),
     Margin(Arc<MarginRule>),
@@ -376,6 +379,7 @@ impl CssRule {
             CssRule::FontFace(_) => 0,
             CssRule::FontFeatureValues(_) => 0,
             CssRule::FontPaletteValues(_) => 0,
+            CssRule::ColorProfile(_) => 0,
             CssRule::CounterStyle(_) => 0,
             CssRule::Keyframes(_) => 0,
             CssRule::Margin(ref arc) => {
@@ -452,6 +456,8 @@ pub enum CssRuleType {
     PositionTry = 23,
     // https://drafts.csswg.org/css-nesting-1/#nested-declarations-rule
     NestedDeclarations = 24,
+    // https://drafts.csswg.org/css-color-5/#at-profile
+    ColorProfile = 25,
 }
 
 impl CssRuleType {
@@ -531,6 +537,7 @@ impl CssRule {
             CssRule::FontFace(_) => CssRuleType::FontFace,
             CssRule::FontFeatureValues(_) => CssRuleType::FontFeatureValues,
             CssRule::FontPaletteValues(_) => CssRuleType::FontPaletteValues,
+            CssRule::ColorProfile(_) => CssRuleType::ColorProfile,
             CssRule::CounterStyle(_) => CssRuleType::CounterStyle,
             CssRule::Keyframes(_) => CssRuleType::Keyframes,
             CssRule::Margin(_) => CssRuleType::Margin,
@@ -650,6 +657,7 @@ impl DeepCloneWithLock for CssRule {
             },
             CssRule::FontFeatureValues(ref arc) => CssRule::FontFeatureValues(arc.clone()),
             CssRule::FontPaletteValues(ref arc) => CssRule::FontPaletteValues(arc.clone()),
+            CssRule::ColorProfile(ref arc) => CssRule::ColorProfile(arc.clone()),
             CssRule::CounterStyle(ref arc) => {
                 let rule = arc.read_with(guard);
                 CssRule::CounterStyle(Arc::new(lock.wrap(rule.clone())))
@@ -708,6 +716,7 @@ impl ToCssWithGuard for CssRule {
             CssRule::FontFace(ref lock) => lock.read_with(guard).to_css(guard, dest),
             CssRule::FontFeatureValues(ref rule) => rule.to_css(guard, dest),
             CssRule::FontPaletteValues(ref rule) => rule.to_css(guard, dest),
+            CssRule::ColorProfile(ref rule) => rule.to_css(guard, dest),
             CssRule::CounterStyle(ref lock) => lock.read_with(guard).to_css(guard, dest),
             CssRule::Keyframes(ref lock) => lock.read_with(guard).to_css(guard, dest),
             CssRule::Margin(ref rule) => rule.to_css(guard, dest),
@@ -26,8 +26,8 @@ use crate::stylesheets::layer_rule::{LayerBlockRule, LayerName, LayerStatementRu
 use crate::stylesheets::scope_rule::{ScopeBounds, ScopeRule};
 use crate::stylesheets::supports_rule::SupportsCondition;
 use crate::stylesheets::{
-    AllowImportRules, CorsMode, CssRule, CssRuleType, CssRuleTypes, CssRules, DocumentRule,
-    FontFeatureValuesRule, FontPaletteValuesRule, KeyframesRule, MarginRule, MarginRuleType,
+    AllowImportRules, ColorProfileRule, CorsMode, CssRule, CssRuleType, CssRuleTypes, CssRules,
+    DocumentRule, FontFeatureValuesRule, FontPaletteValuesRule, KeyframesRule, MarginRule, MarginRuleType,
     MediaRule, NamespaceRule, PageRule, PageSelectors, RulesMutateError, StyleRule,
     StylesheetLoader, SupportsRule, StartingStyleRule, NestedDeclarationsRule, PositionTryRule
 };
@@ -242,6 +242,8 @@ pub enum AtRulePrelude {
     FontFeatureValues(Vec<FamilyName>),
     /// A @font-palette-values rule prelude, with its identifier.
     FontPaletteValues(DashedIdent),
+    /// A @color-profile rule prelude, with its identifier.
+    ColorProfile(DashedIdent),
     /// A @counter-style rule prelude, with its counter style name.
     CounterStyle(CustomIdent),
     /// A @media rule prelude, with its media queries.
@@ -285,6 +287,7 @@ impl AtRulePrelude {
             Self::FontFace => "font-face",
             Self::FontFeatureValues(..) => "font-feature-values",
             Self::FontPaletteValues(..) => "font-palette-values",
+            Self::ColorProfile(..) => "color-profile",
             Self::CounterStyle(..) => "counter-style",
             Self::Media(..) => "media",
             Self::Container(..) => "container",
@@ -521,6 +524,7 @@ impl<'a, 'i> NestedRuleParser<'a, 'i> {
             AtRulePrelude::FontFace |
             AtRulePrelude::FontFeatureValues(..) |
             AtRulePrelude::FontPaletteValues(..) |
+            AtRulePrelude::ColorProfile(..) |
             AtRulePrelude::CounterStyle(..) |
             AtRulePrelude::Keyframes(..) |
             AtRulePrelude::Page(..) |
@@ -703,6 +707,10 @@ impl<'a, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'i> {
                 let name = DashedIdent::parse(&self.context, input)?;
                 AtRulePrelude::FontPaletteValues(name)
             },
+            "color-profile" if crate::color::parsing::color_profile_enabled() => {
+                let name = DashedIdent::parse(&self.context, input)?;
+                AtRulePrelude::ColorProfile(name)
+            },
             "counter-style" if cfg!(feature = "gecko") => {
                 let name = parse_counter_style_name_definition(input)?;
                 AtRulePrelude::CounterStyle(name)
@@ -799,6 +807,16 @@ impl<'a, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'i> {
                     )))
                 })
             },
+            AtRulePrelude::ColorProfile(name) => {
+                self.nest_for_rule(CssRuleType::ColorProfile, |p| {
+                    CssRule::ColorProfile(Arc::new(ColorProfileRule::parse(
+                        &p.context,
+                        input,
+                        name,
+                        source_location,
+                    )))
+                })
+            },
             AtRulePrelude::CounterStyle(name) => {
                 let body = self.nest_for_rule(CssRuleType::CounterStyle, |p| {
                     parse_counter_style_body(name, &p.context, input, source_location)
@@ -71,6 +71,7 @@ where
             CssRule::LayerStatement(_) |
             CssRule::FontFeatureValues(_) |
             CssRule::FontPaletteValues(_) |
+            CssRule::ColorProfile(_) |
             CssRule::NestedDeclarations(_) |
             CssRule::PositionTry(_) => None,
             CssRule::Page(ref page_rule) => {
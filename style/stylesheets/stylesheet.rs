@@ -373,6 +373,7 @@ impl SanitizationKind {
             CssRule::Property(..) |
             CssRule::FontFeatureValues(..) |
             CssRule::FontPaletteValues(..) |
+            CssRule::ColorProfile(..) |
             CssRule::CounterStyle(..) => !is_standard,
         }
     }
@@ -21,6 +21,7 @@ use crate::invalidation::media_queries::{
 };
 use crate::invalidation::stylesheets::RuleChangeKind;
 use crate::media_queries::Device;
+use crate::queries::QueryCondition;
 use crate::properties::{self, CascadeMode, ComputedValues, FirstLineReparenting};
 use crate::properties::{AnimationDeclarations, PropertyDeclarationBlock};
 use crate::properties_and_values::registry::{
@@ -50,7 +51,7 @@ use crate::stylesheets::{
     PagePseudoClassFlags,
 };
 use crate::stylesheets::{
-    CssRule, EffectiveRulesIterator, Origin, OriginSet, PageRule, PerOrigin,
+    ColorProfileRule, CssRule, EffectiveRulesIterator, Origin, OriginSet, PageRule, PerOrigin,
     PerOriginIter, StylesheetContents, StylesheetInDocument,
 };
 use crate::values::{computed, AtomIdent};
@@ -72,12 +73,14 @@ use selectors::parser::{
     SelectorList,
 };
 use selectors::visitor::{SelectorListKind, SelectorVisitor};
+use selectors::NthIndexCache;
 use servo_arc::{Arc, ArcBorrow};
 use smallvec::SmallVec;
 use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use std::sync::Mutex;
 use std::{mem, ops};
+use style_traits::ToCss;
 
 /// The type of the stylesheets that the stylist contains.
 #[cfg(feature = "servo")]
@@ -315,7 +318,9 @@ impl CascadeDataCacheEntry for UserAgentCascadeData {
             )?;
         }
 
-        new_data.cascade_data.did_finish_rebuild();
+        new_data
+            .cascade_data
+            .did_finish_rebuild(device, quirks_mode);
 
         Ok(Arc::new(new_data))
     }
@@ -775,6 +780,14 @@ impl Stylist {
         PropertyRegistrationData::unregistered()
     }
 
+    /// Returns the effective @color-profile rule with the given name, if any. The embedder uses
+    /// this to load the profile that `color()` functions with this name refer to, see
+    /// `Device::set_color_profile`.
+    pub fn color_profile_rule(&self, name: &Atom) -> Option<&Arc<ColorProfileRule>> {
+        self.iter_origins()
+            .find_map(|(data, _)| data.color_profiles.get(name))
+    }
+
     /// Returns custom properties with their registered initial values.
     pub fn get_custom_property_initial_values(&self) -> &ComputedCustomProperties {
         &self.initial_values_for_custom_properties
@@ -1220,6 +1233,7 @@ impl Stylist {
             FirstLineReparenting::No,
             /* rule_cache = */ None,
             &mut RuleCacheConditions::default(),
+            /* nth_index_cache = */ None,
         )
     }
 
@@ -1235,6 +1249,9 @@ impl Stylist {
     ///
     /// layout_parent_style is the style used for some property fixups.  It's
     /// the style of the nearest ancestor with a layout box.
+    ///
+    /// nth_index_cache, if given, is used to share sibling indices between the
+    /// tree-counting functions of the elements styled by the traversal.
     pub fn cascade_style_and_visited<E>(
         &self,
         element: Option<E>,
@@ -1246,6 +1263,7 @@ impl Stylist {
         first_line_reparenting: FirstLineReparenting,
         rule_cache: Option<&RuleCache>,
         rule_cache_conditions: &mut RuleCacheConditions,
+        nth_index_cache: Option<&mut NthIndexCache>,
     ) -> Arc<ComputedValues>
     where
         E: TElement,
@@ -1284,6 +1302,7 @@ impl Stylist {
             rule_cache,
             rule_cache_conditions,
             element,
+            nth_index_cache,
         )
     }
 
@@ -1439,6 +1458,14 @@ impl Stylist {
             }
         }
 
+        for (cascade_data, origin) in self.cascade_data.iter_origins() {
+            if !origins.contains(origin.into()) &&
+                cascade_data.if_media_conditions_changed(device, self.quirks_mode)
+            {
+                origins |= origin;
+            }
+        }
+
         origins
     }
 
@@ -1702,6 +1729,7 @@ impl Stylist {
             /* rule_cache = */ None,
             &mut Default::default(),
             /* element = */ None,
+            /* tree_counting_provider = */ None,
         )
     }
 
@@ -2697,6 +2725,11 @@ pub struct CascadeData {
     #[ignore_malloc_size_of = "Arc"]
     custom_property_registrations: LayerOrderedMap<Arc<PropertyRegistration>>,
 
+    /// A map with all the layer-ordered @color-profile rules at this `CascadeData`'s origin,
+    /// indexed by name.
+    #[ignore_malloc_size_of = "Arc"]
+    color_profiles: LayerOrderedMap<Arc<ColorProfileRule>>,
+
     /// A map from cascade layer name to layer order.
     layer_id: FxHashMap<LayerName, LayerId>,
 
@@ -2727,6 +2760,23 @@ pub struct CascadeData {
 
     /// The total number of declarations.
     num_declarations: usize,
+
+    /// The `media()` conditions of `if()` functions in declarations, along with whether they
+    /// matched when this data was built, so that media feature changes that flip them restyle.
+    /// Conditions in style attributes aren't tracked.
+    ///
+    /// Conditions can't be hashed, so they're keyed by their serialization.
+    if_media_conditions: FxHashMap<String, (QueryCondition, bool)>,
+}
+
+fn if_media_condition_matches(
+    condition: &QueryCondition,
+    device: &Device,
+    quirks_mode: QuirksMode,
+) -> bool {
+    computed::Context::for_media_query_evaluation(device, quirks_mode, |context| {
+        condition.matches(context).to_bool(/* unknown = */ false)
+    })
 }
 
 fn parent_selector_for_scope(parent: Option<&SelectorList<SelectorImpl>>) -> &SelectorList<SelectorImpl> {
@@ -2766,6 +2816,7 @@ impl CascadeData {
             selectors_for_cache_revalidation: SelectorMap::new(),
             animations: Default::default(),
             custom_property_registrations: Default::default(),
+            color_profiles: Default::default(),
             layer_id: Default::default(),
             layers: smallvec::smallvec![CascadeLayer::root()],
             container_conditions: smallvec::smallvec![ContainerConditionReference::none()],
@@ -2776,6 +2827,7 @@ impl CascadeData {
             rules_source_order: 0,
             num_selectors: 0,
             num_declarations: 0,
+            if_media_conditions: Default::default(),
         }
     }
 
@@ -2818,7 +2870,7 @@ impl CascadeData {
             result.is_ok()
         });
 
-        self.did_finish_rebuild();
+        self.did_finish_rebuild(device, quirks_mode);
 
         result
     }
@@ -2855,7 +2907,11 @@ impl CascadeData {
     }
 
     /// Returns whether the given attribute might appear in an attribute
-    /// selector of some rule.
+    /// selector of some rule, or in an `attr()` function of some declaration.
+    ///
+    /// Note that `attr()` functions in style attributes aren't accounted for
+    /// here, so attribute changes on elements whose styles use `attr()` (see
+    /// `ComputedValueFlags::USES_ATTR_FUNCTION`) also need to be snapshotted.
     #[inline]
     pub fn might_have_attribute_dependency(&self, local_name: &LocalName) -> bool {
         self.attribute_dependencies.contains(local_name)
@@ -3123,9 +3179,20 @@ impl CascadeData {
         }
     }
 
-    fn did_finish_rebuild(&mut self) {
+    fn did_finish_rebuild(&mut self, device: &Device, quirks_mode: QuirksMode) {
         self.shrink_maps_if_needed();
         self.compute_layer_order();
+        for &mut (ref condition, ref mut matches) in self.if_media_conditions.values_mut() {
+            *matches = if_media_condition_matches(condition, device, quirks_mode);
+        }
+    }
+
+    /// Returns whether the result of any `media()` condition in an `if()` function changed since
+    /// this data was built.
+    pub fn if_media_conditions_changed(&self, device: &Device, quirks_mode: QuirksMode) -> bool {
+        self.if_media_conditions.values().any(|&(ref condition, matched)| {
+            if_media_condition_matches(condition, device, quirks_mode) != matched
+        })
     }
 
     fn shrink_maps_if_needed(&mut self) {
@@ -3138,6 +3205,7 @@ impl CascadeData {
         }
         self.animations.shrink_if_needed();
         self.custom_property_registrations.shrink_if_needed();
+        self.color_profiles.shrink_if_needed();
         self.invalidation_map.shrink_if_needed();
         self.relative_selector_invalidation_map.shrink_if_needed();
         self.attribute_dependencies.shrink_if_needed();
@@ -3194,7 +3262,8 @@ impl CascadeData {
         }
         self.animations
             .sort_with(&self.layers, compare_keyframes_in_same_layer);
-        self.custom_property_registrations.sort(&self.layers)
+        self.custom_property_registrations.sort(&self.layers);
+        self.color_profiles.sort(&self.layers)
     }
 
     /// Collects all the applicable media query results into `results`.
@@ -3255,7 +3324,9 @@ impl CascadeData {
         mut precomputed_pseudo_element_decls: Option<&mut PrecomputedPseudoElementDeclarations>,
         quirks_mode: QuirksMode,
     ) -> Result<(), AllocErr> {
-        self.num_declarations += declarations.read_with(guard).len();
+        let block = declarations.read_with(guard);
+        self.num_declarations += block.len();
+        self.note_substitution_function_dependencies(block);
         for selector in selectors.slice() {
             self.num_selectors += 1;
 
@@ -3405,6 +3476,25 @@ impl CascadeData {
         Ok(())
     }
 
+    /// Notes the attributes referenced from `attr()` functions, and the
+    /// `media()` conditions of `if()` functions, in the given declarations, so
+    /// that changes to them are invalidated.
+    fn note_substitution_function_dependencies(&mut self, block: &PropertyDeclarationBlock) {
+        block.each_unsubstituted_value(|value| {
+            value.each_referenced_attribute(|name| {
+                let name = LocalName::from(name.as_ref());
+                self.attribute_dependencies.insert(name.clone());
+                self.invalidation_map.attr_function_dependencies.insert(name);
+            });
+            value.each_if_media_condition(|condition| {
+                self.if_media_conditions
+                    .entry(condition.to_css_string())
+                    // Evaluated once the rebuild finishes.
+                    .or_insert_with(|| (condition.clone(), false));
+            });
+        });
+    }
+
     fn add_rule_list<S>(
         &mut self,
         rules: std::slice::Iter<CssRule>,
@@ -3508,6 +3598,13 @@ impl CascadeData {
                         containing_rule_state.layer_id,
                     )?;
                 },
+                CssRule::ColorProfile(ref rule) => {
+                    self.color_profiles.try_insert(
+                        rule.name.0.clone(),
+                        Arc::clone(rule),
+                        containing_rule_state.layer_id,
+                    )?;
+                },
                 #[cfg(feature = "gecko")]
                 CssRule::FontFace(ref rule) => {
                     // NOTE(emilio): We don't care about container_condition_id
@@ -3872,6 +3969,7 @@ impl CascadeData {
                 CssRule::LayerBlock(..) |
                 CssRule::LayerStatement(..) |
                 CssRule::FontPaletteValues(..) |
+                CssRule::ColorProfile(..) |
                 CssRule::FontFeatureValues(..) |
                 CssRule::Scope(..) |
                 CssRule::StartingStyle(..) |
@@ -3932,6 +4030,11 @@ impl CascadeData {
         &self.custom_property_registrations
     }
 
+    /// Returns the @color-profile rules map.
+    pub fn color_profiles(&self) -> &LayerOrderedMap<Arc<ColorProfileRule>> {
+        &self.color_profiles
+    }
+
     fn revalidate_scopes<E: TElement>(
         &self,
         stylist: &Stylist,
@@ -3980,6 +4083,7 @@ impl CascadeData {
         }
         self.animations.clear();
         self.custom_property_registrations.clear();
+        self.color_profiles.clear();
         self.layer_id.clear();
         self.layers.clear();
         self.layers.push(CascadeLayer::root());
@@ -3993,6 +4097,7 @@ impl CascadeData {
         self.rules_source_order = 0;
         self.num_selectors = 0;
         self.num_declarations = 0;
+        self.if_media_conditions.clear();
     }
 
     fn clear(&mut self) {
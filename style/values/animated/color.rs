@@ -9,7 +9,9 @@ use crate::color::AbsoluteColor;
 use crate::values::animated::{Animate, Procedure, ToAnimatedZero};
 use crate::values::computed::Percentage;
 use crate::values::distance::{ComputeSquaredDistance, SquaredDistance};
-use crate::values::generics::color::{ColorMixFlags, GenericColor, GenericColorMix};
+use crate::values::generics::color::{
+    ColorMixFlags, ColorMixItem, GenericColor, GenericColorMix,
+};
 
 impl Animate for AbsoluteColor {
     #[inline]
@@ -59,14 +61,36 @@ impl Animate for Color {
     #[inline]
     fn animate(&self, other: &Self, procedure: Procedure) -> Result<Self, ()> {
         let (left_weight, right_weight) = procedure.weights();
+        let (left_weight, right_weight) = (left_weight as f32, right_weight as f32);
+        let interpolation = ColorInterpolationMethod::srgb();
+        // See https://github.com/w3c/csswg-drafts/issues/7324
+        let flags = ColorMixFlags::empty();
+        // Mix absolute colors right away, which is the common case, to avoid
+        // allocating the items of a color-mix().
+        if let (Some(left), Some(right)) = (self.as_absolute(), other.as_absolute()) {
+            return Ok(Self::Absolute(crate::color::mix::mix(
+                interpolation,
+                left,
+                left_weight,
+                right,
+                right_weight,
+                flags,
+            )));
+        }
+        let items: Box<[_]> = Box::new([
+            ColorMixItem {
+                color: self.clone(),
+                percentage: Percentage(left_weight),
+            },
+            ColorMixItem {
+                color: other.clone(),
+                percentage: Percentage(right_weight),
+            },
+        ]);
         Ok(Self::from_color_mix(ColorMix {
-            interpolation: ColorInterpolationMethod::srgb(),
-            left: self.clone(),
-            left_percentage: Percentage(left_weight as f32),
-            right: other.clone(),
-            right_percentage: Percentage(right_weight as f32),
-            // See https://github.com/w3c/csswg-drafts/issues/7324
-            flags: ColorMixFlags::empty(),
+            interpolation,
+            items: items.into(),
+            flags,
         }))
     }
 }
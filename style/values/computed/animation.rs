@@ -13,7 +13,8 @@ use style_traits::{CssWriter, ToCss};
 
 pub use crate::values::specified::animation::{
     AnimationComposition, AnimationDirection, AnimationFillMode, AnimationName, AnimationPlayState,
-    ScrollAxis, TimelineName, TransitionBehavior, TransitionProperty, ViewTransitionName,
+    ScrollAxis, TimelineName, TimelineRangeName, TransitionBehavior, TransitionProperty,
+    ViewTransitionName,
 };
 
 /// A computed value for the `animation-duration` property.
@@ -81,5 +82,9 @@ impl ToCss for AnimationIterationCount {
 /// A computed value for the `animation-timeline` property.
 pub type AnimationTimeline = generics::GenericAnimationTimeline<LengthPercentage>;
 
+/// A computed value for the `animation-range-start` and `animation-range-end`
+/// properties.
+pub type AnimationRangeValue = generics::GenericAnimationRangeValue<LengthPercentage>;
+
 /// A computed value for the `view-timeline-inset` property.
 pub type ViewTimelineInset = generics::GenericViewTimelineInset<LengthPercentage>;
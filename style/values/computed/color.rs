@@ -34,6 +34,11 @@ impl ToCss for Color {
             Self::ColorFunction(ref color_function) => color_function.to_css(dest),
             Self::CurrentColor => dest.write_str("currentcolor"),
             Self::ColorMix(ref m) => m.to_css(dest),
+            Self::ContrastColor(ref c) => {
+                dest.write_str("contrast-color(")?;
+                c.to_css(dest)?;
+                dest.write_char(')')
+            },
         }
     }
 }
@@ -61,25 +66,17 @@ impl Color {
     /// Combine this complex color with the given foreground color into an
     /// absolute color.
     pub fn resolve_to_absolute(&self, current_color: &AbsoluteColor) -> AbsoluteColor {
-        use crate::values::specified::percentage::ToPercentage;
-
         match *self {
             Self::Absolute(c) => c,
             Self::ColorFunction(ref color_function) => {
                 color_function.resolve_to_absolute(current_color)
             },
             Self::CurrentColor => *current_color,
-            Self::ColorMix(ref mix) => {
-                let left = mix.left.resolve_to_absolute(current_color);
-                let right = mix.right.resolve_to_absolute(current_color);
-                crate::color::mix::mix(
-                    mix.interpolation,
-                    &left,
-                    mix.left_percentage.to_percentage(),
-                    &right,
-                    mix.right_percentage.to_percentage(),
-                    mix.flags,
-                )
+            Self::ColorMix(ref mix) => mix
+                .mix_with(|color| Some(color.resolve_to_absolute(current_color)))
+                .unwrap(),
+            Self::ContrastColor(ref color) => {
+                color.resolve_to_absolute(current_color).contrast_color()
             },
         }
     }
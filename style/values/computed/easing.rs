@@ -7,9 +7,15 @@
 use euclid::approxeq::ApproxEq;
 
 use crate::bezier::Bezier;
+use crate::parser::{Parse, ParserContext};
 use crate::piecewise_linear::PiecewiseLinearFunction;
+use crate::stylesheets::{Origin, UrlExtraData};
 use crate::values::computed::{Integer, Number};
 use crate::values::generics::easing::{self, BeforeFlag, StepPosition, TimingKeyword};
+use crate::values::specified::easing::TimingFunction as SpecifiedTimingFunction;
+use cssparser::{Parser, ParserInput};
+use selectors::matching::QuirksMode;
+use style_traits::{ParseError, ParsingMode};
 
 /// A computed timing function.
 pub type ComputedTimingFunction = easing::TimingFunction<Integer, Number, PiecewiseLinearFunction>;
@@ -17,6 +23,19 @@ pub type ComputedTimingFunction = easing::TimingFunction<Integer, Number, Piecew
 /// An alias of the computed timing function.
 pub type TimingFunction = ComputedTimingFunction;
 
+/// The precision used to solve cubic bézier curves when the duration of the
+/// animation is unknown. This is precise enough for animations of up to 5000
+/// seconds, using the usual epsilon of `1 / (200 * duration)`.
+const DEFAULT_EPSILON: f64 = 1e-6;
+
+// Embedders evaluate timing functions off the style thread, e.g. on a
+// compositor.
+#[allow(unused)]
+fn static_assert() {
+    fn assert_send_and_sync<T: Send + Sync + 'static>() {}
+    assert_send_and_sync::<ComputedTimingFunction>();
+}
+
 impl ComputedTimingFunction {
     fn calculate_step_output(
         steps: i32,
@@ -68,7 +87,19 @@ impl ComputedTimingFunction {
     }
 
     /// The output of the timing function given the progress ratio of this animation.
-    pub fn calculate_output(&self, progress: f64, before_flag: BeforeFlag, epsilon: f64) -> f64 {
+    #[inline]
+    pub fn calculate_output(&self, progress: f64, before_flag: BeforeFlag) -> f64 {
+        self.calculate_output_with_epsilon(progress, before_flag, DEFAULT_EPSILON)
+    }
+
+    /// As `calculate_output`, but solving cubic bézier curves with the given
+    /// precision, which is usually `1 / (200 * duration)` for a duration in seconds.
+    pub fn calculate_output_with_epsilon(
+        &self,
+        progress: f64,
+        before_flag: BeforeFlag,
+        epsilon: f64,
+    ) -> f64 {
         let progress = match self {
             TimingFunction::CubicBezier { x1, y1, x2, y2 } => {
                 Bezier::calculate_bezier_output(progress, epsilon, *x1, *y1, *x2, *y2)
@@ -106,4 +137,70 @@ impl ComputedTimingFunction {
         // https://github.com/w3c/csswg-drafts/issues/8344
         progress.min(f64::MAX).max(f64::MIN)
     }
+
+    /// Samples this timing function over the input progress range [0, 1] into a
+    /// polyline of `(input, output)` points, e.g. for devtools to draw it. The
+    /// range is split into `segments` evenly spaced segments, and the points of
+    /// `linear()` functions are kept as-is. Step functions get two points at each
+    /// jump, for the output values just before and at the jump.
+    pub fn sample_polyline(&self, segments: usize) -> Vec<(f64, f64)> {
+        let segments = segments.max(1);
+        let mut inputs: Vec<f64> = (0..=segments).map(|i| i as f64 / segments as f64).collect();
+        match *self {
+            TimingFunction::LinearFunction(ref function) => {
+                let mut points = vec![(0., self.calculate_output(0., BeforeFlag::Unset))];
+                points.extend(
+                    function
+                        .iter()
+                        .filter(|entry| entry.x > 0. && entry.x < 1.)
+                        .map(|entry| (entry.x as f64, entry.y as f64)),
+                );
+                points.push((1., self.calculate_output(1., BeforeFlag::Unset)));
+                return points;
+            },
+            // Don't bother with the jumps if there are more than segments, the
+            // evenly spaced samples are as good as it gets then.
+            TimingFunction::Steps(steps, _) if steps > 0 && steps as usize <= segments => {
+                inputs.extend((0..=steps).map(|i| i as f64 / steps as f64));
+                inputs.sort_by(f64::total_cmp);
+                inputs.dedup();
+            },
+            _ => {},
+        }
+
+        let mut points = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            if let TimingFunction::Steps(..) = *self {
+                let before = self.calculate_output(input, BeforeFlag::Set);
+                if before != self.calculate_output(input, BeforeFlag::Unset) {
+                    points.push((input, before));
+                }
+            }
+            points.push((input, self.calculate_output(input, BeforeFlag::Unset)));
+        }
+        points
+    }
+
+    /// Parses a timing function from its serialization, such as the output of
+    /// `ToCss`, so that embedders can ship it to other threads or processes as
+    /// a string.
+    pub fn parse_serialized<'i>(
+        url_data: &UrlExtraData,
+        css: &'i str,
+    ) -> Result<Self, ParseError<'i>> {
+        let context = ParserContext::new(
+            Origin::Author,
+            url_data,
+            None,
+            ParsingMode::DEFAULT,
+            QuirksMode::NoQuirks,
+            /* namespaces = */ Default::default(),
+            None,
+            None,
+        );
+        let mut input = ParserInput::new(css);
+        let function = Parser::new(&mut input)
+            .parse_entirely(|input| SpecifiedTimingFunction::parse(&context, input))?;
+        Ok(function.to_computed_value_without_context())
+    }
 }
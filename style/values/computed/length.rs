@@ -9,8 +9,8 @@ use crate::values::animated::{Context as AnimatedContext, ToAnimatedValue};
 use crate::values::computed::{NonNegativeNumber, Zoom};
 use crate::values::generics::length as generics;
 use crate::values::generics::length::{
-    GenericAnchorSizeFunction, GenericLengthOrNumber, GenericLengthPercentageOrNormal,
-    GenericMaxSize, GenericSize,
+    CalcSizeBasis, GenericAnchorSizeFunction, GenericCalcSize, GenericLengthOrNumber,
+    GenericLengthPercentageOrNormal, GenericMaxSize, GenericSize,
 };
 use crate::values::generics::NonNegative;
 use crate::values::resolved::{Context as ResolvedContext, ToResolvedValue};
@@ -20,6 +20,7 @@ use crate::Zero;
 use app_units::Au;
 use std::fmt::{self, Write};
 use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+use style_traits::values::specified::AllowedNumericType;
 use style_traits::{CSSPixel, CssWriter, ToCss};
 
 pub use super::image::Image;
@@ -66,6 +67,19 @@ impl specified::NoCalcLength {
     }
 }
 
+/// Something that can compute relative lengths, so that they can be divided by other lengths in
+/// calculations that don't otherwise accept lengths, like `calc(1em / 1px)` in a `<number>`.
+pub trait RelativeLengthProvider {
+    /// Returns the computed value of the given length.
+    fn compute_length(&self, length: &specified::NoCalcLength) -> Length;
+}
+
+impl<'a> RelativeLengthProvider for Context<'a> {
+    fn compute_length(&self, length: &specified::NoCalcLength) -> Length {
+        length.to_computed_value(self)
+    }
+}
+
 impl ToComputedValue for specified::Length {
     type ComputedValue = Length;
 
@@ -179,54 +193,68 @@ impl NonNegativeLengthPercentageOrAuto {
 #[cfg(feature = "servo")]
 impl MaxSize {
     /// Convert the computed value into used value.
+    ///
+    /// `resolve_basis` returns the size of the basis of a `calc-size()` function, if known.
     #[inline]
-    pub fn to_used_value(&self, percentage_basis: Au) -> Option<Au> {
-        match *self {
-            Self::None | Self::MinContent | Self::MaxContent | Self::FitContent | Self::Stretch => {
-                None
-            },
-            Self::LengthPercentage(ref lp) => Some(lp.to_used_value(percentage_basis)),
-            Self::AnchorSizeFunction(_) => unreachable!("anchor-size() should be disabled"),
-        }
+    pub fn to_used_value(
+        &self,
+        percentage_basis: Au,
+        resolve_basis: impl FnOnce(CalcSizeBasis) -> Option<Au>,
+    ) -> Option<Au> {
+        self.maybe_to_used_value(Some(percentage_basis), resolve_basis)
     }
 
     /// Convert the computed value into used value if there is enough information.
     #[inline]
-    pub fn maybe_to_used_value(&self, percentage_basis: Option<Au>) -> Option<Au> {
+    pub fn maybe_to_used_value(
+        &self,
+        percentage_basis: Option<Au>,
+        resolve_basis: impl FnOnce(CalcSizeBasis) -> Option<Au>,
+    ) -> Option<Au> {
         match *self {
             Self::None | Self::MinContent | Self::MaxContent | Self::FitContent | Self::Stretch => {
                 None
             },
             Self::LengthPercentage(ref lp) => lp.maybe_to_used_value(percentage_basis),
             Self::AnchorSizeFunction(_) => unreachable!("anchor-size() should be disabled"),
+            Self::CalcSize(ref calc_size) => {
+                calc_size.maybe_to_used_value(percentage_basis, resolve_basis)
+            },
         }
     }
 }
 
 impl Size {
     /// Convert the computed value into used value.
+    ///
+    /// `resolve_basis` returns the size of the basis of a `calc-size()` function, if known.
     #[inline]
     #[cfg(feature = "servo")]
-    pub fn to_used_value(&self, percentage_basis: Au) -> Option<Au> {
-        match *self {
-            Self::Auto | Self::MinContent | Self::MaxContent | Self::FitContent | Self::Stretch => {
-                None
-            },
-            Self::LengthPercentage(ref lp) => Some(lp.to_used_value(percentage_basis)),
-            Self::AnchorSizeFunction(_) => unreachable!("anchor-size() should be disabled"),
-        }
+    pub fn to_used_value(
+        &self,
+        percentage_basis: Au,
+        resolve_basis: impl FnOnce(CalcSizeBasis) -> Option<Au>,
+    ) -> Option<Au> {
+        self.maybe_to_used_value(Some(percentage_basis), resolve_basis)
     }
 
     /// Convert the computed value into used value if there is enough information.
     #[inline]
     #[cfg(feature = "servo")]
-    pub fn maybe_to_used_value(&self, percentage_basis: Option<Au>) -> Option<Au> {
+    pub fn maybe_to_used_value(
+        &self,
+        percentage_basis: Option<Au>,
+        resolve_basis: impl FnOnce(CalcSizeBasis) -> Option<Au>,
+    ) -> Option<Au> {
         match *self {
             Self::Auto | Self::MinContent | Self::MaxContent | Self::FitContent | Self::Stretch => {
                 None
             },
             Self::LengthPercentage(ref lp) => lp.maybe_to_used_value(percentage_basis),
             Self::AnchorSizeFunction(_) => unreachable!("anchor-size() should be disabled"),
+            Self::CalcSize(ref calc_size) => {
+                calc_size.maybe_to_used_value(percentage_basis, resolve_basis)
+            },
         }
     }
 
@@ -240,13 +268,136 @@ impl Size {
             Self::MaxContent |
             Self::FitContent |
             Self::Stretch |
-            Self::AnchorSizeFunction(_) => false,
+            Self::AnchorSizeFunction(_) |
+            Self::CalcSize(_) => false,
             #[cfg(feature = "gecko")]
             Self::MozAvailable | Self::WebkitFillAvailable | Self::FitContentFunction(_) => false,
         }
     }
 }
 
+/// Returns whether intrinsic size keywords should be interpolated, per `interpolate-size`.
+fn interpolates_size_keywords(context: &AnimatedContext) -> bool {
+    #[cfg(feature = "servo")]
+    {
+        use crate::properties::longhands::interpolate_size::computed_value::T as InterpolateSize;
+        context.style.get_inherited_box().clone_interpolate_size() == InterpolateSize::AllowKeywords
+    }
+    #[cfg(not(feature = "servo"))]
+    {
+        let _ = context;
+        false
+    }
+}
+
+/// Implements `ToAnimatedValue` for sizing properties, turning intrinsic size keywords into
+/// `calc-size(<keyword>, size)` when `interpolate-size` allows them to be interpolated.
+macro_rules! size_to_animated_value {
+    ($size:ident, $generic:ident, [$($keyword:ident),*]) => {
+        impl ToAnimatedValue for $size {
+            type AnimatedValue = $generic<LengthPercentage>;
+
+            fn to_animated_value(self, context: &AnimatedContext) -> Self::AnimatedValue {
+                if let Some(basis) = self.calc_size_basis() {
+                    if interpolates_size_keywords(context) {
+                        return $generic::CalcSize(Box::new(CalcSize::keyword(basis).to_animated_value(context)));
+                    }
+                }
+                match self {
+                    $generic::LengthPercentage(lp) => $generic::LengthPercentage(lp.to_animated_value(context)),
+                    $generic::AnchorSizeFunction(f) => $generic::AnchorSizeFunction(f.to_animated_value(context)),
+                    $generic::CalcSize(c) => $generic::CalcSize(c.to_animated_value(context)),
+                    #[cfg(feature = "gecko")]
+                    $generic::FitContentFunction(lp) => $generic::FitContentFunction(lp.to_animated_value(context)),
+                    #[cfg(feature = "gecko")]
+                    $generic::MozAvailable => $generic::MozAvailable,
+                    #[cfg(feature = "gecko")]
+                    $generic::WebkitFillAvailable => $generic::WebkitFillAvailable,
+                    $($generic::$keyword => $generic::$keyword,)*
+                }
+            }
+
+            fn from_animated_value(animated: Self::AnimatedValue) -> Self {
+                match animated {
+                    $generic::LengthPercentage(lp) => $generic::LengthPercentage(ToAnimatedValue::from_animated_value(lp)),
+                    $generic::AnchorSizeFunction(f) => $generic::AnchorSizeFunction(ToAnimatedValue::from_animated_value(f)),
+                    // Interpolating a calc-size() with a <length-percentage> gives an `any` basis,
+                    // which is equivalent to its calculation.
+                    $generic::CalcSize(c) => match c.basis {
+                        CalcSizeBasis::Any => $generic::LengthPercentage(ToAnimatedValue::from_animated_value(c.calculation)),
+                        _ => $generic::CalcSize(ToAnimatedValue::from_animated_value(c)),
+                    },
+                    #[cfg(feature = "gecko")]
+                    $generic::FitContentFunction(lp) => $generic::FitContentFunction(ToAnimatedValue::from_animated_value(lp)),
+                    #[cfg(feature = "gecko")]
+                    $generic::MozAvailable => $generic::MozAvailable,
+                    #[cfg(feature = "gecko")]
+                    $generic::WebkitFillAvailable => $generic::WebkitFillAvailable,
+                    $($generic::$keyword => $generic::$keyword,)*
+                }
+            }
+        }
+    };
+}
+
+size_to_animated_value!(Size, GenericSize, [Auto, MaxContent, MinContent, FitContent, Stretch]);
+size_to_animated_value!(MaxSize, GenericMaxSize, [None, MaxContent, MinContent, FitContent, Stretch]);
+
+/// A computed value for the `calc-size()` function.
+pub type CalcSize = GenericCalcSize<NonNegativeLengthPercentage>;
+
+impl CalcSize {
+    /// Returns `calc-size(<basis>, size)`, which is how intrinsic size keywords are interpolated.
+    pub fn keyword(basis: CalcSizeBasis) -> Self {
+        let size = super::length_percentage::CalcNode::SizeKeyword;
+        Self {
+            basis,
+            calculation: NonNegative(LengthPercentage::new_calc(
+                size,
+                AllowedNumericType::NonNegative,
+                /* has_anchor_function = */ false,
+            )),
+        }
+    }
+
+    /// Resolves the calculation against the given percentage basis. Layout provides the size of
+    /// the basis through `resolve_basis`, which is only called if the calculation uses it.
+    pub fn resolve(
+        &self,
+        percentage_basis: Length,
+        resolve_basis: impl FnOnce(CalcSizeBasis) -> Length,
+    ) -> Length {
+        self.calculation
+            .0
+            .resolve_with_size(percentage_basis, || resolve_basis(self.basis))
+    }
+
+    /// Converts the calculation into a used value if there is enough information, that is, if
+    /// there's a percentage basis whenever the calculation has percentages, and `resolve_basis`
+    /// knows the size of the basis whenever the calculation uses the `size` keyword.
+    pub fn maybe_to_used_value(
+        &self,
+        percentage_basis: Option<Au>,
+        resolve_basis: impl FnOnce(CalcSizeBasis) -> Option<Au>,
+    ) -> Option<Au> {
+        let mut basis_is_unknown = false;
+        let length = self.calculation.0.maybe_resolve_with_size(
+            percentage_basis.map(Length::from),
+            || match resolve_basis(self.basis) {
+                Some(size) => size.into(),
+                None => {
+                    basis_is_unknown = true;
+                    Length::zero()
+                },
+            },
+        )?;
+        if basis_is_unknown {
+            return None;
+        }
+        Some(std::cmp::max(Au::from(length), Au(0)))
+    }
+}
+
 /// The computed `<length>` value.
 #[derive(
     Animate,
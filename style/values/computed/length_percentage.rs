@@ -499,6 +499,30 @@ impl LengthPercentage {
         }
     }
 
+    /// Resolves the percentage, as well as the `size` keyword if this is the calculation of a
+    /// `calc-size()` function. `size` is only called if the keyword is used.
+    #[inline]
+    pub fn resolve_with_size(&self, basis: Length, size: impl FnOnce() -> Length) -> Length {
+        match self.unpack() {
+            Unpacked::Calc(c) => c.resolve_with_size(basis, size),
+            _ => self.resolve(basis),
+        }
+    }
+
+    /// Like `resolve_with_size`, but returns `None` if there are percentages to resolve and no
+    /// basis for them.
+    #[inline]
+    pub fn maybe_resolve_with_size(
+        &self,
+        basis: Option<Length>,
+        size: impl FnOnce() -> Length,
+    ) -> Option<Length> {
+        match self.unpack() {
+            Unpacked::Calc(c) => c.maybe_resolve_with_size(basis, size),
+            _ => self.maybe_percentage_relative_to(basis),
+        }
+    }
+
     /// Resolves the percentage. Just an alias of resolve().
     #[inline]
     pub fn percentage_relative_to(&self, basis: Length) -> Length {
@@ -759,6 +783,14 @@ impl calc::CalcNodeLeaf for CalcLengthPercentageLeaf {
         })
     }
 
+    fn canonical_value(&self) -> Option<f32> {
+        match *self {
+            // Percentages can't be multiplied without knowing what they're relative to.
+            Self::Percentage(_) => None,
+            _ => self.unitless_value(),
+        }
+    }
+
     fn new_number(value: f32) -> Self {
         Self::Number(value)
     }
@@ -949,12 +981,35 @@ impl CalcLengthPercentage {
     /// Resolves the percentage.
     #[inline]
     pub fn resolve(&self, basis: Length) -> Length {
-        // unwrap() is fine because the conversion below is infallible.
+        self.resolve_with_size(basis, || {
+            debug_assert!(
+                false,
+                "The size keyword is only valid within calc-size(), use resolve_with_size"
+            );
+            Length::zero()
+        })
+    }
+
+    /// Resolves the percentage and the `size` keyword of a `calc-size()` calculation.
+    pub fn resolve_with_size(&self, basis: Length, size: impl FnOnce() -> Length) -> Length {
+        // unwrap() is fine because the conversion below is infallible when there's a basis.
+        self.maybe_resolve_with_size(Some(basis), size).unwrap()
+    }
+
+    /// Resolves the percentage and the `size` keyword of a `calc-size()` calculation, returning
+    /// `None` if there are percentages and no basis for them.
+    pub fn maybe_resolve_with_size(
+        &self,
+        basis: Option<Length>,
+        size: impl FnOnce() -> Length,
+    ) -> Option<Length> {
+        let mut size = Some(size);
+        let mut resolved_size = None;
         if let CalcLengthPercentageLeaf::Length(px) = self
             .node
             .resolve_map(|leaf| {
                 Ok(if let CalcLengthPercentageLeaf::Percentage(p) = leaf {
-                    CalcLengthPercentageLeaf::Length(Length::new(basis.px() * p.0))
+                    CalcLengthPercentageLeaf::Length(Length::new(basis.ok_or(())?.px() * p.0))
                 } else {
                     leaf.clone()
                 })
@@ -972,12 +1027,16 @@ impl CalcLengthPercentage {
                         }
                         Ok(CalcNode::Leaf(CalcLengthPercentageLeaf::Length(Length::zero())))
                     }
+                    CalcNode::SizeKeyword => {
+                        let size = *resolved_size.get_or_insert_with(|| (size.take().unwrap())());
+                        Ok(CalcNode::Leaf(CalcLengthPercentageLeaf::Length(size)))
+                    },
                     _ => Err(()),
                 }
             })
-            .unwrap()
+            .ok()?
         {
-            Length::new(self.clamping_mode.clamp(px.px())).normalized()
+            Some(Length::new(self.clamping_mode.clamp(px.px())).normalized())
         } else {
             unreachable!("resolve_map should turn percentages to lengths, and parsing should ensure that we don't end up with a number");
         }
@@ -1078,6 +1137,10 @@ impl specified::CalcLengthPercentage {
                 }
             }),
             Leaf::Number(n) => CalcLengthPercentageLeaf::Number(n),
+            Leaf::TreeCountingFunction(ref f) => {
+                CalcLengthPercentageLeaf::Number(f.to_computed_value(context) as CSSFloat)
+            },
+            Leaf::QueryProgress(ref f) => CalcLengthPercentageLeaf::Number(f.evaluate(context)),
             Leaf::Angle(..) | Leaf::Time(..) | Leaf::Resolution(..) | Leaf::ColorComponent(..) => {
                 unreachable!("Shouldn't have parsed")
             },
@@ -17,11 +17,15 @@ use super::{CSSFloat, CSSInteger};
 use crate::computed_value_flags::ComputedValueFlags;
 use crate::context::QuirksMode;
 use crate::custom_properties::ComputedCustomProperties;
+use crate::dom::{
+    AttributeProvider, DummyAttributeProvider, DummyTreeCountingProvider, OpaqueNode,
+    TreeCountingProvider,
+};
 use crate::font_metrics::{FontMetrics, FontMetricsOrientation};
 use crate::media_queries::Device;
 #[cfg(feature = "gecko")]
 use crate::properties;
-use crate::properties::{ComputedValues, StyleBuilder};
+use crate::properties::{ComputedValues, PropertyId, StyleBuilder};
 use crate::rule_cache::RuleCacheConditions;
 use crate::stylesheets::container_rule::{
     ContainerInfo, ContainerSizeQuery, ContainerSizeQueryResult,
@@ -31,7 +35,7 @@ use crate::values::specified::length::FontBaseSize;
 use crate::{ArcSlice, Atom, One};
 use euclid::{default, Point2D, Rect, Size2D};
 use servo_arc::Arc;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::cmp;
 use std::f32;
 use std::ops::{Add, Sub};
@@ -41,8 +45,9 @@ pub use self::align::{AlignSelf, JustifySelf};
 pub use self::angle::Angle;
 pub use self::animation::{
     AnimationComposition, AnimationDirection, AnimationDuration, AnimationFillMode,
-    AnimationIterationCount, AnimationName, AnimationPlayState, AnimationTimeline, ScrollAxis,
-    TimelineName, TransitionBehavior, TransitionProperty, ViewTimelineInset, ViewTransitionName,
+    AnimationIterationCount, AnimationName, AnimationPlayState, AnimationRangeValue,
+    AnimationTimeline, ScrollAxis, TimelineName, TimelineRangeName, TransitionBehavior,
+    TransitionProperty, ViewTimelineInset, ViewTransitionName,
 };
 pub use self::background::{BackgroundRepeat, BackgroundSize};
 pub use self::basic_shape::FillRule;
@@ -103,7 +108,7 @@ pub use self::resolution::Resolution;
 pub use self::svg::{DProperty, MozContextProperties};
 pub use self::svg::{SVGLength, SVGOpacity, SVGPaint, SVGPaintKind};
 pub use self::svg::{SVGPaintOrder, SVGStrokeDashArray, SVGWidth, VectorEffect};
-pub use self::text::{HyphenateCharacter, HyphenateLimitChars};
+pub use self::text::{BlockEllipsis, HyphenateCharacter, HyphenateLimitChars};
 pub use self::text::TextUnderlinePosition;
 pub use self::text::{InitialLetter, LetterSpacing, LineBreak, TextIndent};
 pub use self::text::{OverflowWrap, RubyPosition, TextOverflow, WordBreak, WordSpacing};
@@ -147,6 +152,7 @@ pub mod outline;
 pub mod page;
 pub mod percentage;
 pub mod position;
+pub mod random;
 pub mod ratio;
 pub mod rect;
 pub mod resolution;
@@ -197,6 +203,20 @@ pub struct Context<'a> {
     /// Returns the container information to evaluate a given container query.
     pub container_info: Option<ContainerInfo>,
 
+    /// The source of attribute values for `attr()` substitution.
+    pub attribute_provider: &'a dyn AttributeProvider,
+
+    /// The source of the sibling index and count for tree-counting functions.
+    pub tree_counting_provider: &'a dyn TreeCountingProvider,
+
+    /// The property whose value is being resolved at computed-value time, which keys the values
+    /// of random functions that aren't named, see `set_random_property`.
+    random_property: RefCell<Option<PropertyId>>,
+
+    /// The number of random functions resolved so far for `random_property`, which identifies
+    /// each function within its value.
+    random_function_count: Cell<usize>,
+
     /// Whether we're computing a value for a non-inherited property.
     /// False if we are computed a value for an inherited property or not computing for a property
     /// at all (e.g. in a media query evaluation).
@@ -234,6 +254,10 @@ impl<'a> Context<'a> {
             quirks_mode,
             for_smil_animation: false,
             container_info: None,
+            attribute_provider: &DummyAttributeProvider,
+            tree_counting_provider: &DummyTreeCountingProvider,
+            random_property: RefCell::new(None),
+            random_function_count: Cell::new(0),
             for_non_inherited_property: false,
             rule_cache_conditions: RefCell::new(&mut conditions),
             container_size_query: RefCell::new(ContainerSizeQuery::none()),
@@ -270,6 +294,10 @@ impl<'a> Context<'a> {
             quirks_mode,
             for_smil_animation: false,
             container_info,
+            attribute_provider: &DummyAttributeProvider,
+            tree_counting_provider: &DummyTreeCountingProvider,
+            random_property: RefCell::new(None),
+            random_function_count: Cell::new(0),
             for_non_inherited_property: false,
             rule_cache_conditions: RefCell::new(&mut conditions),
             container_size_query: RefCell::new(container_size_query),
@@ -292,6 +320,10 @@ impl<'a> Context<'a> {
             in_container_query: false,
             quirks_mode,
             container_info: None,
+            attribute_provider: &DummyAttributeProvider,
+            tree_counting_provider: &DummyTreeCountingProvider,
+            random_property: RefCell::new(None),
+            random_function_count: Cell::new(0),
             for_smil_animation: false,
             for_non_inherited_property: false,
             rule_cache_conditions: RefCell::new(rule_cache_conditions),
@@ -314,6 +346,10 @@ impl<'a> Context<'a> {
             in_container_query: false,
             quirks_mode,
             container_info: None,
+            attribute_provider: &DummyAttributeProvider,
+            tree_counting_provider: &DummyTreeCountingProvider,
+            random_property: RefCell::new(None),
+            random_function_count: Cell::new(0),
             for_smil_animation,
             for_non_inherited_property: false,
             rule_cache_conditions: RefCell::new(rule_cache_conditions),
@@ -336,6 +372,10 @@ impl<'a> Context<'a> {
             in_container_query: false,
             quirks_mode: stylist.quirks_mode(),
             container_info: None,
+            attribute_provider: &DummyAttributeProvider,
+            tree_counting_provider: &DummyTreeCountingProvider,
+            random_property: RefCell::new(None),
+            random_function_count: Cell::new(0),
             for_smil_animation: false,
             for_non_inherited_property: false,
             rule_cache_conditions: RefCell::new(rule_cache_conditions),
@@ -358,6 +398,16 @@ impl<'a> Context<'a> {
         self.builder.is_root_element
     }
 
+    /// Notes that the style depends on the position of the element in the tree, and thus can't
+    /// be shared with or cached for other elements.
+    fn note_tree_counting_function(&self) {
+        if self.for_non_inherited_property {
+            self.rule_cache_conditions.borrow_mut().set_uncacheable();
+        }
+        self.builder
+            .add_flags(ComputedValueFlags::USES_TREE_COUNTING_FUNCTIONS);
+    }
+
     /// Queries font metrics.
     pub fn query_font_metrics(
         &self,
@@ -448,6 +498,22 @@ impl<'a> Context<'a> {
     }
 }
 
+impl<'a> TreeCountingProvider for Context<'a> {
+    fn sibling_index(&self) -> u32 {
+        self.note_tree_counting_function();
+        self.tree_counting_provider.sibling_index()
+    }
+
+    fn sibling_count(&self) -> u32 {
+        self.note_tree_counting_function();
+        self.tree_counting_provider.sibling_count()
+    }
+
+    fn element(&self) -> Option<OpaqueNode> {
+        self.tree_counting_provider.element()
+    }
+}
+
 /// An iterator over a slice of computed values
 #[derive(Clone)]
 pub struct ComputedVecIter<'a, 'cx, 'cx_a: 'cx, S: ToComputedValue + 'a> {
@@ -0,0 +1,105 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Resolution of `random()` and `random-item()` functions.
+//!
+//! <https://drafts.csswg.org/css-values-5/#randomness>
+
+use crate::computed_value_flags::ComputedValueFlags;
+use crate::dom::TreeCountingProvider;
+use crate::properties::PropertyId;
+use crate::values::computed::Context;
+use crate::values::specified::calc::RandomValueSharing;
+use crate::values::CSSFloat;
+use fxhash::FxHasher64;
+use std::hash::{Hash, Hasher};
+
+/// Something that provides the base value of random functions at computed-value time.
+pub trait RandomValueProvider {
+    /// Returns the base value, in the `[0, 1)` range, of a `random()` or `random-item()`
+    /// function.
+    ///
+    /// Functions are expected to be resolved in the order they appear in the value of the
+    /// property being resolved, which identifies them if the sharing options don't name the
+    /// value.
+    fn random_base_value(&self, sharing: &RandomValueSharing, function: &str) -> CSSFloat;
+}
+
+/// The per-document state used to generate random base values, owned by the device.
+///
+/// Base values are a hash of the seed and the caching key of each function, so they're stable
+/// across restyles, and reproducible for a given seed. Per-element values identify the element
+/// through `TreeCountingProvider::element`.
+#[derive(Debug, Default)]
+pub struct RandomBaseValues {
+    /// The seed all the base values derive from.
+    seed: u64,
+}
+
+impl RandomBaseValues {
+    /// Returns the current seed.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Sets the seed random values derive from. Styles need to be recomputed for the new seed to
+    /// apply to them.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn base_value(&self, key: impl Hash) -> CSSFloat {
+        let mut hasher = FxHasher64::default();
+        self.seed.hash(&mut hasher);
+        key.hash(&mut hasher);
+        // FxHasher mixes its input poorly, so finalize it with SplitMix64 to spread the bits.
+        let mut bits = hasher.finish();
+        bits = (bits ^ (bits >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        bits = (bits ^ (bits >> 27)).wrapping_mul(0x94d049bb133111eb);
+        bits ^= bits >> 31;
+        // Use the top 24 bits, which is all a float can represent in [0, 1).
+        (bits >> 40) as CSSFloat / (1u32 << 24) as CSSFloat
+    }
+}
+
+impl<'a> Context<'a> {
+    /// Sets the property whose value is about to be resolved, which keys the values of the random
+    /// functions in it that aren't named.
+    pub fn set_random_property(&self, property: PropertyId) {
+        *self.random_property.borrow_mut() = Some(property);
+        self.random_function_count.set(0);
+    }
+}
+
+impl<'a> RandomValueProvider for Context<'a> {
+    fn random_base_value(&self, sharing: &RandomValueSharing, function: &str) -> CSSFloat {
+        let index = self.random_function_count.get();
+        self.random_function_count.set(index + 1);
+        let (name, element_shared) = match *sharing {
+            RandomValueSharing::Fixed(value) => return value,
+            RandomValueSharing::Cached {
+                ref name,
+                element_shared,
+            } => (name, element_shared),
+        };
+        let element = if element_shared {
+            None
+        } else {
+            self.builder
+                .add_flags(ComputedValueFlags::USES_PER_ELEMENT_RANDOM_VALUES);
+            Some((self.element(), self.builder.pseudo))
+        };
+        let random = self.device().random_base_values();
+        match *name {
+            Some(ref name) => random.base_value((name, element)),
+            None => random.base_value((
+                &*self.random_property.borrow(),
+                function,
+                index,
+                element,
+            )),
+        }
+    }
+}
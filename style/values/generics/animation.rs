@@ -5,7 +5,9 @@
 //! Generic values for properties related to animations and transitions.
 
 use crate::values::generics::length::GenericLengthPercentageOrAuto;
-use crate::values::specified::animation::{ScrollAxis, ScrollFunction, TimelineName};
+use crate::values::specified::animation::{
+    ScrollAxis, ScrollFunction, TimelineName, TimelineRangeName,
+};
 use crate::Zero;
 use std::fmt::{self, Write};
 use style_traits::{CssWriter, ToCss};
@@ -205,3 +207,44 @@ impl<LengthPercent> Default for ViewTimelineInset<LengthPercent> {
         }
     }
 }
+
+/// A value for the `animation-range-start` and `animation-range-end` properties.
+///
+/// https://drafts.csswg.org/scroll-animations-1/#animation-range-start
+/// https://drafts.csswg.org/scroll-animations-1/#animation-range-end
+#[derive(
+    Clone,
+    Debug,
+    MallocSizeOf,
+    PartialEq,
+    SpecifiedValueInfo,
+    ToComputedValue,
+    ToCss,
+    ToResolvedValue,
+    ToShmem,
+)]
+#[repr(C, u8)]
+pub enum GenericAnimationRangeValue<LengthPercent> {
+    /// The start (or end) of the animation's timeline.
+    Normal,
+    /// An offset measured from the start of the timeline's `cover` range.
+    LengthPercentage(LengthPercent),
+    /// An offset into a named timeline range. When omitted, this is the start
+    /// of the range for `animation-range-start`, and its end for
+    /// `animation-range-end`.
+    Named(TimelineRangeName, Option<LengthPercent>),
+}
+
+pub use self::GenericAnimationRangeValue as AnimationRangeValue;
+
+impl<LengthPercent> AnimationRangeValue<LengthPercent> {
+    /// Returns the `normal` value.
+    pub fn normal() -> Self {
+        Self::Normal
+    }
+
+    /// Returns true if it is `normal` (i.e. the default value).
+    pub fn is_normal(&self) -> bool {
+        matches!(self, Self::Normal)
+    }
+}
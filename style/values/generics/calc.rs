@@ -83,6 +83,37 @@ impl ModRemOp {
     }
 }
 
+/// Calculates the progress of `value` from `start` to `end`.
+///
+/// <https://drafts.csswg.org/css-values-5/#calculate-a-progress-function>
+pub fn progress(value: f32, start: f32, end: f32) -> f32 {
+    if start != end {
+        return (value - start) / (end - start);
+    }
+    if value < start {
+        f32::NEG_INFINITY
+    } else if value > start {
+        f32::INFINITY
+    } else {
+        0.0
+    }
+}
+
+/// Calculates the progress of a `progress()` function whose arguments are all leaves, failing if
+/// they're not of the same type.
+fn progress_of_leaves<L: CalcNodeLeaf>(value: &L, start: &L, end: &L) -> Result<f32, ()> {
+    // Resolve the offsets from the start first, which makes sure that all the arguments are in
+    // the same unit, and then canonicalize them in case the units were just convertible.
+    let mut offset = value.try_op(start, Sub::sub)?;
+    let mut range = end.try_op(start, Sub::sub)?;
+    offset.simplify();
+    range.simplify();
+    match (offset.unitless_value(), range.unitless_value()) {
+        (Some(offset), Some(range)) => Ok(progress(offset, 0.0, range)),
+        _ => Err(()),
+    }
+}
+
 /// The strategy used in `round()`
 #[derive(
     Clone,
@@ -240,6 +271,15 @@ pub enum GenericCalcNode<L> {
     },
     /// A `hypot()` function
     Hypot(crate::OwnedSlice<GenericCalcNode<L>>),
+    /// A `progress()` function.
+    Progress {
+        /// The progress value.
+        value: Box<GenericCalcNode<L>>,
+        /// The progress start value.
+        start: Box<GenericCalcNode<L>>,
+        /// The progress end value.
+        end: Box<GenericCalcNode<L>>,
+    },
     /// An `abs()` function.
     Abs(Box<GenericCalcNode<L>>),
     /// A `sign()` function.
@@ -248,6 +288,9 @@ pub enum GenericCalcNode<L> {
     Anchor(Box<GenericCalcAnchorFunction<L>>),
     /// An `anchor-size()` function.
     AnchorSize(Box<GenericCalcAnchorSizeFunction<L>>),
+    /// The `size` keyword in the calculation of a `calc-size()` function, which refers to the
+    /// size of its basis, and can only be resolved by layout.
+    SizeKeyword,
 }
 
 pub use self::GenericCalcNode as CalcNode;
@@ -336,6 +379,78 @@ macro_rules! compare_helpers {
     };
 }
 
+/// The type of a product of calculations, tracked as the exponent of each base unit, so that
+/// dimensions can be multiplied and divided by each other.
+///
+/// <https://drafts.css-houdini.org/css-typed-om-1/#cssnumericvalue-type>
+#[derive(Clone, Copy, Debug, Default)]
+struct ProductType {
+    /// The exponent of each unit, indexed by the bit position of its `CalcUnits` flag.
+    exponents: [i8; 8],
+    /// Whether one of the factors was a `<length-percentage>`, in which case percentages are
+    /// lengths for the purpose of the type (the "percent hint").
+    percent_hint: bool,
+    /// Whether one of the factors was a color component. These behave like numbers, but we can't
+    /// resolve them until the origin color is known.
+    has_color_component: bool,
+}
+
+impl ProductType {
+    /// Adds a factor of the given unit, or divides by it if `inverted` is true.
+    fn multiply(&mut self, mut unit: CalcUnits, inverted: bool) -> Result<(), ()> {
+        if unit == CalcUnits::COLOR_COMPONENT {
+            self.has_color_component = true;
+            return Ok(());
+        }
+        if unit == CalcUnits::LENGTH_PERCENTAGE {
+            self.percent_hint = true;
+            unit = CalcUnits::LENGTH;
+        }
+        if unit.is_empty() {
+            return Ok(());
+        }
+        if !unit.is_single_unit() {
+            return Err(());
+        }
+        self.exponents[unit.bits().trailing_zeros() as usize] += if inverted { -1 } else { 1 };
+        Ok(())
+    }
+
+    /// Returns the unit of the product, which is a number or a single unit with an exponent of
+    /// one. Anything else can't be represented by any property value, so it is invalid.
+    fn unit(mut self) -> Result<CalcUnits, ()> {
+        if self.percent_hint {
+            let percentage = CalcUnits::PERCENTAGE.bits().trailing_zeros() as usize;
+            let length = CalcUnits::LENGTH.bits().trailing_zeros() as usize;
+            self.exponents[length] += std::mem::take(&mut self.exponents[percentage]);
+        }
+
+        let mut unit = CalcUnits::empty();
+        for (bit, exponent) in self.exponents.iter().enumerate() {
+            match *exponent {
+                0 => continue,
+                1 if unit.is_empty() => unit = CalcUnits::from_bits_retain(1 << bit),
+                _ => return Err(()),
+            }
+        }
+
+        if unit.is_empty() {
+            return Ok(if self.has_color_component {
+                CalcUnits::COLOR_COMPONENT
+            } else {
+                CalcUnits::empty()
+            });
+        }
+        if self.has_color_component {
+            return Err(());
+        }
+        if self.percent_hint && unit == CalcUnits::LENGTH {
+            unit = CalcUnits::LENGTH_PERCENTAGE;
+        }
+        Ok(unit)
+    }
+}
+
 /// A trait that represents all the stuff a valid leaf of a calc expression.
 pub trait CalcNodeLeaf: Clone + Sized + PartialEq + ToCss {
     /// Returns the unit of the leaf.
@@ -344,6 +459,13 @@ pub trait CalcNodeLeaf: Clone + Sized + PartialEq + ToCss {
     /// Returns the unitless value of this leaf if one is available.
     fn unitless_value(&self) -> Option<f32>;
 
+    /// Returns the value of this leaf in the canonical unit of its type (px, deg, s, dppx), if it
+    /// can be converted to it. This is what gets multiplied when both sides of a product have
+    /// units.
+    fn canonical_value(&self) -> Option<f32> {
+        self.unitless_value()
+    }
+
     /// Return true if the units of both leaves are equal. (NOTE: Does not take
     /// the values into account)
     fn is_same_unit_as(&self, other: &Self) -> bool {
@@ -489,9 +611,7 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
     pub fn unit(&self) -> Result<CalcUnits, ()> {
         Ok(match self {
             CalcNode::Leaf(l) => l.unit(),
-            CalcNode::Negate(child) | CalcNode::Invert(child) | CalcNode::Abs(child) => {
-                child.unit()?
-            },
+            CalcNode::Negate(child) | CalcNode::Abs(child) => child.unit()?,
             CalcNode::Sum(children) => {
                 let mut unit = children.first().unwrap().unit()?;
                 for child in children.iter().skip(1) {
@@ -503,27 +623,10 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                 }
                 unit
             },
-            CalcNode::Product(children) => {
-                // Only one node is allowed to have a unit, the rest must be numbers.
-                let mut unit = None;
-                for child in children.iter() {
-                    let child_unit = child.unit()?;
-                    if child_unit.is_empty() {
-                        // Numbers are always allowed in a product, so continue with the next.
-                        continue;
-                    }
-
-                    if unit.is_some() {
-                        // We already have a unit for the node, so another unit node is invalid.
-                        return Err(());
-                    }
-
-                    // We have the unit for the node.
-                    unit = Some(child_unit);
-                }
-                // We only keep track of specified units, so if we end up with a None and no failure
-                // so far, then we have a number.
-                unit.unwrap_or(CalcUnits::empty())
+            CalcNode::Product(..) | CalcNode::Invert(..) => {
+                let mut product_type = ProductType::default();
+                self.multiply_type(&mut product_type, false)?;
+                product_type.unit()?
             },
             CalcNode::MinMax(children, _) | CalcNode::Hypot(children) => {
                 let mut unit = children.first().unwrap().unit()?;
@@ -576,12 +679,38 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                 let _ = child.unit()?;
                 CalcUnits::empty()
             },
+            CalcNode::Progress { value, start, end } => {
+                // Likewise for progress(), but all the arguments need to be of the same type.
+                let value_unit = value.unit()?;
+                let start_unit = start.unit()?;
+                let end_unit = end.unit()?;
+                if !value_unit.can_sum_with(start_unit) || !start_unit.can_sum_with(end_unit) {
+                    return Err(());
+                }
+                CalcUnits::empty()
+            },
             CalcNode::Anchor(..) | CalcNode::AnchorSize(..) => {
                 CalcUnits::LENGTH_PERCENTAGE
-            }
+            },
+            CalcNode::SizeKeyword => CalcUnits::LENGTH,
         })
     }
 
+    /// Multiplies `product_type` by the type of this node, or divides it if `inverted` is true.
+    fn multiply_type(&self, product_type: &mut ProductType, inverted: bool) -> Result<(), ()> {
+        match self {
+            CalcNode::Product(children) => {
+                for child in children.iter() {
+                    child.multiply_type(product_type, inverted)?;
+                }
+                Ok(())
+            },
+            CalcNode::Invert(child) => child.multiply_type(product_type, !inverted),
+            CalcNode::Negate(child) => child.multiply_type(product_type, inverted),
+            _ => product_type.multiply(self.unit()?, inverted),
+        }
+    }
+
     /// Negate the node inline.  If the node is distributive, it is replaced by the result,
     /// otherwise the node is wrapped in a [`Negate`] node.
     pub fn negate(&mut self) {
@@ -675,13 +804,13 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                     child.negate();
                 }
             },
-            CalcNode::Abs(_) => {
+            CalcNode::Abs(_) | CalcNode::Progress { .. } => {
                 wrap_self_in_negate(self);
             },
             CalcNode::Sign(ref mut child) => {
                 child.negate();
             },
-            CalcNode::Anchor(_) | CalcNode::AnchorSize(_) => {
+            CalcNode::Anchor(_) | CalcNode::AnchorSize(_) | CalcNode::SizeKeyword => {
                 wrap_self_in_negate(self);
             },
         }
@@ -690,7 +819,7 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
     fn sort_key(&self) -> SortKey {
         match *self {
             Self::Leaf(ref l) => l.sort_key(),
-            Self::Anchor(..) | Self::AnchorSize(..) => SortKey::Px,
+            Self::Anchor(..) | Self::AnchorSize(..) | Self::SizeKeyword => SortKey::Px,
             _ => SortKey::Other,
         }
     }
@@ -805,10 +934,16 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                     }
                     Ok(())
                 },
+                CalcNode::Progress { value, start, end } => {
+                    map_internal(value, op)?;
+                    map_internal(start, op)?;
+                    map_internal(end, op)
+                },
                 CalcNode::Abs(child) | CalcNode::Sign(child) => map_internal(child, op),
                 // It is invalid to treat inner `CalcNode`s here - `anchor(--foo 50%) / 2` != `anchor(--foo 25%)`.
                 // Same applies to fallback, as we don't know if it will be used. Similar reasoning applies to `anchor-size()`.
-                CalcNode::Anchor(_) | CalcNode::AnchorSize(_) => Err(())
+                CalcNode::Anchor(_) | CalcNode::AnchorSize(_) => Err(()),
+                CalcNode::SizeKeyword => Err(()),
             }
         }
 
@@ -888,6 +1023,15 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                 }
             },
             Self::Hypot(ref c) => CalcNode::Hypot(map_children(c, map)),
+            Self::Progress {
+                ref value,
+                ref start,
+                ref end,
+            } => CalcNode::Progress {
+                value: Box::new(value.map_leaves_internal(map)),
+                start: Box::new(start.map_leaves_internal(map)),
+                end: Box::new(end.map_leaves_internal(map)),
+            },
             Self::Abs(ref c) => CalcNode::Abs(Box::new(c.map_leaves_internal(map))),
             Self::Sign(ref c) => CalcNode::Sign(Box::new(c.map_leaves_internal(map))),
             Self::Anchor(ref f) => CalcNode::Anchor(Box::new(
@@ -907,6 +1051,7 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                     fallback: f.fallback.as_ref().map(|fb| Box::new(fb.map_leaves_internal(map))).into(),
                 }
             )),
+            Self::SizeKeyword => CalcNode::SizeKeyword,
         }
     }
 
@@ -924,6 +1069,58 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
         self.resolve_internal(&mut leaf_to_output_fn, &mut node_mapping_fn)
     }
 
+    /// Resolves a product in which more than one factor has a unit, by multiplying the canonical
+    /// values of the factors, and expressing the result in the unit of the type of the product.
+    ///
+    /// <https://drafts.csswg.org/css-values-4/#calc-type-checking>
+    fn resolve_typed_product<F, NF>(
+        children: &[Self],
+        leaf_to_output_fn: &mut F,
+        node_mapping_fn: &mut NF,
+    ) -> Result<L, ()>
+    where
+        F: FnMut(&L) -> Result<L, ()>,
+        NF: FnMut(&CalcNode<L>) -> Result<CalcNode<L>, ()>,
+    {
+        let mut product_type = ProductType::default();
+        for child in children.iter() {
+            child.multiply_type(&mut product_type, false)?;
+        }
+        let unit = product_type.unit()?;
+
+        let mut value = 1.0;
+        let mut result = None;
+        for child in children.iter() {
+            let (child, inverted) = match child {
+                Self::Invert(child) => (&**child, true),
+                child => (child, false),
+            };
+            let resolved = child.resolve_internal(leaf_to_output_fn, node_mapping_fn)?;
+            // Use the first factor of the resulting type as the result, so that its unit is
+            // preserved, and scale it by all the other factors.
+            let resolved_unit = resolved.unit();
+            if result.is_none() &&
+                !inverted &&
+                !resolved_unit.is_empty() &&
+                resolved_unit.can_sum_with(unit)
+            {
+                result = Some(resolved);
+                continue;
+            }
+            let factor = resolved.canonical_value().ok_or(())?;
+            value *= if inverted { 1.0 / factor } else { factor };
+        }
+
+        match result {
+            Some(mut result) => {
+                result.map(|v| v * value)?;
+                Ok(result)
+            },
+            None if unit.is_empty() => Ok(L::new_number(value)),
+            None => Err(()),
+        }
+    }
+
     fn resolve_internal<F, NF>(&self, leaf_to_output_fn: &mut F, node_mapping_fn: &mut NF) -> Result<L, ()>
     where
         F: FnMut(&L) -> Result<L, ()>,
@@ -972,8 +1169,13 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                                     result.map(|v| v * right)?;
                                 },
                                 None => {
-                                    // Multiplying with both sides having units.
-                                    return Err(());
+                                    // Both sides have units, so the product needs typed
+                                    // arithmetic.
+                                    return Self::resolve_typed_product(
+                                        children,
+                                        leaf_to_output_fn,
+                                        node_mapping_fn,
+                                    );
                                 },
                             }
                         },
@@ -1169,6 +1371,17 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                 result.map(|v| v.sqrt())?;
                 Ok(result)
             },
+            Self::Progress {
+                ref value,
+                ref start,
+                ref end,
+            } => {
+                let value = value.resolve_internal(leaf_to_output_fn, node_mapping_fn)?;
+                let start = start.resolve_internal(leaf_to_output_fn, node_mapping_fn)?;
+                let end = end.resolve_internal(leaf_to_output_fn, node_mapping_fn)?;
+
+                Ok(L::new_number(progress_of_leaves(&value, &start, &end)?))
+            },
             Self::Abs(ref c) => {
                 let mut result = c.resolve_internal(leaf_to_output_fn, node_mapping_fn)?;
 
@@ -1180,7 +1393,7 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                 let result = c.resolve_internal(leaf_to_output_fn, node_mapping_fn)?;
                 Ok(L::sign_from(&result)?)
             },
-            Self::Anchor(_) | Self::AnchorSize(_) => Err(()),
+            Self::Anchor(_) | Self::AnchorSize(_) | Self::SizeKeyword => Err(()),
         }
     }
 
@@ -1288,6 +1501,26 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                         anchor_positioning_resolver,
                     )
                 },
+                CalcNode::Progress { value, start, end } => {
+                    resolve_anchor_internal(
+                        value,
+                        side,
+                        position_property,
+                        anchor_positioning_resolver,
+                    )?;
+                    resolve_anchor_internal(
+                        start,
+                        side,
+                        position_property,
+                        anchor_positioning_resolver,
+                    )?;
+                    resolve_anchor_internal(
+                        end,
+                        side,
+                        position_property,
+                        anchor_positioning_resolver,
+                    )
+                },
                 CalcNode::Anchor(f) => {
                     *node =
                         anchor_positioning_resolver.resolve_anchor(f, side, position_property)?;
@@ -1298,6 +1531,7 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                         anchor_positioning_resolver.resolve_anchor_size(f, position_property)?;
                     Ok(())
                 },
+                CalcNode::SizeKeyword => Ok(()),
             }
         }
 
@@ -1377,6 +1611,15 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                 dividend.visit_depth_first_internal(f);
                 divisor.visit_depth_first_internal(f);
             },
+            Self::Progress {
+                ref mut value,
+                ref mut start,
+                ref mut end,
+            } => {
+                value.visit_depth_first_internal(f);
+                start.visit_depth_first_internal(f);
+                end.visit_depth_first_internal(f);
+            },
             Self::Sum(ref mut children) |
             Self::Product(ref mut children) |
             Self::MinMax(ref mut children, _) |
@@ -1391,7 +1634,7 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
             Self::Abs(ref mut value) | Self::Sign(ref mut value) => {
                 value.visit_depth_first_internal(f);
             },
-            Self::Leaf(..) | Self::Anchor(..) | Self::AnchorSize(..) => {},
+            Self::Leaf(..) | Self::Anchor(..) | Self::AnchorSize(..) | Self::SizeKeyword => {},
         }
         f(self);
     }
@@ -1749,6 +1992,23 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                 // a is removed.
                 children.dedup_by(|right, left| left.try_product_in_place(right));
 
+                // If more than one factor has a unit, they can still be multiplied together if
+                // they're all known.
+                let all_leaves = children.iter().all(|c| match c {
+                    Self::Invert(c) => matches!(**c, Self::Leaf(..)),
+                    c => matches!(*c, Self::Leaf(..)),
+                });
+                if children.len() > 1 && all_leaves {
+                    if let Ok(leaf) =
+                        Self::resolve_typed_product(&children, &mut |l| Ok(l.clone()), &mut |_| {
+                            Err(())
+                        })
+                    {
+                        *self = Self::Leaf(leaf);
+                        return;
+                    }
+                }
+
                 if children.len() == 1 {
                     // If only one children remains, lift it up, and carry on.
                     replace_self_with!(&mut children[0]);
@@ -1757,6 +2017,19 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                     *children_slot = children.into_boxed_slice().into();
                 }
             },
+            Self::Progress {
+                ref value,
+                ref start,
+                ref end,
+            } => {
+                let (Self::Leaf(value), Self::Leaf(start), Self::Leaf(end)) =
+                    (&**value, &**start, &**end)
+                else {
+                    return;
+                };
+                let progress = value_or_stop!(progress_of_leaves(value, start, end));
+                *self = Self::Leaf(L::new_number(progress));
+            },
             Self::Hypot(ref children) => {
                 let mut result = value_or_stop!(children[0].try_op(&children[0], Mul::mul));
 
@@ -1834,7 +2107,8 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                 if let Some(fallback) = f.fallback.as_mut() {
                     fallback.simplify_and_sort();
                 }
-            }
+            },
+            Self::SizeKeyword => {},
         }
     }
 
@@ -1881,6 +2155,10 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                 dest.write_str("hypot(")?;
                 true
             },
+            Self::Progress { .. } => {
+                dest.write_str("progress(")?;
+                true
+            },
             Self::Abs(_) => {
                 dest.write_str("abs(")?;
                 true
@@ -1918,7 +2196,7 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
                     true
                 },
             },
-            Self::Leaf(_) | Self::Anchor(_) | Self::AnchorSize(_) => match level {
+            Self::Leaf(_) | Self::Anchor(_) | Self::AnchorSize(_) | Self::SizeKeyword => match level {
                 ArgumentLevel::CalculationRoot => {
                     dest.write_str("calc(")?;
                     true
@@ -2026,9 +2304,21 @@ impl<L: CalcNodeLeaf> CalcNode<L> {
             Self::Abs(ref v) | Self::Sign(ref v) => {
                 v.to_css_impl(dest, ArgumentLevel::ArgumentRoot)?
             },
+            Self::Progress {
+                ref value,
+                ref start,
+                ref end,
+            } => {
+                value.to_css_impl(dest, ArgumentLevel::ArgumentRoot)?;
+                dest.write_str(", ")?;
+                start.to_css_impl(dest, ArgumentLevel::ArgumentRoot)?;
+                dest.write_str(", ")?;
+                end.to_css_impl(dest, ArgumentLevel::ArgumentRoot)?;
+            },
             Self::Leaf(ref l) => l.to_css(dest)?,
             Self::Anchor(ref f) => f.to_css(dest)?,
             Self::AnchorSize(ref f) => f.to_css(dest)?,
+            Self::SizeKeyword => dest.write_str("size")?,
         }
 
         if write_closing_paren {
@@ -2066,6 +2356,94 @@ impl<L: CalcNodeLeaf> ToCss for CalcNode<L> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::values::specified::calc::Leaf;
+    use crate::values::specified::length::{AbsoluteLength, FontRelativeLength, NoCalcLength};
+    use crate::values::specified::Angle;
+
+    fn px(value: f32) -> CalcNode<Leaf> {
+        CalcNode::Leaf(Leaf::Length(NoCalcLength::Absolute(AbsoluteLength::Px(value))))
+    }
+
+    fn em(value: f32) -> CalcNode<Leaf> {
+        CalcNode::Leaf(Leaf::Length(NoCalcLength::FontRelative(FontRelativeLength::Em(value))))
+    }
+
+    fn deg(value: f32) -> CalcNode<Leaf> {
+        CalcNode::Leaf(Leaf::Angle(Angle::from_degrees(value, false)))
+    }
+
+    fn number(value: f32) -> CalcNode<Leaf> {
+        CalcNode::Leaf(Leaf::Number(value))
+    }
+
+    fn invert(node: CalcNode<Leaf>) -> CalcNode<Leaf> {
+        CalcNode::Invert(Box::new(node))
+    }
+
+    fn product(children: Vec<CalcNode<Leaf>>) -> CalcNode<Leaf> {
+        CalcNode::Product(children.into())
+    }
+
+    fn product_unit(node: &CalcNode<Leaf>) -> Result<CalcUnits, ()> {
+        let mut product_type = ProductType::default();
+        node.multiply_type(&mut product_type, false)?;
+        product_type.unit()
+    }
+
+    fn resolve(node: &CalcNode<Leaf>, font_size: Option<f32>) -> Result<Leaf, ()> {
+        node.resolve_map(
+            |leaf| {
+                Ok(match (leaf, font_size) {
+                    (
+                        &Leaf::Length(NoCalcLength::FontRelative(FontRelativeLength::Em(v))),
+                        Some(size),
+                    ) => {
+                        Leaf::Length(NoCalcLength::Absolute(AbsoluteLength::Px(v * size)))
+                    },
+                    _ => leaf.clone(),
+                })
+            },
+            |_| Err(()),
+        )
+    }
+
+    #[test]
+    fn typed_product_of_lengths() {
+        // 2px * 3px / 4px
+        let node = product(vec![px(2.), px(3.), invert(px(4.))]);
+        assert!(product_unit(&node) == Ok(CalcUnits::LENGTH));
+        assert_eq!(
+            resolve(&node, None),
+            Ok(Leaf::Length(NoCalcLength::Absolute(AbsoluteLength::Px(1.5))))
+        );
+    }
+
+    #[test]
+    fn typed_product_of_length_ratio() {
+        // 1em / 1px
+        let node = product(vec![em(1.), invert(px(1.))]);
+        assert!(product_unit(&node) == Ok(CalcUnits::empty()));
+        // Font-relative lengths have no canonical value until the font size is known.
+        assert_eq!(resolve(&node, None), Err(()));
+        assert_eq!(resolve(&node, Some(16.)), Ok(Leaf::Number(16.)));
+    }
+
+    #[test]
+    fn invalid_typed_products() {
+        // 1px * 1px
+        let node = product(vec![px(1.), px(1.)]);
+        assert!(product_unit(&node).is_err());
+        assert_eq!(resolve(&node, None), Err(()));
+
+        // 1px * 1deg
+        let node = product(vec![px(1.), deg(1.)]);
+        assert!(product_unit(&node).is_err());
+        assert_eq!(resolve(&node, None), Err(()));
+
+        // 1 / 1px
+        let node = product(vec![number(1.), invert(px(1.))]);
+        assert!(product_unit(&node).is_err());
+    }
 
     #[test]
     fn can_sum_with_checks() {
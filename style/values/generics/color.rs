@@ -6,6 +6,7 @@
 
 use crate::color::{mix::ColorInterpolationMethod, AbsoluteColor, ColorFunction};
 use crate::values::specified::percentage::ToPercentage;
+use smallvec::SmallVec;
 use std::fmt::{self, Write};
 use style_traits::{CssWriter, ToCss};
 
@@ -22,6 +23,9 @@ pub enum GenericColor<Percentage> {
     CurrentColor,
     /// The color-mix() function.
     ColorMix(Box<GenericColorMix<Self, Percentage>>),
+    /// The contrast-color() function, when its argument can't be resolved
+    /// at computed-value time.
+    ContrastColor(Box<Self>),
 }
 
 /// Flags used to modify the calculation of a color mix result.
@@ -37,6 +41,27 @@ bitflags! {
     }
 }
 
+/// A single `<color> <percentage>?` item of a color-mix() function, with the
+/// percentage already filled in.
+#[derive(
+    Clone,
+    Debug,
+    MallocSizeOf,
+    PartialEq,
+    ToAnimatedValue,
+    ToComputedValue,
+    ToResolvedValue,
+    ToShmem,
+)]
+#[allow(missing_docs)]
+#[repr(C)]
+pub struct GenericColorMixItem<Color, Percentage> {
+    pub color: Color,
+    pub percentage: Percentage,
+}
+
+pub use self::GenericColorMixItem as ColorMixItem;
+
 /// A restricted version of the css `color-mix()` function, which only supports
 /// percentages.
 ///
@@ -55,10 +80,7 @@ bitflags! {
 #[repr(C)]
 pub struct GenericColorMix<Color, Percentage> {
     pub interpolation: ColorInterpolationMethod,
-    pub left: Color,
-    pub left_percentage: Percentage,
-    pub right: Color,
-    pub right_percentage: Percentage,
+    pub items: crate::OwnedSlice<GenericColorMixItem<Color, Percentage>>,
     pub flags: ColorMixFlags,
 }
 
@@ -86,42 +108,66 @@ impl<Color: ToCss, Percentage: ToCss + ToPercentage> ToCss for ColorMix<Color, P
             (1.0 - percent.to_percentage() - other.to_percentage()).abs() <= f32::EPSILON
         }
 
+        // With other than two colors, percentages are only omitted when they
+        // are all the implicit equal share.
+        let equal_share = 1.0 / self.items.len() as f32;
+        let omit_all = self.items.iter().all(|item| {
+            !item.percentage.is_calc() &&
+                (item.percentage.to_percentage() - equal_share).abs() <= f32::EPSILON
+        });
+
         dest.write_str("color-mix(")?;
         self.interpolation.to_css(dest)?;
-        dest.write_str(", ")?;
-        self.left.to_css(dest)?;
-        if !can_omit(&self.left_percentage, &self.right_percentage, true) {
-            dest.write_char(' ')?;
-            self.left_percentage.to_css(dest)?;
-        }
-        dest.write_str(", ")?;
-        self.right.to_css(dest)?;
-        if !can_omit(&self.right_percentage, &self.left_percentage, false) {
-            dest.write_char(' ')?;
-            self.right_percentage.to_css(dest)?;
+        for (index, item) in self.items.iter().enumerate() {
+            dest.write_str(", ")?;
+            item.color.to_css(dest)?;
+            let omit = match *self.items {
+                [ref left, ref right] => {
+                    let (percent, other) = if index == 0 {
+                        (&left.percentage, &right.percentage)
+                    } else {
+                        (&right.percentage, &left.percentage)
+                    };
+                    can_omit(percent, other, index == 0)
+                },
+                _ => omit_all,
+            };
+            if !omit {
+                dest.write_char(' ')?;
+                item.percentage.to_css(dest)?;
+            }
         }
         dest.write_char(')')
     }
 }
 
+impl<Color, Percentage: ToPercentage> ColorMix<Color, Percentage> {
+    /// Mix the colors after resolving each of them with the given function.
+    /// Returns None if any of the colors can't be resolved.
+    pub fn mix_with<F>(&self, mut resolve: F) -> Option<AbsoluteColor>
+    where
+        F: FnMut(&Color) -> Option<AbsoluteColor>,
+    {
+        let mut items = SmallVec::<[(AbsoluteColor, f32); 4]>::new();
+        for item in self.items.iter() {
+            items.push((resolve(&item.color)?, item.percentage.to_percentage()));
+        }
+        Some(crate::color::mix::mix_many(
+            self.interpolation,
+            &items,
+            self.flags,
+        ))
+    }
+}
+
 impl<Percentage> ColorMix<GenericColor<Percentage>, Percentage> {
-    /// Mix the colors so that we get a single color. If any of the 2 colors are
+    /// Mix the colors so that we get a single color. If any of the colors are
     /// not mixable (perhaps not absolute?), then return None.
     pub fn mix_to_absolute(&self) -> Option<AbsoluteColor>
     where
         Percentage: ToPercentage,
     {
-        let left = self.left.as_absolute()?;
-        let right = self.right.as_absolute()?;
-
-        Some(crate::color::mix::mix(
-            self.interpolation,
-            &left,
-            self.left_percentage.to_percentage(),
-            &right,
-            self.right_percentage.to_percentage(),
-            self.flags,
-        ))
+        self.mix_with(|color| color.as_absolute().copied())
     }
 }
 
@@ -5,6 +5,7 @@
 //! Generic types for CSS values related to length.
 
 use crate::parser::{Parse, ParserContext};
+use crate::values::animated::{Animate, Procedure};
 use crate::values::generics::box_::PositionProperty;
 use crate::values::generics::Optional;
 use crate::values::DashedIdent;
@@ -138,13 +139,11 @@ impl<LengthPercentage: Parse> Parse for LengthPercentageOrAuto<LengthPercentage>
 /// Note that it only accepts non-negative values.
 #[allow(missing_docs)]
 #[derive(
-    Animate,
     ComputeSquaredDistance,
     Clone,
     Debug,
     MallocSizeOf,
     PartialEq,
-    ToAnimatedValue,
     ToAnimatedZero,
     ToComputedValue,
     ToCss,
@@ -178,6 +177,8 @@ pub enum GenericSize<LengthPercent> {
         #[distance(field_bound)]
         Box<GenericAnchorSizeFunction<LengthPercent>>
     ),
+    #[animation(error)]
+    CalcSize(Box<GenericCalcSize<LengthPercent>>),
 }
 
 impl<LengthPercent> SpecifiedValueInfo for GenericSize<LengthPercent>
@@ -193,6 +194,9 @@ LengthPercent: SpecifiedValueInfo
         if static_prefs::pref!("layout.css.anchor-positioning.enabled") {
             f(&["anchor-size"]);
         }
+        if static_prefs::pref!("layout.css.calc-size.enabled") {
+            f(&["calc-size"]);
+        }
     }
 }
 
@@ -210,18 +214,55 @@ impl<LengthPercentage> Size<LengthPercentage> {
     pub fn is_auto(&self) -> bool {
         matches!(*self, Size::Auto)
     }
+
+    /// Returns the `calc-size()` basis this value would use if it was interpolated as an
+    /// intrinsic size keyword, if any.
+    pub fn calc_size_basis(&self) -> Option<CalcSizeBasis> {
+        Some(match *self {
+            Size::Auto => CalcSizeBasis::Auto,
+            Size::MinContent => CalcSizeBasis::MinContent,
+            Size::MaxContent => CalcSizeBasis::MaxContent,
+            Size::FitContent => CalcSizeBasis::FitContent,
+            Size::Stretch => CalcSizeBasis::Stretch,
+            _ => return None,
+        })
+    }
+}
+
+impl<LengthPercent> Animate for GenericSize<LengthPercent>
+where
+    LengthPercent: Animate + Clone,
+    Box<GenericAnchorSizeFunction<LengthPercent>>: Animate,
+{
+    fn animate(&self, other: &Self, procedure: Procedure) -> Result<Self, ()> {
+        Ok(match (self, other) {
+            (Self::LengthPercentage(a), Self::LengthPercentage(b)) => {
+                Self::LengthPercentage(a.animate(b, procedure)?)
+            },
+            (Self::Auto, Self::Auto) => Self::Auto,
+            (Self::AnchorSizeFunction(a), Self::AnchorSizeFunction(b)) => {
+                Self::AnchorSizeFunction(a.animate(b, procedure)?)
+            },
+            (Self::CalcSize(a), Self::CalcSize(b)) => Self::CalcSize(a.animate(b, procedure)?),
+            (Self::CalcSize(a), Self::LengthPercentage(b)) => Self::CalcSize(Box::new(
+                (**a).animate(&GenericCalcSize::any(b.clone()), procedure)?,
+            )),
+            (Self::LengthPercentage(a), Self::CalcSize(b)) => Self::CalcSize(Box::new(
+                GenericCalcSize::any(a.clone()).animate(&**b, procedure)?,
+            )),
+            _ => return Err(()),
+        })
+    }
 }
 
 /// A generic value for the `max-width` or `max-height` property.
 #[allow(missing_docs)]
 #[derive(
-    Animate,
     Clone,
     ComputeSquaredDistance,
     Debug,
     MallocSizeOf,
     PartialEq,
-    ToAnimatedValue,
     ToAnimatedZero,
     ToComputedValue,
     ToCss,
@@ -255,6 +296,8 @@ pub enum GenericMaxSize<LengthPercent> {
         #[distance(field_bound)]
         Box<GenericAnchorSizeFunction<LengthPercent>>
     ),
+    #[animation(error)]
+    CalcSize(Box<GenericCalcSize<LengthPercent>>),
 }
 
 impl<LP> SpecifiedValueInfo for GenericMaxSize<LP>
@@ -270,6 +313,9 @@ where
         if static_prefs::pref!("layout.css.anchor-positioning.enabled") {
             f(&["anchor-size"]);
         }
+        if static_prefs::pref!("layout.css.calc-size.enabled") {
+            f(&["calc-size"]);
+        }
     }
 }
 
@@ -281,6 +327,130 @@ impl<LengthPercentage> MaxSize<LengthPercentage> {
     pub fn none() -> Self {
         MaxSize::None
     }
+
+    /// Returns the `calc-size()` basis this value would use if it was interpolated as an
+    /// intrinsic size keyword, if any.
+    pub fn calc_size_basis(&self) -> Option<CalcSizeBasis> {
+        Some(match *self {
+            MaxSize::MinContent => CalcSizeBasis::MinContent,
+            MaxSize::MaxContent => CalcSizeBasis::MaxContent,
+            MaxSize::FitContent => CalcSizeBasis::FitContent,
+            MaxSize::Stretch => CalcSizeBasis::Stretch,
+            _ => return None,
+        })
+    }
+}
+
+impl<LengthPercent> Animate for GenericMaxSize<LengthPercent>
+where
+    LengthPercent: Animate + Clone,
+    Box<GenericAnchorSizeFunction<LengthPercent>>: Animate,
+{
+    fn animate(&self, other: &Self, procedure: Procedure) -> Result<Self, ()> {
+        Ok(match (self, other) {
+            (Self::LengthPercentage(a), Self::LengthPercentage(b)) => {
+                Self::LengthPercentage(a.animate(b, procedure)?)
+            },
+            (Self::None, Self::None) => Self::None,
+            (Self::AnchorSizeFunction(a), Self::AnchorSizeFunction(b)) => {
+                Self::AnchorSizeFunction(a.animate(b, procedure)?)
+            },
+            (Self::CalcSize(a), Self::CalcSize(b)) => Self::CalcSize(a.animate(b, procedure)?),
+            (Self::CalcSize(a), Self::LengthPercentage(b)) => Self::CalcSize(Box::new(
+                (**a).animate(&GenericCalcSize::any(b.clone()), procedure)?,
+            )),
+            (Self::LengthPercentage(a), Self::CalcSize(b)) => Self::CalcSize(Box::new(
+                GenericCalcSize::any(a.clone()).animate(&**b, procedure)?,
+            )),
+            _ => return Err(()),
+        })
+    }
+}
+
+/// The basis of a `calc-size()` function.
+///
+/// https://drafts.csswg.org/css-values-5/#calc-size
+#[allow(missing_docs)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    MallocSizeOf,
+    Parse,
+    PartialEq,
+    SpecifiedValueInfo,
+    ToAnimatedValue,
+    ToComputedValue,
+    ToCss,
+    ToResolvedValue,
+    ToShmem,
+)]
+#[repr(u8)]
+pub enum CalcSizeBasis {
+    Auto,
+    MinContent,
+    MaxContent,
+    FitContent,
+    Stretch,
+    /// The basis of a `calc-size()` whose basis was a `<length-percentage>`, or that was
+    /// interpolated from one. The `size` keyword can't be used in its calculation.
+    Any,
+}
+
+impl Animate for CalcSizeBasis {
+    fn animate(&self, other: &Self, _: Procedure) -> Result<Self, ()> {
+        // Two calc-size() values can only be interpolated if their bases match, though
+        // `any` can interpolate with every other basis.
+        match (*self, *other) {
+            (a, b) if a == b => Ok(a),
+            (CalcSizeBasis::Any, b) => Ok(b),
+            (a, CalcSizeBasis::Any) => Ok(a),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A `calc-size()` function, used to perform calculations on intrinsic sizes.
+///
+/// Specified values with a `<length-percentage>` basis, or nested `calc-size()` functions, are
+/// canonicalized at parse time, so the basis is always a keyword.
+///
+/// https://drafts.csswg.org/css-values-5/#calc-size
+#[derive(
+    Animate,
+    Clone,
+    Debug,
+    MallocSizeOf,
+    PartialEq,
+    SpecifiedValueInfo,
+    ToAnimatedValue,
+    ToComputedValue,
+    ToCss,
+    ToResolvedValue,
+    ToShmem,
+)]
+#[css(comma, function = "calc-size")]
+#[repr(C)]
+pub struct GenericCalcSize<LengthPercentage> {
+    /// The size the `size` keyword in the calculation refers to.
+    pub basis: CalcSizeBasis,
+    /// The calculation, in terms of the basis.
+    pub calculation: LengthPercentage,
+}
+
+pub use self::GenericCalcSize as CalcSize;
+
+impl<LengthPercentage> CalcSize<LengthPercentage> {
+    /// Returns a `calc-size(any, <calculation>)` value, used to interpolate a plain
+    /// `<length-percentage>` with a `calc-size()` value.
+    #[inline]
+    pub fn any(calculation: LengthPercentage) -> Self {
+        Self {
+            basis: CalcSizeBasis::Any,
+            calculation,
+        }
+    }
 }
 
 /// A generic `<length>` | `<number>` value for the `tab-size` property.
@@ -620,6 +790,9 @@ where
         if static_prefs::pref!("layout.css.anchor-positioning.enabled") {
             f(&["anchor-size"]);
         }
+        if static_prefs::pref!("layout.css.calc-size.enabled") {
+            f(&["calc-size"]);
+        }
     }
 }
 
@@ -648,6 +648,67 @@ impl Parse for AnimationTimeline {
     }
 }
 
+/// A value for the <timeline-range-name>.
+///
+/// https://drafts.csswg.org/scroll-animations-1/#typedef-timeline-range-name
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Eq,
+    Hash,
+    MallocSizeOf,
+    Parse,
+    PartialEq,
+    SpecifiedValueInfo,
+    ToComputedValue,
+    ToCss,
+    ToResolvedValue,
+    ToShmem,
+)]
+#[repr(u8)]
+pub enum TimelineRangeName {
+    /// The full range of the view progress timeline.
+    Cover,
+    /// The range during which the subject is fully contained by, or fully
+    /// covers, the scrollport.
+    Contain,
+    /// The range during which the subject enters the scrollport.
+    Entry,
+    /// The range during which the subject exits the scrollport.
+    Exit,
+    /// The range during which the subject crosses the end border edge.
+    EntryCrossing,
+    /// The range during which the subject crosses the start border edge.
+    ExitCrossing,
+}
+
+/// A specified value for the `animation-range-start` and `animation-range-end`
+/// properties.
+pub type AnimationRangeValue = generics::GenericAnimationRangeValue<LengthPercentage>;
+
+impl Parse for AnimationRangeValue {
+    fn parse<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        // normal | <length-percentage> | <timeline-range-name> <length-percentage>?
+        if input.try_parse(|i| i.expect_ident_matching("normal")).is_ok() {
+            return Ok(Self::Normal);
+        }
+
+        if let Ok(offset) = input.try_parse(|i| LengthPercentage::parse(context, i)) {
+            return Ok(Self::LengthPercentage(offset));
+        }
+
+        let name = TimelineRangeName::parse(input)?;
+        let offset = input
+            .try_parse(|i| LengthPercentage::parse(context, i))
+            .ok();
+        Ok(Self::Named(name, offset))
+    }
+}
+
 /// A specified value for the `view-timeline-inset` property.
 pub type ViewTimelineInset = generics::GenericViewTimelineInset<LengthPercentage>;
 
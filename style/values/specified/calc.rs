@@ -7,7 +7,10 @@
 //! [calc]: https://drafts.csswg.org/css-values/#calc-notation
 
 use crate::color::parsing::ChannelKeyword;
+use crate::dom::TreeCountingProvider;
 use crate::parser::{ParserContext, Parse};
+use crate::queries::feature_expression::QueryFeatureProgress;
+use crate::queries::FeatureType;
 use crate::values::generics::position::{AnchorSide, AnchorSideKeyword, GenericAnchorFunction};
 use crate::values::generics::length::GenericAnchorSizeFunction;
 use crate::values::generics::calc::{
@@ -16,8 +19,10 @@ use crate::values::generics::calc::{
 };
 use crate::values::specified::length::{AbsoluteLength, FontRelativeLength, NoCalcLength};
 use crate::values::specified::length::{ContainerRelativeLength, ViewportPercentageLength};
+use crate::values::computed;
 use crate::values::specified::{self, Angle, Resolution, Time};
 use crate::values::{serialize_number, serialize_percentage, CSSFloat, CSSInteger, DashedIdent};
+use crate::Atom;
 use cssparser::{CowRcStr, Parser, Token};
 use smallvec::SmallVec;
 use std::cmp;
@@ -70,6 +75,150 @@ pub enum MathFunction {
     Abs,
     /// `sign()`: https://drafts.csswg.org/css-values-4/#funcdef-sign
     Sign,
+    /// `random()`: https://drafts.csswg.org/css-values-5/#funcdef-random
+    Random,
+    /// `progress()`: https://drafts.csswg.org/css-values-5/#progress-func
+    Progress,
+}
+
+/// A tree-counting function, which resolves to an `<integer>` at computed-value time.
+///
+/// <https://drafts.csswg.org/css-values-5/#tree-counting>
+#[derive(Clone, Copy, Debug, MallocSizeOf, PartialEq, ToShmem)]
+pub enum TreeCountingFunction {
+    /// `sibling-index()`
+    SiblingIndex,
+    /// `sibling-count()`
+    SiblingCount,
+}
+
+impl ToCss for TreeCountingFunction {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        dest.write_str(match *self {
+            Self::SiblingIndex => "sibling-index()",
+            Self::SiblingCount => "sibling-count()",
+        })
+    }
+}
+
+impl TreeCountingFunction {
+    /// Returns the tree-counting function with the given name, if any.
+    pub fn from_function_name(name: &str) -> Option<Self> {
+        if !static_prefs::pref!("layout.css.tree-counting-functions.enabled") {
+            return None;
+        }
+        Some(match_ignore_ascii_case! { name,
+            "sibling-index" => Self::SiblingIndex,
+            "sibling-count" => Self::SiblingCount,
+            _ => return None,
+        })
+    }
+
+    /// Resolves the function for the element the provider represents.
+    pub fn resolve(&self, provider: &dyn TreeCountingProvider) -> u32 {
+        match *self {
+            Self::SiblingIndex => provider.sibling_index(),
+            Self::SiblingCount => provider.sibling_count(),
+        }
+    }
+
+    /// Resolves the function for the element being styled.
+    pub fn to_computed_value(&self, context: &computed::Context) -> u32 {
+        self.resolve(context)
+    }
+}
+
+/// Returns the type of the feature a `media-progress()` or `container-progress()` function with
+/// the given name queries, if any.
+pub fn query_progress_feature_type(name: &str) -> Option<FeatureType> {
+    if !static_prefs::pref!("layout.css.progress-functions.enabled") {
+        return None;
+    }
+    Some(match_ignore_ascii_case! { name,
+        "media-progress" => FeatureType::Media,
+        "container-progress" => FeatureType::Container,
+        _ => return None,
+    })
+}
+
+/// The caching options of a `random()` or `random-item()` function, which determine which
+/// functions share their base value.
+///
+/// <https://drafts.csswg.org/css-values-5/#typedef-random-value-sharing>
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+pub enum RandomValueSharing {
+    /// The base value is cached per element and property, or by the given name.
+    Cached {
+        /// The `<dashed-ident>` the base value is cached by, if any.
+        name: Option<Atom>,
+        /// Whether the base value is shared across all elements (`element-shared`).
+        element_shared: bool,
+    },
+    /// `fixed <number [0,1]>`: The base value is the given number.
+    Fixed(CSSFloat),
+}
+
+impl Default for RandomValueSharing {
+    fn default() -> Self {
+        Self::Cached {
+            name: None,
+            element_shared: false,
+        }
+    }
+}
+
+impl RandomValueSharing {
+    /// Parses `[ [ auto | <dashed-ident> ] || element-shared ] | fixed <number [0,1]>`.
+    pub fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i>> {
+        if input
+            .try_parse(|i| i.expect_ident_matching("fixed"))
+            .is_ok()
+        {
+            let location = input.current_source_location();
+            let value = input.expect_number()?;
+            if !(0.0..=1.0).contains(&value) {
+                return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+            }
+            // A base value of 1 would be out of the range of the function.
+            return Ok(Self::Fixed(value.min(1.0 - f32::EPSILON)));
+        }
+        let mut name = None;
+        let mut element_shared = false;
+        loop {
+            if !element_shared &&
+                input
+                    .try_parse(|i| i.expect_ident_matching("element-shared"))
+                    .is_ok()
+            {
+                element_shared = true;
+                continue;
+            }
+            if name.is_none() {
+                if input.try_parse(|i| i.expect_ident_matching("auto")).is_ok() {
+                    name = Some(None);
+                    continue;
+                }
+                if let Ok(ident) = input.try_parse(|i| {
+                    let location = i.current_source_location();
+                    DashedIdent::from_ident(location, i.expect_ident()?)
+                }) {
+                    name = Some(Some(ident.0));
+                    continue;
+                }
+            }
+            break;
+        }
+        if name.is_none() && !element_shared {
+            return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
+        Ok(Self::Cached {
+            name: name.flatten(),
+            element_shared,
+        })
+    }
 }
 
 /// A leaf node inside a `Calc` expression's AST.
@@ -90,6 +239,12 @@ pub enum Leaf {
     Percentage(CSSFloat),
     /// `<number>`
     Number(CSSFloat),
+    /// A tree-counting function, which is an `<integer>` that can only be
+    /// resolved at computed-value time.
+    TreeCountingFunction(TreeCountingFunction),
+    /// A `media-progress()` or `container-progress()` function, which is a
+    /// `<number>` that can only be resolved at computed-value time.
+    QueryProgress(Box<QueryFeatureProgress>),
 }
 
 impl Leaf {
@@ -114,6 +269,8 @@ impl ToCss for Leaf {
             Self::Angle(ref a) => a.to_css(dest),
             Self::Time(ref t) => t.to_css(dest),
             Self::ColorComponent(ref s) => s.to_css(dest),
+            Self::TreeCountingFunction(ref f) => f.to_css(dest),
+            Self::QueryProgress(ref f) => f.to_css(dest),
         }
     }
 }
@@ -177,6 +334,8 @@ bitflags! {
         const ANCHOR = 1 << 0;
         /// `anchor-size()` function.
         const ANCHOR_SIZE = 1 << 1;
+        /// The `size` keyword, in the calculation of a `calc-size()` function.
+        const SIZE_KEYWORD = 1 << 2;
     }
 }
 
@@ -219,7 +378,9 @@ impl generic::CalcNodeLeaf for Leaf {
             Leaf::Resolution(_) => CalcUnits::RESOLUTION,
             Leaf::ColorComponent(_) => CalcUnits::COLOR_COMPONENT,
             Leaf::Percentage(_) => CalcUnits::PERCENTAGE,
-            Leaf::Number(_) => CalcUnits::empty(),
+            Leaf::Number(_) | Leaf::TreeCountingFunction(_) | Leaf::QueryProgress(_) => {
+                CalcUnits::empty()
+            },
         }
     }
 
@@ -230,10 +391,21 @@ impl generic::CalcNodeLeaf for Leaf {
             Self::Resolution(ref r) => r.dppx(),
             Self::Angle(ref a) => a.degrees(),
             Self::Time(ref t) => t.seconds(),
-            Self::ColorComponent(_) => return None,
+            Self::ColorComponent(_) | Self::TreeCountingFunction(_) | Self::QueryProgress(_) => {
+                return None
+            },
         })
     }
 
+    fn canonical_value(&self) -> Option<f32> {
+        match *self {
+            Self::Length(ref l) => l.to_computed_pixel_length_without_context().ok(),
+            // Percentages can't be multiplied without knowing what they're relative to.
+            Self::Percentage(_) => None,
+            _ => self.unitless_value(),
+        }
+    }
+
     fn new_number(value: f32) -> Self {
         Self::Number(value)
     }
@@ -266,10 +438,25 @@ impl generic::CalcNodeLeaf for Leaf {
             (&Resolution(ref one), &Resolution(ref other)) => one.dppx().partial_cmp(&other.dppx()),
             (&Number(ref one), &Number(ref other)) => one.partial_cmp(other),
             (&ColorComponent(ref one), &ColorComponent(ref other)) => one.partial_cmp(other),
+            (TreeCountingFunction(one), TreeCountingFunction(other)) => {
+                if one == other {
+                    Some(cmp::Ordering::Equal)
+                } else {
+                    None
+                }
+            },
+            (QueryProgress(one), QueryProgress(other)) => {
+                if one == other {
+                    Some(cmp::Ordering::Equal)
+                } else {
+                    None
+                }
+            },
             _ => {
                 match *self {
                     Length(..) | Percentage(..) | Angle(..) | Time(..) | Number(..) |
-                    Resolution(..) | ColorComponent(..) => {},
+                    Resolution(..) | ColorComponent(..) | TreeCountingFunction(..) |
+                    QueryProgress(..) => {},
                 }
                 unsafe {
                     debug_unreachable!("Forgot a branch?");
@@ -285,7 +472,9 @@ impl generic::CalcNodeLeaf for Leaf {
             Leaf::Time(_) |
             Leaf::Resolution(_) |
             Leaf::Percentage(_) |
-            Leaf::ColorComponent(_) => None,
+            Leaf::ColorComponent(_) |
+            Leaf::TreeCountingFunction(_) |
+            Leaf::QueryProgress(_) => None,
             Leaf::Number(value) => Some(value),
         }
     }
@@ -346,6 +535,7 @@ impl generic::CalcNodeLeaf for Leaf {
                 NoCalcLength::ServoCharacterWidth(..) => unreachable!(),
             },
             Self::ColorComponent(..) => SortKey::ColorComponent,
+            Self::TreeCountingFunction(..) | Self::QueryProgress(..) => SortKey::Other,
         }
     }
 
@@ -383,10 +573,13 @@ impl generic::CalcNodeLeaf for Leaf {
             (&mut Length(ref mut one), &Length(ref other)) => {
                 *one = one.try_op(other, std::ops::Add::add)?;
             },
+            (&mut TreeCountingFunction(..), &TreeCountingFunction(..)) |
+            (&mut QueryProgress(..), &QueryProgress(..)) => return Err(()),
             _ => {
                 match *other {
                     Number(..) | Percentage(..) | Angle(..) | Time(..) | Resolution(..) |
-                    Length(..) | ColorComponent(..) => {},
+                    Length(..) | ColorComponent(..) | TreeCountingFunction(..) |
+                    QueryProgress(..) => {},
                 }
                 unsafe {
                     debug_unreachable!();
@@ -461,10 +654,13 @@ impl generic::CalcNodeLeaf for Leaf {
             (&Length(ref one), &Length(ref other)) => {
                 return Ok(Leaf::Length(one.try_op(other, op)?));
             },
+            (&TreeCountingFunction(..), &TreeCountingFunction(..)) |
+            (&QueryProgress(..), &QueryProgress(..)) => Err(()),
             _ => {
                 match *other {
                     Number(..) | Percentage(..) | Angle(..) | Time(..) | Length(..) |
-                    Resolution(..) | ColorComponent(..) => {},
+                    Resolution(..) | ColorComponent(..) | TreeCountingFunction(..) |
+                    QueryProgress(..) => {},
                 }
                 unsafe {
                     debug_unreachable!();
@@ -481,7 +677,9 @@ impl generic::CalcNodeLeaf for Leaf {
             Leaf::Resolution(one) => *one = specified::Resolution::from_dppx(op(one.dppx())),
             Leaf::Percentage(one) => *one = op(*one),
             Leaf::Number(one) => *one = op(*one),
-            Leaf::ColorComponent(..) => return Err(()),
+            Leaf::ColorComponent(..) | Leaf::TreeCountingFunction(..) | Leaf::QueryProgress(..) => {
+                return Err(())
+            },
         })
     }
 }
@@ -702,10 +900,48 @@ impl CalcNode {
                     true,
                 ))
             },
+            Token::Function(ref name)
+                if context.allows_computational_dependence() &&
+                    TreeCountingFunction::from_function_name(name).is_some() =>
+            {
+                let function = TreeCountingFunction::from_function_name(name).unwrap();
+                input.parse_nested_block(|input| input.expect_exhausted().map_err(Into::into))?;
+                // If we're parsing at computed-value time we can resolve the function right away,
+                // which allows it to be used where we resolve calc() at parse time.
+                let leaf = match context.tree_counting_provider {
+                    Some(provider) => Leaf::Number(function.resolve(provider) as CSSFloat),
+                    None => Leaf::TreeCountingFunction(function),
+                };
+                Ok(ParsedCalcNode::new(CalcNode::Leaf(leaf), false))
+            },
+            Token::Function(ref name)
+                if context.allows_computational_dependence() &&
+                    query_progress_feature_type(name).is_some() =>
+            {
+                let feature_type = query_progress_feature_type(name).unwrap();
+                let progress = input.parse_nested_block(|input| {
+                    QueryFeatureProgress::parse_arguments(context, input, feature_type)
+                })?;
+                // Like tree-counting functions, resolve right away if we're parsing at
+                // computed-value time.
+                let leaf = match context.query_progress_provider {
+                    Some(provider) => Leaf::Number(provider.query_progress(&progress)),
+                    None => Leaf::QueryProgress(Box::new(progress)),
+                };
+                Ok(ParsedCalcNode::new(CalcNode::Leaf(leaf), false))
+            },
             &Token::Function(ref name) => {
                 let function = CalcNode::math_function(context, name, location)?;
                 CalcNode::parse(context, input, function, allowed)
             },
+            Token::Ident(ref ident)
+                if allowed
+                    .additional_functions
+                    .intersects(AdditionalFunctions::SIZE_KEYWORD) &&
+                    ident.eq_ignore_ascii_case("size") =>
+            {
+                Ok(ParsedCalcNode::new(CalcNode::SizeKeyword, false))
+            },
             &Token::Ident(ref ident) => {
                 let leaf = match_ignore_ascii_case! { &**ident,
                     "e" => Leaf::Number(std::f32::consts::E),
@@ -1006,6 +1242,103 @@ impl CalcNode {
                         has_anchor_function: node.has_anchor_function,
                     })
                 },
+                MathFunction::Random => {
+                    // The base value can only be known at computed-value time, so fail to parse
+                    // otherwise, and let the declaration be resolved later.
+                    let provider = match context.random_provider {
+                        Some(provider) => provider,
+                        None => {
+                            return Err(
+                                input.new_custom_error(StyleParseErrorKind::UnspecifiedError)
+                            )
+                        },
+                    };
+                    let sharing = match input.try_parse(RandomValueSharing::parse) {
+                        Ok(sharing) => {
+                            input.expect_comma()?;
+                            sharing
+                        },
+                        Err(..) => RandomValueSharing::default(),
+                    };
+                    let min = Self::parse_argument(context, input, allowed)?;
+                    input.expect_comma()?;
+                    let max = Self::parse_argument(context, input, allowed)?;
+                    let step = input
+                        .try_parse(|input| {
+                            input.expect_comma()?;
+                            Self::parse_argument(context, input, allowed)
+                        })
+                        .ok();
+                    let base = provider.random_base_value(&sharing, "random");
+
+                    let mut has_anchor_function =
+                        min.has_anchor_function || max.has_anchor_function;
+                    // If max is less than min, the range is empty and the result is min.
+                    let mut negated_min = min.node.clone();
+                    negated_min.negate();
+                    let range = Self::Sum(
+                        vec![
+                            Self::MinMax(
+                                vec![min.node.clone(), max.node].into(),
+                                MinMaxOp::Max,
+                            ),
+                            negated_min,
+                        ]
+                        .into(),
+                    );
+                    let offset = match step {
+                        None => Self::Product(
+                            vec![Self::Leaf(Leaf::Number(base)), range].into(),
+                        ),
+                        Some(step) => {
+                            // Pick one of the multiples of step that fit in the range:
+                            // round(down, base * (round(down, range, step) + step), step).
+                            has_anchor_function |= step.has_anchor_function;
+                            let steps = Self::Sum(
+                                vec![
+                                    Self::Round {
+                                        strategy: RoundingStrategy::Down,
+                                        value: Box::new(range),
+                                        step: Box::new(step.node.clone()),
+                                    },
+                                    step.node.clone(),
+                                ]
+                                .into(),
+                            );
+                            Self::Round {
+                                strategy: RoundingStrategy::Down,
+                                value: Box::new(Self::Product(
+                                    vec![Self::Leaf(Leaf::Number(base)), steps].into(),
+                                )),
+                                step: Box::new(step.node),
+                            }
+                        },
+                    };
+                    Ok(ParsedCalcNode {
+                        node: Self::Sum(vec![min.node, offset].into()),
+                        has_anchor_function,
+                    })
+                },
+                MathFunction::Progress => {
+                    // Like for sign(), the arguments can be of any type (as long as it's the same
+                    // for all of them), except percentages if there's no basis.
+                    let allowed = allowed.new_including(CalcUnits::ALL - CalcUnits::PERCENTAGE);
+                    let value = Self::parse_argument(context, input, allowed)?;
+                    input.expect_comma()?;
+                    let start = Self::parse_argument(context, input, allowed)?;
+                    input.expect_comma()?;
+                    let end = Self::parse_argument(context, input, allowed)?;
+                    Ok(ParsedCalcNode {
+                        node: Self::Progress {
+                            value: Box::new(value.node),
+                            start: Box::new(start.node),
+                            end: Box::new(end.node),
+                        },
+                        has_anchor_function: value.has_anchor_function ||
+                            start.has_anchor_function ||
+                            end.has_anchor_function,
+                    })
+                },
                 MathFunction::Sign => {
                     // The sign of a percentage is dependent on the percentage basis, so if
                     // percentages aren't allowed (so there's no basis) we shouldn't allow them in
@@ -1116,8 +1449,16 @@ impl CalcNode {
         input: &mut Parser<'i, 't>,
         allowed: AllowParse,
     ) -> Result<ParsedCalcNode, ParseError<'i>> {
+        // Factors may have any unit, as long as the type of the whole product is allowed.
+        let factor_allowed = if static_prefs::pref!("layout.css.typed-arithmetic.enabled") {
+            allowed
+                .new_including(CalcUnits::ALL - CalcUnits::PERCENTAGE - CalcUnits::COLOR_COMPONENT)
+        } else {
+            allowed
+        };
         let mut product = SmallVec::<[CalcNode; 1]>::new();
-        let first = Self::parse_one(context, input, allowed)?;
+        let mut first = Self::parse_one(context, input, factor_allowed)?;
+        Self::compute_relative_lengths(context, allowed, &mut first.node);
         product.push(first.node);
         let mut has_anchor_function = first.has_anchor_function;
 
@@ -1125,7 +1466,8 @@ impl CalcNode {
             let start = input.state();
             match input.next() {
                 Ok(&Token::Delim('*')) => {
-                    let mut rhs = Self::parse_one(context, input, allowed)?;
+                    let mut rhs = Self::parse_one(context, input, factor_allowed)?;
+                    Self::compute_relative_lengths(context, allowed, &mut rhs.node);
                     has_anchor_function |= rhs.has_anchor_function;
 
                     // We can unwrap here, becuase we start the function by adding a node to
@@ -1140,7 +1482,8 @@ impl CalcNode {
                     }
                 },
                 Ok(&Token::Delim('/')) => {
-                    let rhs = Self::parse_one(context, input, allowed)?;
+                    let mut rhs = Self::parse_one(context, input, factor_allowed)?;
+                    Self::compute_relative_lengths(context, allowed, &mut rhs.node);
                     has_anchor_function |= rhs.has_anchor_function;
 
                     enum InPlaceDivisionResult {
@@ -1149,7 +1492,7 @@ impl CalcNode {
                         /// The right is not a number or could not be resolved, so the left is
                         /// unchanged.
                         Unchanged,
-                        /// The right was resolved, but was not a number, so the calculation is
+                        /// The left could not be divided by the right, so the calculation is
                         /// invalid.
                         Invalid,
                     }
@@ -1166,21 +1509,14 @@ impl CalcNode {
                                     }
                                     return InPlaceDivisionResult::Merged;
                                 }
-                            } else {
-                                // Color components are valid denominators, but they can't resolve
-                                // at parse time.
-                                return if resolved.unit().contains(CalcUnits::COLOR_COMPONENT) {
-                                    InPlaceDivisionResult::Unchanged
-                                } else {
-                                    InPlaceDivisionResult::Invalid
-                                };
                             }
                         }
                         InPlaceDivisionResult::Unchanged
                     }
 
-                    // The right hand side of a division *must* be a number, so if we can
-                    // already resolve it, then merge it with the last node on the product list.
+                    // If the right hand side of the division is a number we can already
+                    // resolve, then merge it with the last node on the product list. Otherwise
+                    // it may also be a dimension, which is checked with the type of the product.
                     // We can unwrap here, becuase we start the function by adding a node to
                     // the list.
                     match try_division_in_place(&mut product.last_mut().unwrap(), &rhs.node) {
@@ -1202,16 +1538,47 @@ impl CalcNode {
             }
         }
 
+        let node = if product.len() == 1 {
+            product.drain(..).next().unwrap()
+        } else {
+            Self::Product(product.into_boxed_slice().into())
+        };
+
+        // Products that don't have a valid type may still be part of a larger one, so only
+        // reject the ones that resolve to a unit that isn't allowed.
+        if let Ok(unit) = node.unit() {
+            if !unit.is_empty() && !allowed.includes(unit) {
+                return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+            }
+        }
+
         Ok(ParsedCalcNode {
-            node: if product.len() == 1 {
-                product.drain(..).next().unwrap()
-            } else {
-                Self::Product(product.into_boxed_slice().into())
-            },
+            node,
             has_anchor_function,
         })
     }
 
+    /// Computes the relative lengths of a factor of a product in a context that doesn't accept
+    /// lengths, where they can only be valid if divided by another length. This can only happen
+    /// when parsing at computed-value time, otherwise the product can't be resolved and the
+    /// declaration is deferred.
+    fn compute_relative_lengths(context: &ParserContext, allowed: AllowParse, node: &mut Self) {
+        if allowed.includes(CalcUnits::LENGTH) {
+            return;
+        }
+        let Some(provider) = context.relative_length_provider else {
+            return;
+        };
+        node.visit_depth_first(|node| {
+            if let Self::Leaf(Leaf::Length(ref mut length)) = *node {
+                if !matches!(*length, NoCalcLength::Absolute(..)) {
+                    let px = provider.compute_length(length).px();
+                    *length = NoCalcLength::Absolute(AbsoluteLength::Px(px));
+                }
+            }
+        });
+    }
+
     fn try_resolve<'i, 't, F>(
         input: &Parser<'i, 't>,
         closure: F,
@@ -1319,6 +1686,18 @@ impl CalcNode {
             },
         };
 
+        if matches!(function, MathFunction::Random) &&
+            !static_prefs::pref!("layout.css.random-function.enabled")
+        {
+            return Err(location.new_unexpected_token_error(Token::Function(name.clone())));
+        }
+
+        if matches!(function, MathFunction::Progress) &&
+            !static_prefs::pref!("layout.css.progress-functions.enabled")
+        {
+            return Err(location.new_unexpected_token_error(Token::Function(name.clone())));
+        }
+
         Ok(function)
     }
 
@@ -1356,6 +1735,26 @@ impl CalcNode {
             .map_err(|()| input.new_custom_error(StyleParseErrorKind::UnspecifiedError))
     }
 
+    /// Parses the calculation argument of a `calc-size()` function, which is a `<calc-sum>` that
+    /// may reference the basis through the `size` keyword if `allow_size_keyword` is true.
+    pub fn parse_calc_size_calculation<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+        allow_size_keyword: bool,
+    ) -> Result<CalcLengthPercentage, ParseError<'i>> {
+        let allowed = AllowParse {
+            units: CalcUnits::LENGTH_PERCENTAGE,
+            additional_functions: if allow_size_keyword {
+                AdditionalFunctions::SIZE_KEYWORD
+            } else {
+                AdditionalFunctions::empty()
+            },
+        };
+        Self::parse_argument(context, input, allowed)?
+            .into_length_or_percentage(AllowedNumericType::NonNegative)
+            .map_err(|()| input.new_custom_error(StyleParseErrorKind::UnspecifiedError))
+    }
+
     /// Convenience parsing function for percentages.
     pub fn parse_percentage<'i, 't>(
         context: &ParserContext,
@@ -6,12 +6,13 @@
 
 use super::AllowQuirks;
 use crate::color::mix::ColorInterpolationMethod;
+use crate::color::profile::ProfiledColor;
 use crate::color::{parsing, AbsoluteColor, ColorFunction, ColorSpace};
 use crate::media_queries::Device;
 use crate::parser::{Parse, ParserContext};
 use crate::values::computed::{Color as ComputedColor, Context, ToComputedValue};
 use crate::values::generics::color::{
-    ColorMixFlags, GenericCaretColor, GenericColorMix, GenericColorOrAuto,
+    ColorMixFlags, ColorMixItem, GenericCaretColor, GenericColorMix, GenericColorOrAuto,
 };
 use crate::values::specified::Percentage;
 use crate::values::{normalize, CustomIdent};
@@ -42,43 +43,48 @@ impl ColorMix {
                     .ok()
             };
 
-            let mut left_percentage = try_parse_percentage(input);
-
-            let left = Color::parse_internal(context, input, preserve_authored)?;
-            if left_percentage.is_none() {
-                left_percentage = try_parse_percentage(input);
-            }
-
-            input.expect_comma()?;
-
-            let mut right_percentage = try_parse_percentage(input);
-
-            let right = Color::parse_internal(context, input, preserve_authored)?;
-
-            if right_percentage.is_none() {
-                right_percentage = try_parse_percentage(input);
-            }
-
-            let right_percentage = right_percentage
-                .unwrap_or_else(|| Percentage::new(1.0 - left_percentage.map_or(0.5, |p| p.get())));
-
-            let left_percentage =
-                left_percentage.unwrap_or_else(|| Percentage::new(1.0 - right_percentage.get()));
+            let items = input.parse_comma_separated(|input| {
+                let mut percentage = try_parse_percentage(input);
+                let color = Color::parse_internal(context, input, preserve_authored)?;
+                if percentage.is_none() {
+                    percentage = try_parse_percentage(input);
+                }
+                Ok((color, percentage))
+            })?;
+
+            // https://drafts.csswg.org/css-color-5/#color-mix-percent-norm
+            //
+            // Omitted percentages share whatever is left up to 100% equally,
+            // or nothing if the specified ones already add up to more.
+            let omitted = items.iter().filter(|(_, p)| p.is_none()).count();
+            let specified_sum: f32 = items.iter().filter_map(|(_, p)| p.map(|p| p.get())).sum();
+            let omitted_percentage = if omitted == items.len() {
+                1.0 / omitted as f32
+            } else if omitted > 0 {
+                (1.0 - specified_sum).max(0.0) / omitted as f32
+            } else {
+                0.0
+            };
 
-            if left_percentage.get() + right_percentage.get() <= 0.0 {
+            if specified_sum + omitted_percentage * omitted as f32 <= 0.0 {
                 // If the percentages sum to zero, the function is invalid.
                 return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
             }
 
+            let items = items
+                .into_iter()
+                .map(|(color, percentage)| ColorMixItem {
+                    color,
+                    percentage: percentage.unwrap_or_else(|| Percentage::new(omitted_percentage)),
+                })
+                .collect::<Vec<_>>();
+
             // Pass RESULT_IN_MODERN_SYNTAX here, because the result of the color-mix() function
             // should always be in the modern color syntax to allow for out of gamut results and
             // to preserve floating point precision.
             Ok(ColorMix {
                 interpolation,
-                left,
-                left_percentage,
-                right,
-                right_percentage,
+                items: items.into(),
                 flags: ColorMixFlags::NORMALIZE_WEIGHTS | ColorMixFlags::RESULT_IN_MODERN_SYNTAX,
             })
         })
@@ -119,12 +125,15 @@ pub enum Color {
     /// Right now this is only the case for relative colors with `currentColor` as the origin.
     ColorFunction(Box<ColorFunction<Self>>),
     /// A system color.
-    #[cfg(feature = "gecko")]
     System(SystemColor),
     /// A color mix.
     ColorMix(Box<ColorMix>),
     /// A light-dark() color.
     LightDark(Box<LightDark>),
+    /// A contrast-color() color.
+    ContrastColor(Box<ContrastColor>),
+    /// A color in a custom color space, which needs the loaded profile to be resolved.
+    Profiled(Box<ProfiledColor>),
     /// Quirksmode-only rule for inheriting color from the body
     #[cfg(feature = "gecko")]
     InheritFromBodyQuirk,
@@ -172,6 +181,45 @@ impl LightDark {
     }
 }
 
+/// A contrast-color(<color>) function.
+///
+/// https://drafts.csswg.org/css-color-5/#contrast-color
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem, ToCss)]
+#[css(function)]
+pub struct ContrastColor {
+    /// The <color> to contrast with.
+    pub color: Color,
+}
+
+impl ContrastColor {
+    fn compute(&self, context: Option<&Context>) -> Option<ComputedColor> {
+        Some(match self.color.to_computed_color(context)? {
+            ComputedColor::Absolute(ref absolute) => {
+                ComputedColor::Absolute(absolute.contrast_color())
+            },
+            // Colors that depend on currentcolor are resolved at used-value time.
+            color => ComputedColor::ContrastColor(Box::new(color)),
+        })
+    }
+
+    fn parse<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+        preserve_authored: PreserveAuthored,
+    ) -> Result<Self, ParseError<'i>> {
+        let enabled = context.chrome_rules_enabled() ||
+            static_prefs::pref!("layout.css.contrast-color.enabled");
+        if !enabled {
+            return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
+        input.expect_function_matching("contrast-color")?;
+        input.parse_nested_block(|input| {
+            let color = Color::parse_internal(context, input, preserve_authored)?;
+            Ok(ContrastColor { color })
+        })
+    }
+}
+
 impl From<AbsoluteColor> for Color {
     #[inline]
     fn from(value: AbsoluteColor) -> Self {
@@ -438,6 +486,56 @@ impl SystemColor {
     }
 }
 
+/// System colors, as defined in CSS Color 4. The deprecated system colors are
+/// parsed as aliases of the color they're mapped to.
+///
+///   https://drafts.csswg.org/css-color/#css-system-colors
+///   https://drafts.csswg.org/css-color/#deprecated-system-colors
+#[allow(missing_docs)]
+#[cfg(feature = "servo")]
+#[derive(Clone, Copy, Debug, Eq, Hash, MallocSizeOf, Parse, PartialEq, ToCss, ToShmem)]
+#[repr(u8)]
+pub enum SystemColor {
+    Accentcolor,
+    Accentcolortext,
+    Activetext,
+    #[parse(aliases = "activeborder,inactiveborder,threeddarkshadow,threedhighlight,threedlightshadow,threedshadow,windowframe")]
+    Buttonborder,
+    #[parse(aliases = "buttonhighlight,buttonshadow,threedface")]
+    Buttonface,
+    Buttontext,
+    #[parse(aliases = "activecaption,appworkspace,background,inactivecaption,infobackground,menu,scrollbar,window")]
+    Canvas,
+    #[parse(aliases = "captiontext,infotext,menutext,windowtext")]
+    Canvastext,
+    Field,
+    Fieldtext,
+    #[parse(aliases = "inactivecaptiontext")]
+    Graytext,
+    Highlight,
+    Highlighttext,
+    Linktext,
+    Mark,
+    Marktext,
+    Selecteditem,
+    Selecteditemtext,
+    Visitedtext,
+}
+
+#[cfg(feature = "servo")]
+impl SystemColor {
+    #[inline]
+    fn compute(&self, cx: &Context) -> ComputedColor {
+        let color = cx.device().system_color(*self, cx.builder.color_scheme);
+        if cx.for_non_inherited_property {
+            cx.rule_cache_conditions
+                .borrow_mut()
+                .set_color_scheme_dependency(cx.builder.color_scheme);
+        }
+        ComputedColor::Absolute(color)
+    }
+}
+
 /// Whether to preserve authored colors during parsing. That's useful only if we
 /// plan to serialize the color back.
 #[derive(Copy, Clone)]
@@ -484,11 +582,8 @@ impl Color {
                 Ok(color)
             },
             Err(e) => {
-                #[cfg(feature = "gecko")]
-                {
-                    if let Ok(system) = input.try_parse(|i| SystemColor::parse(context, i)) {
-                        return Ok(Color::System(system));
-                    }
+                if let Ok(system) = input.try_parse(|i| <SystemColor as Parse>::parse(context, i)) {
+                    return Ok(Color::System(system));
                 }
 
                 if let Ok(mix) = input.try_parse(|i| ColorMix::parse(context, i, preserve_authored))
@@ -501,6 +596,12 @@ impl Color {
                     return Ok(Color::LightDark(Box::new(ld)));
                 }
 
+                if let Ok(cc) =
+                    input.try_parse(|i| ContrastColor::parse(context, i, preserve_authored))
+                {
+                    return Ok(Color::ContrastColor(Box::new(cc)));
+                }
+
                 match e.kind {
                     ParseErrorKind::Basic(BasicParseErrorKind::UnexpectedToken(t)) => {
                         Err(e.location.new_custom_error(StyleParseErrorKind::ValueError(
@@ -575,7 +676,8 @@ impl ToCss for Color {
             Color::ColorFunction(ref color_function) => color_function.to_css(dest),
             Color::ColorMix(ref mix) => mix.to_css(dest),
             Color::LightDark(ref ld) => ld.to_css(dest),
-            #[cfg(feature = "gecko")]
+            Color::ContrastColor(ref cc) => cc.to_css(dest),
+            Color::Profiled(ref color) => color.to_css(dest),
             Color::System(system) => system.to_css(dest),
             #[cfg(feature = "gecko")]
             Color::InheritFromBodyQuirk => Ok(()),
@@ -590,7 +692,6 @@ impl Color {
             #[cfg(feature = "gecko")]
             Self::InheritFromBodyQuirk => false,
             Self::CurrentColor => true,
-            #[cfg(feature = "gecko")]
             Self::System(..) => true,
             Self::Absolute(ref absolute) => allow_transparent && absolute.color.is_transparent(),
             Self::ColorFunction(ref color_function) => {
@@ -605,10 +706,14 @@ impl Color {
                 ld.light.honored_in_forced_colors_mode(allow_transparent) &&
                     ld.dark.honored_in_forced_colors_mode(allow_transparent)
             },
-            Self::ColorMix(ref mix) => {
-                mix.left.honored_in_forced_colors_mode(allow_transparent) &&
-                    mix.right.honored_in_forced_colors_mode(allow_transparent)
+            Self::ColorMix(ref mix) => mix
+                .items
+                .iter()
+                .all(|item| item.color.honored_in_forced_colors_mode(allow_transparent)),
+            Self::ContrastColor(ref cc) => {
+                cc.color.honored_in_forced_colors_mode(allow_transparent)
             },
+            Self::Profiled(..) => false,
         }
     }
 
@@ -638,22 +743,12 @@ impl Color {
     ///   https://drafts.csswg.org/css-color-5/#absolute-color
     /// Returns None if the specified color is not valid as an absolute color.
     pub fn resolve_to_absolute(&self) -> Option<AbsoluteColor> {
-        use crate::values::specified::percentage::ToPercentage;
-
         match self {
             Self::Absolute(c) => Some(c.color),
             Self::ColorFunction(ref color_function) => color_function.resolve_to_absolute().ok(),
-            Self::ColorMix(ref mix) => {
-                let left = mix.left.resolve_to_absolute()?;
-                let right = mix.right.resolve_to_absolute()?;
-                Some(crate::color::mix::mix(
-                    mix.interpolation,
-                    &left,
-                    mix.left_percentage.to_percentage(),
-                    &right,
-                    mix.right_percentage.to_percentage(),
-                    mix.flags,
-                ))
+            Self::ColorMix(ref mix) => mix.mix_with(|color| color.resolve_to_absolute()),
+            Self::ContrastColor(ref cc) => {
+                cc.color.resolve_to_absolute().map(|c| c.contrast_color())
             },
             _ => None,
         }
@@ -802,19 +897,27 @@ impl Color {
             Color::ColorMix(ref mix) => {
                 use crate::values::computed::percentage::Percentage;
 
-                let left = mix.left.to_computed_color(context)?;
-                let right = mix.right.to_computed_color(context)?;
+                let mut items = Vec::with_capacity(mix.items.len());
+                for item in mix.items.iter() {
+                    items.push(ColorMixItem {
+                        color: item.color.to_computed_color(context)?,
+                        percentage: Percentage(item.percentage.get()),
+                    });
+                }
 
                 ComputedColor::from_color_mix(GenericColorMix {
                     interpolation: mix.interpolation,
-                    left,
-                    left_percentage: Percentage(mix.left_percentage.get()),
-                    right,
-                    right_percentage: Percentage(mix.right_percentage.get()),
+                    items: items.into(),
                     flags: mix.flags,
                 })
             },
-            #[cfg(feature = "gecko")]
+            Color::ContrastColor(ref cc) => return cc.compute(context),
+            Color::Profiled(ref color) => {
+                let profile = context?.device().color_profile(&color.profile.0);
+                let mut color = color.resolve(profile);
+                adjust_absolute_color!(color);
+                ComputedColor::Absolute(color)
+            },
             Color::System(system) => system.compute(context?),
             #[cfg(feature = "gecko")]
             Color::InheritFromBodyQuirk => {
@@ -849,6 +952,11 @@ impl ToComputedValue for Color {
             ComputedColor::ColorMix(ref mix) => {
                 Color::ColorMix(Box::new(ToComputedValue::from_computed_value(&**mix)))
             },
+            ComputedColor::ContrastColor(ref color) => {
+                Color::ContrastColor(Box::new(ContrastColor {
+                    color: Self::from_computed_value(&**color),
+                }))
+            },
         }
     }
 }
@@ -877,6 +985,7 @@ impl SpecifiedValueInfo for Color {
             "oklch",
             "color-mix",
             "light-dark",
+            "contrast-color",
         ]);
     }
 }
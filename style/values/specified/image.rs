@@ -83,10 +83,11 @@ impl Color {
                 if mix.flags.contains(ColorMixFlags::RESULT_IN_MODERN_SYNTAX) {
                     true
                 } else {
-                    mix.left.has_modern_syntax() || mix.right.has_modern_syntax()
+                    mix.items.iter().any(|item| item.color.has_modern_syntax())
                 }
             },
             Self::LightDark(ld) => ld.light.has_modern_syntax() || ld.dark.has_modern_syntax(),
+            Self::Profiled(..) => true,
 
             // The default is that this color doesn't have any modern syntax.
             _ => false,
@@ -418,7 +419,12 @@ impl ImageSetItem {
         cors_mode: CorsMode,
         flags: ParseImageFlags,
     ) -> Result<Self, ParseError<'i>> {
-        let image = match input.try_parse(|i| i.expect_url_or_string()) {
+        let image = match input.try_parse(|i| -> Result<_, ParseError<'i>> {
+            if !context.allows_urls() {
+                return Err(i.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+            }
+            Ok(i.expect_url_or_string()?)
+        }) {
             Ok(url) => Image::Url(SpecifiedUrl::parse_from_string(
                 url.as_ref().into(),
                 context,
@@ -1280,7 +1286,7 @@ impl PaintWorklet {
         let arguments = input
             .try_parse(|input| {
                 input.expect_comma()?;
-                input.parse_comma_separated(|input| SpecifiedValue::parse(input, &context.url_data))
+                input.parse_comma_separated(|input| SpecifiedValue::parse(input, context))
             })
             .unwrap_or_default();
         Ok(Self { name, arguments })
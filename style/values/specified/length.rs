@@ -15,8 +15,8 @@ use crate::parser::{Parse, ParserContext};
 use crate::values::computed::{self, CSSPixelLength, Context};
 use crate::values::generics::length as generics;
 use crate::values::generics::length::{
-    GenericAnchorSizeFunction, GenericLengthOrNumber, GenericLengthPercentageOrNormal,
-    GenericMargin, GenericMaxSize, GenericSize,
+    CalcSizeBasis, GenericAnchorSizeFunction, GenericCalcSize, GenericLengthOrNumber,
+    GenericLengthPercentageOrNormal, GenericMargin, GenericMaxSize, GenericSize,
 };
 use crate::values::generics::NonNegative;
 use crate::values::specified::calc::{self, AllowAnchorPositioningFunctions, CalcNode};
@@ -1165,6 +1165,19 @@ impl NoCalcLength {
         })
     }
 
+    /// Returns whether the given unit is a length unit that can only be resolved at
+    /// computed-value time.
+    pub fn is_relative_unit(unit: &str) -> bool {
+        match_ignore_ascii_case! { unit,
+            "em" | "ex" | "ch" | "cap" | "ic" | "rem" | "lh" | "rlh" |
+            "vw" | "svw" | "lvw" | "dvw" | "vh" | "svh" | "lvh" | "dvh" |
+            "vmin" | "svmin" | "lvmin" | "dvmin" | "vmax" | "svmax" | "lvmax" | "dvmax" |
+            "vb" | "svb" | "lvb" | "dvb" | "vi" | "svi" | "lvi" | "dvi" |
+            "cqw" | "cqh" | "cqi" | "cqb" | "cqmin" | "cqmax" => true,
+            _ => false,
+        }
+    }
+
     pub(crate) fn try_op<O>(&self, other: &Self, op: O) -> Result<Self, ()>
     where
         O: Fn(f32, f32) -> f32,
@@ -2068,6 +2081,13 @@ impl Size {
         #[cfg(feature = "gecko")]
         parse_fit_content_function!(Size, input, context, allow_quirks);
 
+        if let Ok(calc_size) = input.try_parse(|i| CalcSize::parse(context, i)) {
+            return Ok(match calc_size.basis {
+                CalcSizeBasis::Any => Self::LengthPercentage(calc_size.calculation),
+                _ => Self::CalcSize(Box::new(calc_size)),
+            });
+        }
+
         if let Ok(length) =
             input.try_parse(|i| NonNegativeLengthPercentage::parse_non_negative_with_anchor_size(context, i, allow_quirks))
         {
@@ -2108,6 +2128,13 @@ impl MaxSize {
         #[cfg(feature = "gecko")]
         parse_fit_content_function!(MaxSize, input, context, allow_quirks);
 
+        if let Ok(calc_size) = input.try_parse(|i| CalcSize::parse(context, i)) {
+            return Ok(match calc_size.basis {
+                CalcSizeBasis::Any => Self::LengthPercentage(calc_size.calculation),
+                _ => Self::CalcSize(Box::new(calc_size)),
+            });
+        }
+
         if let Ok(length) =
             input.try_parse(|i| NonNegativeLengthPercentage::parse_non_negative_with_anchor_size(context, i, allow_quirks))
         {
@@ -2119,6 +2146,55 @@ impl MaxSize {
     }
 }
 
+/// A specified value for the `calc-size()` function.
+pub type CalcSize = GenericCalcSize<NonNegativeLengthPercentage>;
+
+impl CalcSize {
+    /// Parses a `calc-size()` function.
+    ///
+    /// If the basis is a `<length-percentage>`, it's substituted into the calculation right away,
+    /// and the result has an `any` basis. Likewise, a nested `calc-size()` basis is substituted
+    /// into the calculation, and its basis is used instead.
+    fn parse<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        if !static_prefs::pref!("layout.css.calc-size.enabled") {
+            return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
+        input.expect_function_matching("calc-size")?;
+        input.parse_nested_block(|input| {
+            let (basis, substitution) =
+                if let Ok(basis) = input.try_parse(CalcSizeBasis::parse) {
+                    (basis, None)
+                } else if let Ok(inner) = input.try_parse(|i| Self::parse(context, i)) {
+                    (inner.basis, Some(inner.calculation.0))
+                } else {
+                    let length = LengthPercentage::parse_non_negative(context, input)?;
+                    (CalcSizeBasis::Any, Some(length))
+                };
+            input.expect_comma()?;
+            let allow_size_keyword = basis != CalcSizeBasis::Any || substitution.is_some();
+            let mut calculation =
+                CalcNode::parse_calc_size_calculation(context, input, allow_size_keyword)?;
+            if let Some(substitution) = substitution {
+                let (substitution, has_anchor_function) = substitution.to_calc_node();
+                calculation.node.visit_depth_first(|node| {
+                    if matches!(*node, CalcNode::SizeKeyword) {
+                        *node = substitution.clone();
+                    }
+                });
+                calculation.node.simplify_and_sort();
+                calculation.has_anchor_function |= has_anchor_function;
+            }
+            Ok(Self {
+                basis,
+                calculation: NonNegative(LengthPercentage::Calc(Box::new(calculation))),
+            })
+        })
+    }
+}
+
 /// A specified non-negative `<length>` | `<number>`.
 pub type NonNegativeLengthOrNumber = GenericLengthOrNumber<NonNegativeLength, NonNegativeNumber>;
 
@@ -154,6 +154,34 @@ impl Parse for InitialLetter {
     }
 }
 
+/// Values for the `block-ellipsis` property.
+///
+/// https://drafts.csswg.org/css-overflow-4/#block-ellipsis
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    MallocSizeOf,
+    PartialEq,
+    Parse,
+    SpecifiedValueInfo,
+    ToComputedValue,
+    ToCss,
+    ToResolvedValue,
+    ToShmem,
+)]
+#[repr(C, u8)]
+pub enum BlockEllipsis {
+    /// No ellipsis is inserted.
+    None,
+    /// Render an ellipsis, typically `…`, at the end of the last line box
+    /// before a clamp point.
+    Auto,
+    /// Render a given string at the end of the last line box before a clamp
+    /// point.
+    String(crate::values::AtomString),
+}
+
 /// A generic value for the `text-overflow` property.
 #[derive(
     Clone,
@@ -18,6 +18,36 @@ macro_rules! pref {
     ("layout.css.system-ui.enabled") => {
         true
     };
+    ("layout.css.attr.enabled") => {
+        true
+    };
+    ("layout.css.if-function.enabled") => {
+        true
+    };
+    ("layout.css.tree-counting-functions.enabled") => {
+        true
+    };
+    ("layout.css.calc-size.enabled") => {
+        true
+    };
+    ("layout.css.random-function.enabled") => {
+        true
+    };
+    ("layout.css.progress-functions.enabled") => {
+        true
+    };
+    ("layout.css.typed-arithmetic.enabled") => {
+        true
+    };
+    ("layout.css.contrast-color.enabled") => {
+        true
+    };
+    ("layout.css.color-profile.enabled") => {
+        true
+    };
+    ("layout.css.light-dark.enabled") => {
+        true
+    };
     ("layout.css.basic-shape-rect.enabled") => {
         true
     };
@@ -33,6 +63,12 @@ macro_rules! pref {
     ("layout.css.transition-behavior.enabled") => {
         true
     };
+    ("layout.css.starting-style-at-rules.enabled") => {
+        true
+    };
+    ("layout.css.properties-and-values.enabled") => {
+        true
+    };
     ($string:literal) => {
         false
     };
@@ -273,6 +273,9 @@ bitflags! {
         /// independent.
         /// <https://drafts.css-houdini.org/css-properties-values-api-1/#ref-for-computationally-independent%E2%91%A0>
         const DISALLOW_COMPUTATIONALLY_DEPENDENT = 1 << 2;
+        /// When reparsing a value substituted from `attr()`, URLs are not allowed.
+        /// <https://drafts.csswg.org/css-values-5/#attr-security>
+        const DISALLOW_URLS = 1 << 3;
     }
 }
 
@@ -294,6 +297,12 @@ impl ParsingMode {
     pub fn allows_computational_dependence(&self) -> bool {
         !self.intersects(ParsingMode::DISALLOW_COMPUTATIONALLY_DEPENDENT)
     }
+
+    /// Whether the parsing mode allows URLs.
+    #[inline]
+    pub fn allows_urls(&self) -> bool {
+        !self.intersects(ParsingMode::DISALLOW_URLS)
+    }
 }
 
 #[cfg(feature = "servo")]